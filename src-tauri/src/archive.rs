@@ -0,0 +1,367 @@
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{Cursor, Read, Write};
+use std::path::Path;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+use crate::db::{image_bytes, ClipboardItem, Collection, DbState, HistoryFilter, Tag};
+
+/// Bumped whenever the bundle layout changes in a way older readers can't handle.
+pub const BUNDLE_VERSION: u32 = 1;
+/// Name of the JSON bundle inside a ZIP export.
+const ZIP_BUNDLE: &str = "history.json";
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+/// Largest `history.json` and image file read from a ZIP, so a corrupt or
+/// hostile archive can't exhaust memory.
+const ZIP_BUNDLE_LIMIT: u64 = 512 * 1024 * 1024;
+const ZIP_IMAGE_LIMIT: u64 = 64 * 1024 * 1024;
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Json,
+    /// The JSON bundle plus every image as a PNG file.
+    Zip,
+    /// Read-only report; can't be imported back.
+    Markdown,
+    /// Read-only report; can't be imported back.
+    Html,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportMode {
+    /// Adds items that aren't in the history yet.
+    Merge,
+    /// Moves the current history to the trash first (undoable).
+    Replace,
+}
+
+/// Portable copy of (part of) the history.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Bundle {
+    pub version: u32,
+    pub exported_at: i64,
+    pub items: Vec<ClipboardItem>,
+    #[serde(default)]
+    pub tags: Vec<Tag>,
+    #[serde(default)]
+    pub collections: Vec<Collection>,
+    /// Image item id -> file inside the ZIP. Those items have empty content in
+    /// the JSON; it is filled back in from the file on import.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub images: BTreeMap<String, String>,
+}
+
+/// What `import_history` did, for display after an import.
+#[derive(Serialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportSummary {
+    pub added: usize,
+    /// Items already in the history (same id, or same type and content).
+    pub skipped_duplicates: usize,
+    /// Items that could not be read, e.g. an image missing from the ZIP.
+    pub skipped_invalid: usize,
+    /// Items moved to the trash by `ImportMode::Replace`.
+    pub replaced: usize,
+    /// Items dropped afterwards by the history limit.
+    pub pruned: usize,
+    pub tags_created: usize,
+    pub collections_created: usize,
+}
+
+/// Writes the items matching `filter` to `path`. Returns how many were exported.
+pub fn export_history(
+    state: &DbState,
+    path: &Path,
+    format: ExportFormat,
+    filter: &HistoryFilter,
+) -> Result<usize, String> {
+    let items = state.get_history_filtered(filter);
+    let count = items.len();
+    let bundle = Bundle {
+        version: BUNDLE_VERSION,
+        exported_at: chrono::Utc::now().timestamp_millis(),
+        items,
+        tags: state.get_tags(),
+        collections: state.get_collections(),
+        images: BTreeMap::new(),
+    };
+    write_bundle(bundle, path, format)?;
+    Ok(count)
+}
+
+fn write_bundle(bundle: Bundle, path: &Path, format: ExportFormat) -> Result<(), String> {
+    match format {
+        ExportFormat::Json => {
+            let json = serde_json::to_string_pretty(&bundle).map_err(|e| e.to_string())?;
+            fs::write(path, json).map_err(|e| e.to_string())?;
+        }
+        ExportFormat::Zip => write_zip(bundle, path)?,
+        ExportFormat::Markdown => fs::write(path, markdown_report(&bundle)).map_err(|e| e.to_string())?,
+        ExportFormat::Html => fs::write(path, html_report(&bundle)).map_err(|e| e.to_string())?,
+    }
+    Ok(())
+}
+
+fn write_zip(mut bundle: Bundle, path: &Path) -> Result<(), String> {
+    let file = File::create(path).map_err(|e| e.to_string())?;
+    let mut zip = ZipWriter::new(file);
+    // PNGs are already compressed.
+    let stored = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+    let deflated = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    for item in bundle.items.iter_mut().filter(|i| i.r#type == "image") {
+        let png = image_bytes(&item.content)
+            .ok_or_else(|| format!("Image {} is not valid base64", item.id))?;
+        let name = format!("images/{}.png", item.id);
+        zip.start_file(name.as_str(), stored).map_err(|e| e.to_string())?;
+        zip.write_all(&png).map_err(|e| e.to_string())?;
+        item.content.clear();
+        bundle.images.insert(item.id.clone(), name);
+    }
+
+    let json = serde_json::to_vec_pretty(&bundle).map_err(|e| e.to_string())?;
+    zip.start_file(ZIP_BUNDLE, deflated).map_err(|e| e.to_string())?;
+    zip.write_all(&json).map_err(|e| e.to_string())?;
+    zip.finish().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Reads a JSON or ZIP export. Image items whose file is missing from the ZIP
+/// keep empty content and are skipped by the import.
+pub fn read_bundle(path: &Path) -> Result<Bundle, String> {
+    let data = fs::read(path).map_err(|e| e.to_string())?;
+    let bundle = if data.starts_with(ZIP_MAGIC) {
+        read_zip(data)?
+    } else {
+        serde_json::from_slice::<Bundle>(&data)
+            .map_err(|e| format!("Not a clipboard history export: {}", e))?
+    };
+    if bundle.version > BUNDLE_VERSION {
+        return Err(format!(
+            "This export was made by a newer version (format {}); please update first",
+            bundle.version
+        ));
+    }
+    Ok(bundle)
+}
+
+fn read_zip(data: Vec<u8>) -> Result<Bundle, String> {
+    let mut zip = ZipArchive::new(Cursor::new(data)).map_err(|e| e.to_string())?;
+    let mut bundle: Bundle = {
+        let entry = zip
+            .by_name(ZIP_BUNDLE)
+            .map_err(|_| format!("The archive has no {}", ZIP_BUNDLE))?;
+        serde_json::from_reader(entry.take(ZIP_BUNDLE_LIMIT))
+            .map_err(|e| format!("Not a clipboard history export: {}", e))?
+    };
+
+    let images = std::mem::take(&mut bundle.images);
+    for item in bundle.items.iter_mut() {
+        let Some(name) = images.get(&item.id) else { continue };
+        let mut png = Vec::new();
+        match zip.by_name(name) {
+            // One byte over the limit tells a file that is too large from one that fits.
+            Ok(entry) => entry.take(ZIP_IMAGE_LIMIT + 1).read_to_end(&mut png).map_err(|e| e.to_string())?,
+            Err(_) => continue,
+        };
+        if png.len() as u64 > ZIP_IMAGE_LIMIT {
+            continue;
+        }
+        item.content = base64::engine::general_purpose::STANDARD.encode(&png);
+    }
+    Ok(bundle)
+}
+
+fn format_time(timestamp: i64) -> String {
+    chrono::DateTime::from_timestamp_millis(timestamp)
+        .map(|t| t.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_default()
+}
+
+/// Tag and collection names for the report heading of `item`.
+fn label_names(bundle: &Bundle, item: &ClipboardItem) -> Vec<String> {
+    let mut names: Vec<String> = item
+        .tags
+        .iter()
+        .filter_map(|id| bundle.tags.iter().find(|t| t.id == *id))
+        .map(|t| format!("#{}", t.name))
+        .collect();
+    if let Some(c) = item
+        .collection_id
+        .as_ref()
+        .and_then(|id| bundle.collections.iter().find(|c| c.id == *id))
+    {
+        names.insert(0, format!("[{}]", c.name));
+    }
+    if item.is_pinned {
+        names.insert(0, "pinned".to_string());
+    }
+    names
+}
+
+fn markdown_report(bundle: &Bundle) -> String {
+    let mut out = format!(
+        "# Clipboard history\n\nExported {} - {} items\n",
+        format_time(bundle.exported_at),
+        bundle.items.len()
+    );
+    for item in &bundle.items {
+        out.push_str(&format!("\n## {}", format_time(item.timestamp)));
+        let labels = label_names(bundle, item);
+        if !labels.is_empty() {
+            out.push_str(&format!(" ({})", labels.join(", ")));
+        }
+        out.push_str("\n\n");
+        if item.r#type == "image" {
+            out.push_str(&format!("![image](data:image/png;base64,{})\n", item.content));
+        } else {
+            // The fence must be longer than any backtick run in the content.
+            let longest = item
+                .content
+                .split(|c| c != '`')
+                .map(str::len)
+                .max()
+                .unwrap_or(0);
+            let fence = "`".repeat(longest.max(2) + 1);
+            out.push_str(&format!("{}\n{}\n{}\n", fence, item.content, fence));
+        }
+    }
+    out
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn html_report(bundle: &Bundle) -> String {
+    let mut out = String::from(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Clipboard history</title>\n\
+         <style>body{font-family:sans-serif;max-width:60em;margin:auto}\
+         pre{background:#f4f4f4;padding:.5em;white-space:pre-wrap;word-break:break-word}\
+         img{max-width:100%}h2{font-size:1em;color:#555}</style>\n</head>\n<body>\n",
+    );
+    out.push_str(&format!(
+        "<h1>Clipboard history</h1>\n<p>Exported {} - {} items</p>\n",
+        format_time(bundle.exported_at),
+        bundle.items.len()
+    ));
+    for item in &bundle.items {
+        out.push_str(&format!("<h2>{}", format_time(item.timestamp)));
+        let labels = label_names(bundle, item);
+        if !labels.is_empty() {
+            out.push_str(&format!(" ({})", escape_html(&labels.join(", "))));
+        }
+        out.push_str("</h2>\n");
+        if item.r#type == "image" {
+            out.push_str(&format!("<img src=\"data:image/png;base64,{}\" alt=\"image\">\n", item.content));
+        } else {
+            out.push_str(&format!("<pre>{}</pre>\n", escape_html(&item.content)));
+        }
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    /// A 2x1 PNG, base64 encoded.
+    fn png() -> String {
+        let image = image::RgbaImage::from_raw(2, 1, vec![255, 0, 0, 255, 0, 0, 255, 128]).unwrap();
+        let mut bytes = Vec::new();
+        image.write_to(&mut Cursor::new(&mut bytes), image::ImageFormat::Png).unwrap();
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    }
+
+    fn item(id: &str, r#type: &str, content: &str) -> ClipboardItem {
+        ClipboardItem {
+            id: id.to_string(),
+            r#type: r#type.to_string(),
+            content: content.to_string(),
+            timestamp: 1_700_000_000_000,
+            tags: vec!["t".to_string()],
+            ..Default::default()
+        }
+    }
+
+    fn bundle(items: Vec<ClipboardItem>) -> Bundle {
+        Bundle {
+            version: BUNDLE_VERSION,
+            exported_at: 1,
+            items,
+            tags: vec![Tag { id: "t".to_string(), name: "Work".to_string() }],
+            ..Default::default()
+        }
+    }
+
+    fn temp_path(extension: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("clipboard-export-{}.{}", uuid::Uuid::new_v4(), extension))
+    }
+
+    fn round_trip(bundle: Bundle, format: ExportFormat) -> Bundle {
+        let path = temp_path(if format == ExportFormat::Zip { "zip" } else { "json" });
+        write_bundle(bundle, &path, format).unwrap();
+        let read = read_bundle(&path);
+        let _ = fs::remove_file(&path);
+        read.unwrap()
+    }
+
+    #[test]
+    fn json_round_trip_keeps_items_and_labels() {
+        let read = round_trip(bundle(vec![item("a", "text", "hello"), item("b", "image", &png())]), ExportFormat::Json);
+        assert_eq!(read.items.len(), 2);
+        assert_eq!(read.items[0].content, "hello");
+        assert_eq!(read.items[0].tags, ["t"]);
+        assert_eq!(read.items[1].content, png());
+        assert_eq!(read.tags[0].name, "Work");
+        assert!(read.images.is_empty());
+    }
+
+    #[test]
+    fn zip_round_trip_restores_images_from_files() {
+        let data_url = format!("data:image/png;base64,{}", png());
+        let items = vec![item("a", "text", "hello"), item("b", "image", &png()), item("c", "image", &data_url)];
+        let read = round_trip(bundle(items), ExportFormat::Zip);
+        assert_eq!(read.items[0].content, "hello");
+        assert_eq!(read.items[1].content, png());
+        // The prefix is dropped; the image itself is the same.
+        assert_eq!(read.items[2].content, png());
+        assert!(read.images.is_empty());
+    }
+
+    #[test]
+    fn zip_with_missing_image_leaves_it_empty() {
+        let path = temp_path("zip");
+        let mut zip = ZipWriter::new(File::create(&path).unwrap());
+        let mut exported = bundle(vec![item("b", "image", "")]);
+        exported.images.insert("b".to_string(), "images/b.png".to_string());
+        zip.start_file(ZIP_BUNDLE, SimpleFileOptions::default()).unwrap();
+        zip.write_all(&serde_json::to_vec(&exported).unwrap()).unwrap();
+        zip.finish().unwrap();
+
+        let read = read_bundle(&path);
+        let _ = fs::remove_file(&path);
+        assert_eq!(read.unwrap().items[0].content, "");
+    }
+
+    #[test]
+    fn newer_bundles_are_rejected() {
+        let mut newer = bundle(Vec::new());
+        newer.version = BUNDLE_VERSION + 1;
+        let path = temp_path("json");
+        write_bundle(newer, &path, ExportFormat::Json).unwrap();
+        let read = read_bundle(&path);
+        let _ = fs::remove_file(&path);
+        assert!(read.unwrap_err().contains("newer version"));
+    }
+}
@@ -0,0 +1,121 @@
+use std::sync::mpsc::{self, Sender};
+use std::sync::Mutex;
+use std::thread;
+use image::imageops::FilterType;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+use crate::db::DbState;
+
+/// Images are scaled down to fit this many pixels per side before detection;
+/// codes worth decoding stay readable and large screenshots stay cheap.
+const MAX_SCAN_SIDE: u32 = 1600;
+
+/// Feeds image item ids to the scan worker.
+pub struct ScanQueue(Mutex<Sender<String>>);
+
+/// A QR code or barcode found in an image item.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodedCode {
+    /// Symbology as named by the decoder, e.g. `QR_CODE`, `EAN_13`.
+    pub format: String,
+    pub text: String,
+}
+
+/// Every code found in a base64 PNG, without repeats.
+pub fn decode(content: &str) -> Vec<DecodedCode> {
+    let Some(mut image) = crate::paste::decode_image(content) else {
+        return Vec::new();
+    };
+    if image.width().max(image.height()) > MAX_SCAN_SIDE {
+        image = image.resize(MAX_SCAN_SIDE, MAX_SCAN_SIDE, FilterType::Triangle);
+    }
+    let luma = image.to_luma8();
+    let (width, height) = luma.dimensions();
+    // Finding nothing is reported as an error.
+    let Ok(results) = rxing::helpers::detect_multiple_in_luma(luma.into_raw(), width, height) else {
+        return Vec::new();
+    };
+    let mut codes: Vec<DecodedCode> = Vec::new();
+    for result in results {
+        let code = DecodedCode {
+            format: format!("{:?}", result.getBarcodeFormat()),
+            text: result.getText().to_string(),
+        };
+        if !code.text.is_empty() && !codes.contains(&code) {
+            codes.push(code);
+        }
+    }
+    codes
+}
+
+/// Scans image item `id` unless that was done already.
+fn process(app: &AppHandle, id: &str) {
+    let state = app.state::<DbState>();
+    let Some(item) = state.get_item(id) else { return };
+    if item.r#type != "image" || item.decoded.is_some() {
+        return;
+    }
+    let codes = decode(&item.content);
+    let found = !codes.is_empty();
+    if state.set_decoded(id, codes) {
+        let _ = state.save();
+        if found {
+            let _ = app.emit("clipboard-changed", state.get_history());
+        }
+    }
+}
+
+/// Queues image item `id` for scanning.
+pub fn enqueue(app: &AppHandle, id: &str) {
+    let _ = app.state::<ScanQueue>().0.lock().unwrap().send(id.to_string());
+}
+
+/// Queues every image that has not been scanned yet, e.g. after an import.
+pub fn queue_missing(app: &AppHandle) {
+    for item in app.state::<DbState>().get_history() {
+        if item.r#type == "image" && item.decoded.is_none() {
+            enqueue(app, &item.id);
+        }
+    }
+}
+
+/// Starts the worker that scans queued images one at a time, off the capture
+/// path, and queues images captured before scanning existed.
+pub fn start(app: &AppHandle) {
+    let (tx, rx) = mpsc::channel::<String>();
+    app.manage(ScanQueue(Mutex::new(tx)));
+    let worker = app.clone();
+    thread::spawn(move || {
+        for id in rx {
+            process(&worker, &id);
+        }
+    });
+    queue_missing(app);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::Engine;
+
+    #[test]
+    fn finds_a_qr_code() {
+        let png = include_bytes!("../tests/fixtures/qr_code.png");
+        let content = base64::engine::general_purpose::STANDARD.encode(png);
+        let codes = decode(&content);
+        assert_eq!(
+            codes,
+            [DecodedCode { format: "QR_CODE".to_string(), text: "https://example.com/clip".to_string() }]
+        );
+    }
+
+    #[test]
+    fn images_without_codes_decode_to_nothing() {
+        let blank = image::RgbaImage::from_pixel(64, 64, image::Rgba([255, 255, 255, 255]));
+        let mut png = Vec::new();
+        blank.write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png).unwrap();
+        assert!(decode(&base64::engine::general_purpose::STANDARD.encode(png)).is_empty());
+        assert!(decode("not an image").is_empty());
+    }
+}
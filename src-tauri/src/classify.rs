@@ -0,0 +1,322 @@
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+
+/// What a text item looks like, detected when it is captured.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ContentKind {
+    #[default]
+    Text,
+    Url,
+    Email,
+    Path,
+    Color,
+    Json,
+    Code,
+    Number,
+}
+
+/// Kind-specific details extracted alongside the `ContentKind`.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentMeta {
+    /// Host of a URL or domain of an email address.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    /// Whether a path pointed at something on disk when it was captured.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path_exists: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rgba: Option<[u8; 4]>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub json_valid: Option<bool>,
+    /// Most likely programming language of a code snippet.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Classification {
+    pub kind: ContentKind,
+    pub meta: ContentMeta,
+}
+
+impl Classification {
+    fn of(kind: ContentKind) -> Self {
+        Self { kind, meta: ContentMeta::default() }
+    }
+}
+
+const URL_SCHEMES: &[&str] = &["http", "https", "ftp", "ftps", "ws", "wss", "file", "ssh", "git"];
+
+/// Classifies a captured text. Checks run from the most to the least specific.
+pub fn classify(text: &str) -> Classification {
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        return Classification::of(ContentKind::Text);
+    }
+
+    let single_token = !trimmed.contains(char::is_whitespace);
+    if single_token {
+        if let Some(c) = classify_url(trimmed)
+            .or_else(|| classify_email(trimmed))
+            .or_else(|| classify_color(trimmed))
+            .or_else(|| classify_number(trimmed))
+        {
+            return c;
+        }
+    }
+    if !trimmed.contains('\n') {
+        if let Some(c) = classify_color(trimmed).or_else(|| classify_path(trimmed)) {
+            return c;
+        }
+    }
+    if let Some(c) = classify_json(trimmed) {
+        return c;
+    }
+    if let Some(language) = detect_language(trimmed) {
+        let mut c = Classification::of(ContentKind::Code);
+        c.meta.language = Some(language.to_string());
+        return c;
+    }
+    Classification::of(ContentKind::Text)
+}
+
+fn classify_url(text: &str) -> Option<Classification> {
+    let candidate = if text.starts_with("www.") {
+        format!("https://{}", text)
+    } else {
+        text.to_string()
+    };
+    let url = url::Url::parse(&candidate).ok()?;
+    if !URL_SCHEMES.contains(&url.scheme()) {
+        return None;
+    }
+    let host = url.host_str().filter(|h| !h.is_empty());
+    if host.is_none() && url.scheme() != "file" {
+        return None;
+    }
+    let mut c = Classification::of(ContentKind::Url);
+    c.meta.host = host.map(str::to_string);
+    Some(c)
+}
+
+fn classify_email(text: &str) -> Option<Classification> {
+    let text = text.strip_prefix("mailto:").unwrap_or(text);
+    let (local, domain) = text.split_once('@')?;
+    let local_ok = !local.is_empty()
+        && local.chars().all(|c| c.is_alphanumeric() || "._%+-".contains(c))
+        && !local.starts_with('.')
+        && !local.ends_with('.');
+    let labels: Vec<&str> = domain.split('.').collect();
+    let domain_ok = labels.len() >= 2
+        && labels.iter().all(|l| {
+            !l.is_empty()
+                && l.chars().all(|c| c.is_alphanumeric() || c == '-')
+                && !l.starts_with('-')
+                && !l.ends_with('-')
+        })
+        && labels.last().is_some_and(|tld| tld.len() >= 2 && tld.chars().all(char::is_alphabetic));
+    if !local_ok || !domain_ok {
+        return None;
+    }
+    let mut c = Classification::of(ContentKind::Email);
+    c.meta.host = Some(domain.to_lowercase());
+    Some(c)
+}
+
+fn classify_color(text: &str) -> Option<Classification> {
+    let rgba = parse_hex_color(text).or_else(|| parse_rgb_function(text))?;
+    let mut c = Classification::of(ContentKind::Color);
+    c.meta.rgba = Some(rgba);
+    Some(c)
+}
+
+/// `#rgb`, `#rgba`, `#rrggbb` and `#rrggbbaa`.
+fn parse_hex_color(text: &str) -> Option<[u8; 4]> {
+    let hex = text.strip_prefix('#')?;
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let digits: Vec<u8> = hex.chars().map(|c| c.to_digit(16).unwrap() as u8).collect();
+    match digits.len() {
+        3 | 4 => {
+            let mut rgba = [255u8; 4];
+            for (i, d) in digits.iter().enumerate() {
+                rgba[i] = d * 17;
+            }
+            Some(rgba)
+        }
+        6 | 8 => {
+            let mut rgba = [255u8; 4];
+            for (i, pair) in digits.chunks(2).enumerate() {
+                rgba[i] = pair[0] * 16 + pair[1];
+            }
+            Some(rgba)
+        }
+        _ => None,
+    }
+}
+
+/// `rgb(r, g, b)` and `rgba(r, g, b, a)`, with `a` either 0-1 or a percentage.
+fn parse_rgb_function(text: &str) -> Option<[u8; 4]> {
+    let lower = text.to_lowercase();
+    let (args, has_alpha) = if let Some(rest) = lower.strip_prefix("rgba(") {
+        (rest.strip_suffix(')')?, true)
+    } else {
+        (lower.strip_prefix("rgb(")?.strip_suffix(')')?, false)
+    };
+    let parts: Vec<&str> = args.split(',').map(str::trim).collect();
+    if parts.len() != if has_alpha { 4 } else { 3 } {
+        return None;
+    }
+    let mut rgba = [255u8; 4];
+    for (i, part) in parts.iter().take(3).enumerate() {
+        let value: u16 = part.parse().ok()?;
+        rgba[i] = u8::try_from(value).ok()?;
+    }
+    if has_alpha {
+        let alpha = match parts[3].strip_suffix('%') {
+            Some(pct) => pct.parse::<f32>().ok()? / 100.0,
+            None => parts[3].parse::<f32>().ok()?,
+        };
+        if !(0.0..=1.0).contains(&alpha) {
+            return None;
+        }
+        rgba[3] = (alpha * 255.0).round() as u8;
+    }
+    Some(rgba)
+}
+
+/// Integers, decimals (with `,` or `_` digit grouping), scientific notation and hex literals.
+fn classify_number(text: &str) -> Option<Classification> {
+    let unsigned = text.strip_prefix(['-', '+']).unwrap_or(text);
+    let is_number = if let Some(hex) = unsigned.strip_prefix("0x").or_else(|| unsigned.strip_prefix("0X")) {
+        !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit())
+    } else {
+        let grouped: String = unsigned.chars().filter(|c| *c != '_' && *c != ',').collect();
+        unsigned.starts_with(|c: char| c.is_ascii_digit() || c == '.')
+            && grouped.chars().all(|c| c.is_ascii_digit() || ".eE+-".contains(c))
+            && grouped.parse::<f64>().is_ok()
+    };
+    is_number.then(|| Classification::of(ContentKind::Number))
+}
+
+fn classify_path(text: &str) -> Option<Classification> {
+    let looks_like_path = text.starts_with('/')
+        || text.starts_with("~/")
+        || text.starts_with("./")
+        || text.starts_with("../");
+    if !looks_like_path || text.len() < 2 || text.contains("//") {
+        return None;
+    }
+    let expanded = match text.strip_prefix("~/") {
+        Some(rest) => match std::env::var("HOME") {
+            Ok(home) => Path::new(&home).join(rest),
+            Err(_) => Path::new(text).to_path_buf(),
+        },
+        None => Path::new(text).to_path_buf(),
+    };
+    let mut c = Classification::of(ContentKind::Path);
+    c.meta.path_exists = Some(expanded.exists());
+    Some(c)
+}
+
+fn classify_json(text: &str) -> Option<Classification> {
+    let object = text.starts_with('{') && text.ends_with('}');
+    let array = text.starts_with('[') && text.ends_with(']');
+    if !object && !array {
+        return None;
+    }
+    let valid = serde_json::from_str::<serde_json::Value>(text).is_ok();
+    // Broken JSON still counts when it has quoted keys; otherwise it is likely code.
+    let keyed_object = object && text.contains("\":");
+    if !valid && !keyed_object {
+        return None;
+    }
+    let mut c = Classification::of(ContentKind::Json);
+    c.meta.json_valid = Some(valid);
+    Some(c)
+}
+
+/// (language, [(marker, weight)]). Markers are matched case-sensitively except SQL.
+const LANGUAGE_MARKERS: &[(&str, &[(&str, u32)])] = &[
+    ("rust", &[("fn ", 2), ("let mut ", 3), ("impl ", 2), ("pub fn ", 3), ("::", 1), ("-> ", 1), ("#[derive", 3), ("println!", 3), ("use std::", 3), ("&mut ", 2), ("match ", 1)]),
+    ("python", &[("def ", 2), ("import ", 1), ("self.", 2), ("elif ", 3), ("print(", 1), ("__init__", 3), ("None", 1), ("):\n", 2), ("lambda ", 2)]),
+    ("javascript", &[("const ", 2), ("=> ", 2), ("function ", 2), ("console.log", 3), ("require(", 3), ("===", 2), ("document.", 2), ("let ", 1)]),
+    ("typescript", &[("interface ", 2), (": string", 3), (": number", 3), ("export type ", 3), ("as const", 3)]),
+    ("shell", &[("#!/bin/", 4), ("sudo ", 3), ("apt ", 2), ("echo ", 2), ("| grep", 3), ("$(", 2), ("fi\n", 2), ("; then", 3), ("cd ", 1), ("&& ", 1)]),
+    ("html", &[("<!DOCTYPE", 4), ("<html", 4), ("<div", 3), ("</", 2), ("<span", 3), ("<a href", 3)]),
+    ("css", &[("px;", 3), ("color:", 2), ("margin:", 3), ("padding:", 3), ("display:", 3), ("font-size:", 3)]),
+    ("c", &[("#include", 4), ("int main(", 4), ("printf(", 3), ("malloc(", 3), ("->", 1)]),
+    ("java", &[("public class ", 4), ("public static void main", 4), ("System.out.println", 4), ("private ", 1), ("@Override", 3)]),
+    ("go", &[("package main", 4), ("func ", 2), (":= ", 3), ("fmt.", 3), ("err != nil", 4)]),
+];
+
+const SQL_MARKERS: &[(&str, u32)] = &[("select ", 2), (" from ", 2), ("where ", 1), ("insert into ", 4), ("update ", 1), (" set ", 1), ("create table ", 4), ("join ", 2), ("group by ", 3), ("order by ", 3)];
+
+/// Minimum score for a text to be considered code at all.
+const CODE_THRESHOLD: u32 = 3;
+
+fn detect_language(text: &str) -> Option<&'static str> {
+    let score = |markers: &[(&str, u32)], haystack: &str| -> u32 {
+        markers.iter().filter(|(m, _)| haystack.contains(m)).map(|(_, w)| w).sum()
+    };
+
+    let mut best: Option<(&'static str, u32)> = None;
+    for (language, markers) in LANGUAGE_MARKERS {
+        let s = score(markers, text);
+        if s > best.map_or(0, |(_, b)| b) {
+            best = Some((language, s));
+        }
+    }
+    let sql = score(SQL_MARKERS, &text.to_lowercase());
+    if sql > best.map_or(0, |(_, b)| b) {
+        best = Some(("sql", sql));
+    }
+
+    best.filter(|(_, s)| *s >= CODE_THRESHOLD).map(|(language, _)| language)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Sample {
+        text: String,
+        kind: ContentKind,
+        host: Option<String>,
+        path_exists: Option<bool>,
+        rgba: Option<[u8; 4]>,
+        json_valid: Option<bool>,
+        language: Option<String>,
+    }
+
+    #[test]
+    fn labeled_corpus() {
+        let corpus: Vec<Sample> =
+            serde_json::from_str(include_str!("../tests/fixtures/classify_corpus.json")).unwrap();
+        let mut failures = Vec::new();
+
+        for sample in &corpus {
+            let got = classify(&sample.text);
+            let expected_meta = ContentMeta {
+                host: sample.host.clone(),
+                path_exists: sample.path_exists,
+                rgba: sample.rgba,
+                json_valid: sample.json_valid,
+                language: sample.language.clone(),
+            };
+            if got.kind != sample.kind || got.meta != expected_meta {
+                failures.push(format!(
+                    "{:?}: expected {:?} {:?}, got {:?} {:?}",
+                    sample.text, sample.kind, expected_meta, got.kind, got.meta
+                ));
+            }
+        }
+
+        assert!(failures.is_empty(), "{} misclassified:\n{}", failures.len(), failures.join("\n"));
+    }
+}
@@ -1,90 +1,312 @@
 use tauri::{AppHandle, Manager, Emitter};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::Mutex;
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tauri_plugin_clipboard_manager::ClipboardExt;
 use crate::db::{DbState, ClipboardItem};
+use crate::plaintext::{MIME_HTML, MIME_RTF};
+use crate::settings::AutoClear;
 use uuid::Uuid;
 
+/// Runtime flags shared between the watcher thread and the rest of the app.
+#[derive(Default)]
+pub struct WatcherState {
+    paused: AtomicBool,
+    /// While non-zero the watcher does not look at the clipboard at all.
+    holds: AtomicUsize,
+    /// Last clipboard contents the watcher has seen; anything equal is not captured.
+    last_text: Mutex<String>,
+    last_image: Mutex<String>,
+    /// The last copy and when to wipe it from the clipboard (`Settings::auto_clear`).
+    pending_clear: Mutex<Option<(Instant, ClipboardItem)>>,
+    /// Feeds copies to the worker that takes clipboard ownership; started on first use.
+    owner: Mutex<Option<Sender<ClipboardItem>>>,
+}
+
+/// Target set by KeePassXC, KDE Wallet and others on copied passwords.
+const PASSWORD_MANAGER_HINT: &str = "x-kde-passwordManagerHint";
+
+impl WatcherState {
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    /// Flips the pause flag and returns the new value.
+    pub fn toggle_paused(&self) -> bool {
+        !self.paused.fetch_xor(true, Ordering::SeqCst)
+    }
+
+    /// Stops the watcher from reading the clipboard until `release` is called,
+    /// so temporary contents are neither captured nor remembered as seen.
+    pub fn hold(&self) {
+        self.holds.fetch_add(1, Ordering::SeqCst);
+    }
+
+    pub fn release(&self) {
+        self.holds.fetch_sub(1, Ordering::SeqCst);
+    }
+
+    fn is_held(&self) -> bool {
+        self.holds.load(Ordering::SeqCst) > 0
+    }
+
+    /// Marks `text` as already seen so writing it to the clipboard does not
+    /// add a history entry.
+    pub fn skip_text(&self, text: &str) {
+        *self.last_text.lock().unwrap() = text.to_string();
+    }
+
+    /// Like `skip_text`, for whatever `write_item` puts on the clipboard for `item`.
+    pub fn skip_item(&self, item: &ClipboardItem) {
+        match item.r#type.as_str() {
+            "text" => self.skip_text(&item.content),
+            "image" => *self.last_image.lock().unwrap() = item.content.clone(),
+            _ => {}
+        }
+    }
+
+    /// Takes the scheduled auto-clear once it is due.
+    fn take_due_clear(&self) -> Option<ClipboardItem> {
+        let mut pending = self.pending_clear.lock().unwrap();
+        match &*pending {
+            Some((due, _)) if *due <= Instant::now() => pending.take().map(|(_, item)| item),
+            _ => None,
+        }
+    }
+
+    /// Returns true (and remembers it) when `current` differs from the last seen value.
+    fn is_new(last: &Mutex<String>, current: &str) -> bool {
+        let mut last = last.lock().unwrap();
+        if current.is_empty() || *last == current {
+            return false;
+        }
+        *last = current.to_string();
+        true
+    }
+}
+
 pub fn start_watcher(app: AppHandle) {
     thread::spawn(move || {
-        let mut last_text = String::new();
-        let mut last_image = String::new();
-
         loop {
+            let watcher = app.state::<WatcherState>();
+            if watcher.is_held() {
+                thread::sleep(Duration::from_millis(100));
+                continue;
+            }
+            // While paused we still track the clipboard so that whatever was copied
+            // in the meantime is not captured once capture resumes.
+            let paused = watcher.is_paused();
+
             // Check text
             let current_text = app.clipboard().read_text();
-            
+
             if let Ok(text) = current_text {
-                if text != last_text && !text.is_empty() {
-                    last_text = text.clone();
-                    
-                    let item = ClipboardItem {
+                if WatcherState::is_new(&watcher.last_text, &text) {
+                    // Password managers ask for their copies to stay out of any history.
+                    let hinted = crate::selection::offers(PASSWORD_MANAGER_HINT).unwrap_or(false);
+                    let mut item = ClipboardItem {
                         id: Uuid::new_v4().to_string(),
                         r#type: "text".to_string(),
                         content: text.clone(),
-                        timestamp: chrono::Utc::now().timestamp_millis(), 
+                        timestamp: chrono::Utc::now().timestamp_millis(),
                         is_pinned: false,
+                        is_sensitive: hinted,
+                        ..Default::default()
                     };
-
-                    let state = app.state::<DbState>();
-                    state.add_item(item);
-                    if let Err(e) = state.save() {
-                        eprintln!("Failed to save db: {}", e);
-                    }
-                    
-                    let history = state.get_history();
-                    if let Err(e) = app.emit("clipboard-changed", history) {
-                         eprintln!("Failed to emit event: {}", e);
+                    item.classify();
+                    schedule_clear(&app, &item);
+                    if !paused && !hinted {
+                        capture(&app, item);
                     }
                 }
             }
 
             // Check image
-            match app.clipboard().read_image() {
-                Ok(image) => {
-                     // Convert to base64
-                     // image is tauri::image::Image
-                     let (width, height) = (image.width(), image.height());
-                     let rgba = image.rgba();
-                     
-                     if let Some(img_buffer) = image::RgbaImage::from_raw(width, height, rgba.to_vec()) {
-                         use std::io::Cursor;
-                         let mut bytes: Vec<u8> = Vec::new();
-                         let mut cursor = Cursor::new(&mut bytes);
-                         
-                         if let Ok(_) = img_buffer.write_to(&mut cursor, image::ImageFormat::Png) {
-                             use base64::Engine;
-                             let base64_image = base64::engine::general_purpose::STANDARD.encode(&bytes);
-                             
-                             if base64_image != last_image && !base64_image.is_empty() {
-                                 last_image = base64_image.clone();
-
-                                 let item = ClipboardItem {
-                                    id: Uuid::new_v4().to_string(),
-                                    r#type: "image".to_string(),
-                                    content: base64_image,
-                                    timestamp: chrono::Utc::now().timestamp_millis(),
-                                    is_pinned: false,
-                                 };
-
-                                 let state = app.state::<DbState>();
-                                 state.add_item(item);
-                                 if let Err(e) = state.save() {
-                                     eprintln!("Failed to save db (image): {}", e);
-                                 }
-
-                                 let history = state.get_history();
-                                 if let Err(e) = app.emit("clipboard-changed", history) {
-                                     eprintln!("Failed to emit event (image): {}", e);
-                                 }
-                             }
-                         }
-                     }
-                },
-                Err(_) => {} // No image or error reading
+            if let Some(base64_image) = read_image(&app) {
+                if WatcherState::is_new(&watcher.last_image, &base64_image) {
+                    let item = ClipboardItem {
+                        id: Uuid::new_v4().to_string(),
+                        r#type: "image".to_string(),
+                        content: base64_image,
+                        timestamp: chrono::Utc::now().timestamp_millis(),
+                        is_pinned: false,
+                        ..Default::default()
+                    };
+                    schedule_clear(&app, &item);
+                    if !paused {
+                        capture(&app, item);
+                    }
+                }
+            }
+
+            if let Some(item) = watcher.take_due_clear() {
+                clear_if_holding(&app, &item);
             }
-            
+
             thread::sleep(Duration::from_millis(1000));
         }
     });
 }
+
+/// Stores a new copy, handing it to the ownership worker first when
+/// `Settings::take_ownership` is on. Reading every format can take seconds,
+/// so the worker runs off the watcher thread, one copy at a time.
+fn capture(app: &AppHandle, item: ClipboardItem) {
+    if !app.state::<DbState>().get_settings().take_ownership {
+        store_item(app, item);
+        return;
+    }
+    let watcher = app.state::<WatcherState>();
+    let mut owner = watcher.owner.lock().unwrap();
+    let sender = owner.get_or_insert_with(|| {
+        let (tx, rx) = mpsc::channel::<ClipboardItem>();
+        let app = app.clone();
+        thread::spawn(move || {
+            for mut item in rx {
+                take_ownership(&mut item);
+                store_item(&app, item);
+            }
+        });
+        tx
+    });
+    let _ = sender.send(item);
+}
+
+/// Reads every format on the clipboard and serves it from this process, so
+/// the copy survives the source application exiting. Ownership is only taken
+/// when the snapshot is complete; serving part of it would drop formats the
+/// source still offers. HTML and RTF are also kept on `item` for plain-text
+/// pastes.
+fn take_ownership(item: &mut ClipboardItem) {
+    let snapshot = match crate::selection::read_all() {
+        Ok(snapshot) if !snapshot.is_empty() => snapshot,
+        Ok(_) => return,
+        Err(e) => {
+            log::warn!("Could not read clipboard formats: {}", e);
+            return;
+        }
+    };
+    // The clipboard may have changed while the worker was busy.
+    let is_current = || {
+        ["UTF8_STRING", "text/plain;charset=utf-8", "text/plain"]
+            .iter()
+            .find_map(|target| snapshot.get(target))
+            .is_some_and(|text| text == item.content.as_bytes())
+    };
+    if item.r#type == "text" && is_current() {
+        for mime in [MIME_HTML, MIME_RTF] {
+            if let Some(Ok(text)) = snapshot.get(mime).map(std::str::from_utf8) {
+                item.formats.insert(mime.to_string(), text.to_string());
+            }
+        }
+    }
+    if !snapshot.complete {
+        log::info!("Not taking clipboard ownership: some formats could not be read");
+        return;
+    }
+    if let Err(e) = crate::selection::serve(snapshot) {
+        log::warn!("Could not take clipboard ownership: {}", e);
+    }
+}
+
+/// Restarts the auto-clear countdown for a new copy, or cancels it when
+/// `item` is not one the setting covers.
+fn schedule_clear(app: &AppHandle, item: &ClipboardItem) {
+    let state = app.state::<DbState>();
+    let settings = state.get_settings();
+    let covered = match settings.auto_clear {
+        AutoClear::Off => false,
+        AutoClear::All => true,
+        AutoClear::Sensitive => state.is_sensitive(item),
+    };
+    let due = Instant::now() + Duration::from_secs(settings.auto_clear_secs as u64);
+    *app.state::<WatcherState>().pending_clear.lock().unwrap() = covered.then(|| (due, item.clone()));
+}
+
+/// Starts the auto-clear countdown for what the app itself just put on the
+/// clipboard for `source` (a history item, or the image a text was read
+/// from). Needed because the watcher skips or dedups these writes.
+pub fn schedule_written(app: &AppHandle, source: &ClipboardItem) {
+    let (r#type, content) = match app.clipboard().read_text() {
+        Ok(text) => ("text", text),
+        Err(_) => match read_image(app) {
+            Some(image) => ("image", image),
+            None => return,
+        },
+    };
+    let written = ClipboardItem {
+        r#type: r#type.to_string(),
+        content,
+        is_sensitive: app.state::<DbState>().is_sensitive(source),
+        ..Default::default()
+    };
+    schedule_clear(app, &written);
+}
+
+/// Remembers what is on the clipboard now as seen. Used after a transient
+/// paste puts the previous contents back: whatever was marked skipped for the
+/// paste no longer matches, and the restore must not count as a new copy.
+pub fn mark_seen(app: &AppHandle) {
+    let watcher = app.state::<WatcherState>();
+    if let Ok(text) = app.clipboard().read_text() {
+        *watcher.last_text.lock().unwrap() = text;
+    }
+    if let Some(image) = read_image(app) {
+        *watcher.last_image.lock().unwrap() = image;
+    }
+}
+
+/// The image on the clipboard as base64 PNG, the way image items store it.
+fn read_image(app: &AppHandle) -> Option<String> {
+    use base64::Engine;
+    let image = app.clipboard().read_image().ok()?;
+    let buffer = image::RgbaImage::from_raw(image.width(), image.height(), image.rgba().to_vec())?;
+    let mut bytes: Vec<u8> = Vec::new();
+    buffer.write_to(&mut std::io::Cursor::new(&mut bytes), image::ImageFormat::Png).ok()?;
+    Some(base64::engine::general_purpose::STANDARD.encode(&bytes))
+}
+
+/// Clears the system clipboard if it still holds `item`'s content. The
+/// history is left alone. Returns whether the clipboard was cleared.
+pub fn clear_if_holding(app: &AppHandle, item: &ClipboardItem) -> bool {
+    let watcher = app.state::<WatcherState>();
+    let (holding, last) = match item.r#type.as_str() {
+        "text" => (app.clipboard().read_text().is_ok_and(|t| t == item.content), &watcher.last_text),
+        "image" => (read_image(app).is_some_and(|i| i == item.content), &watcher.last_image),
+        _ => return false,
+    };
+    if !holding {
+        return false;
+    }
+    if let Err(e) = app.clipboard().clear() {
+        log::warn!("Failed to clear the clipboard: {}", e);
+        return false;
+    }
+    // Copying the same content again should be captured again.
+    last.lock().unwrap().clear();
+    true
+}
+
+/// Adds a captured item to the history, persists it and notifies the UI.
+pub fn store_item(app: &AppHandle, item: ClipboardItem) {
+    let kind = item.r#type.clone();
+    let state = app.state::<DbState>();
+    let added = state.add_item(item);
+    if let Err(e) = state.save() {
+        eprintln!("Failed to save db ({}): {}", kind, e);
+    }
+    if let Some(id) = added {
+        crate::queue::on_captured(app, &id);
+        if kind == "image" {
+            crate::ocr::enqueue(app, &id);
+            crate::barcode::enqueue(app, &id);
+        }
+    }
+
+    let history = state.get_history();
+    if let Err(e) = app.emit("clipboard-changed", history) {
+        eprintln!("Failed to emit event ({}): {}", kind, e);
+    }
+}
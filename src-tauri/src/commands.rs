@@ -1,12 +1,24 @@
 use tauri::{AppHandle, State, Window, Manager, Emitter};
-use crate::db::{DbState, ClipboardItem};
+use crate::archive::{Bundle, ExportFormat, ImportMode, ImportSummary, BUNDLE_VERSION};
+use crate::db::{DbState, ClipboardItem, Collection, HistoryFilter, ItemUpdate, Snippet, Stats, Tag, TrashedItem};
+use std::collections::{BTreeMap, HashMap};
+use crate::clipboard::WatcherState;
+use crate::imageedit::{ImageEdit, Output, OutputFormat};
+use crate::importers::ImportSource;
+use crate::queue::{PasteQueue, QueueState};
+use crate::settings::{Settings, SettingsError, WindowPosition};
+use crate::sync::SyncReport;
+use crate::template::TemplateError;
+use crate::transform::{Separator, Transform};
 use tauri_plugin_clipboard_manager::ClipboardExt;
 use tauri_plugin_shell::ShellExt;
-use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
 
 #[tauri::command]
-pub fn get_history(state: State<DbState>) -> Vec<ClipboardItem> {
-    state.get_history()
+pub fn get_history(state: State<DbState>, filter: Option<HistoryFilter>) -> Vec<ClipboardItem> {
+    match filter {
+        Some(filter) => state.get_history_filtered(&filter),
+        None => state.get_history(),
+    }
 }
 
 #[tauri::command]
@@ -30,6 +42,223 @@ pub fn toggle_pin(state: State<DbState>, id: String) -> Vec<ClipboardItem> {
     state.get_history()
 }
 
+/// Sets an item's title and note, or edits its text. The previous text is
+/// kept as a revision.
+#[tauri::command]
+pub fn update_item(state: State<DbState>, id: String, update: ItemUpdate) -> Result<Vec<ClipboardItem>, String> {
+    state.update_item(&id, update, chrono::Utc::now().timestamp_millis())?;
+    let _ = state.save();
+    Ok(state.get_history())
+}
+
+/// Puts revision `index` of an item back as its content.
+#[tauri::command]
+pub fn restore_revision(state: State<DbState>, id: String, index: usize) -> Result<Vec<ClipboardItem>, String> {
+    state.restore_revision(&id, index, chrono::Utc::now().timestamp_millis())?;
+    let _ = state.save();
+    Ok(state.get_history())
+}
+
+/// Deletes the item at `at` (Unix ms), or never with `None`.
+#[tauri::command]
+pub fn set_item_expiry(state: State<DbState>, id: String, at: Option<i64>) -> Result<Vec<ClipboardItem>, String> {
+    state.set_item_expiry(&id, at, chrono::Utc::now().timestamp_millis())?;
+    let _ = state.save();
+    Ok(state.get_history())
+}
+
+/// Whether tesseract was found, for the OCR setting.
+#[tauri::command]
+pub fn ocr_available() -> bool {
+    crate::ocr::available()
+}
+
+/// Copies the text recognized in an image item to the clipboard.
+#[tauri::command]
+pub fn copy_ocr_text(app: AppHandle, state: State<DbState>, id: String) -> Result<(), String> {
+    let item = state.get_item(&id).ok_or("Item not found")?;
+    let text = item
+        .ocr_text
+        .clone()
+        .filter(|t| !t.is_empty())
+        .ok_or("No text was recognized in this image")?;
+    copy_derived_text(&app, &state, &item, text)
+}
+
+/// Copies the `index`-th QR code or barcode payload found in an image item.
+#[tauri::command]
+pub fn copy_decoded(app: AppHandle, state: State<DbState>, id: String, index: usize) -> Result<(), String> {
+    let item = state.get_item(&id).ok_or("Item not found")?;
+    let code = item
+        .decoded
+        .as_ref()
+        .and_then(|codes| codes.get(index))
+        .cloned()
+        .ok_or("No such decoded code")?;
+    copy_derived_text(&app, &state, &item, code.text)
+}
+
+/// Copies text read from image item `source`. Text from a sensitive image is
+/// kept out of the history and cleared like the image would be.
+fn copy_derived_text(app: &AppHandle, state: &DbState, source: &ClipboardItem, text: String) -> Result<(), String> {
+    if state.is_sensitive(source) {
+        app.state::<WatcherState>().skip_text(&text);
+    }
+    app.clipboard().write_text(text).map_err(|e| e.to_string())?;
+    crate::clipboard::schedule_written(app, source);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn undo_last_action(state: State<DbState>) -> Vec<ClipboardItem> {
+    if state.undo_last_action() {
+        let _ = state.save();
+    }
+    state.get_history()
+}
+
+#[tauri::command]
+pub fn get_trash(state: State<DbState>) -> Vec<TrashedItem> {
+    state.get_trash()
+}
+
+#[tauri::command]
+pub fn restore_item(state: State<DbState>, id: String) -> Vec<ClipboardItem> {
+    if state.restore_item(&id) {
+        let _ = state.save();
+    }
+    state.get_history()
+}
+
+#[tauri::command]
+pub fn empty_trash(state: State<DbState>) -> Vec<TrashedItem> {
+    state.empty_trash();
+    let _ = state.save();
+    state.get_trash()
+}
+
+/// Writes the (optionally filtered) history to `path`. Returns how many items were exported.
+#[tauri::command]
+pub fn export_history(
+    state: State<DbState>,
+    path: String,
+    format: ExportFormat,
+    filter: Option<HistoryFilter>,
+) -> Result<usize, String> {
+    crate::archive::export_history(&state, std::path::Path::new(&path), format, &filter.unwrap_or_default())
+}
+
+/// Imports a JSON or ZIP export made by `export_history`, or with `source`
+/// the history file of another clipboard manager.
+#[tauri::command]
+pub fn import_history(
+    app: AppHandle,
+    state: State<DbState>,
+    path: String,
+    mode: ImportMode,
+    source: Option<ImportSource>,
+) -> Result<ImportSummary, String> {
+    let path = std::path::Path::new(&path);
+    let (bundle, unreadable) = match source {
+        None => (crate::archive::read_bundle(path)?, 0),
+        Some(source) => {
+            let imported = crate::importers::read(source, path)?;
+            let bundle = Bundle {
+                version: BUNDLE_VERSION,
+                exported_at: chrono::Utc::now().timestamp_millis(),
+                items: imported.items,
+                ..Default::default()
+            };
+            (bundle, imported.skipped)
+        }
+    };
+    let mut summary = state.import_bundle(bundle, mode);
+    summary.skipped_invalid += unreadable;
+    let _ = state.save();
+    let _ = app.emit("clipboard-changed", state.get_history());
+    crate::ocr::queue_missing(&app);
+    crate::barcode::queue_missing(&app);
+    Ok(summary)
+}
+
+/// Syncs with the shared folder right away instead of waiting for the next interval.
+#[tauri::command]
+pub fn sync_now(app: AppHandle) -> Result<SyncReport, String> {
+    crate::sync::run(&app)
+}
+
+/// Totals, per-type counts, the most pasted items and database size.
+#[tauri::command]
+pub fn get_stats(state: State<DbState>) -> Stats {
+    state.get_stats()
+}
+
+#[tauri::command]
+pub fn get_tags(state: State<DbState>) -> Vec<Tag> {
+    state.get_tags()
+}
+
+#[tauri::command]
+pub fn create_tag(state: State<DbState>, name: String) -> Result<Vec<Tag>, String> {
+    state.create_tag(&name)?;
+    let _ = state.save();
+    Ok(state.get_tags())
+}
+
+#[tauri::command]
+pub fn rename_tag(state: State<DbState>, id: String, name: String) -> Result<Vec<Tag>, String> {
+    state.rename_tag(&id, &name)?;
+    let _ = state.save();
+    Ok(state.get_tags())
+}
+
+#[tauri::command]
+pub fn delete_tag(state: State<DbState>, id: String) -> Vec<Tag> {
+    state.delete_tag(&id);
+    let _ = state.save();
+    state.get_tags()
+}
+
+#[tauri::command]
+pub fn set_item_tags(state: State<DbState>, id: String, tag_ids: Vec<String>) -> Result<Vec<ClipboardItem>, String> {
+    state.set_item_tags(&id, tag_ids)?;
+    let _ = state.save();
+    Ok(state.get_history())
+}
+
+#[tauri::command]
+pub fn get_collections(state: State<DbState>) -> Vec<Collection> {
+    state.get_collections()
+}
+
+#[tauri::command]
+pub fn create_collection(state: State<DbState>, name: String) -> Result<Vec<Collection>, String> {
+    state.create_collection(&name)?;
+    let _ = state.save();
+    Ok(state.get_collections())
+}
+
+#[tauri::command]
+pub fn rename_collection(state: State<DbState>, id: String, name: String) -> Result<Vec<Collection>, String> {
+    state.rename_collection(&id, &name)?;
+    let _ = state.save();
+    Ok(state.get_collections())
+}
+
+#[tauri::command]
+pub fn delete_collection(state: State<DbState>, id: String) -> Vec<Collection> {
+    state.delete_collection(&id);
+    let _ = state.save();
+    state.get_collections()
+}
+
+#[tauri::command]
+pub fn set_item_collection(state: State<DbState>, id: String, collection_id: Option<String>) -> Result<Vec<ClipboardItem>, String> {
+    state.set_item_collection(&id, collection_id)?;
+    let _ = state.save();
+    Ok(state.get_history())
+}
+
 #[tauri::command]
 pub fn hide_window(window: Window) {
     let _ = window.hide();
@@ -39,104 +268,278 @@ pub fn hide_window(window: Window) {
 pub fn paste_item(app: AppHandle, state: State<DbState>, id: String) {
     let history = state.get_history();
     if let Some(item) = history.iter().find(|i| i.id == id) {
-        // Write to clipboard
-        let clip = app.clipboard();
-        if item.r#type == "text" {
-            let _ = clip.write_text(item.content.clone());
-        } else if item.r#type == "image" {
-             // Decode base64
-             use base64::Engine;
-             let b64 = item.content.clone();
-             // Remove prefix if present (e.g. "data:image/png;base64,")
-             let b64_clean = if let Some(idx) = b64.find(',') {
-                 &b64[idx+1..]
-             } else {
-                 &b64
-             };
-
-             if let Ok(bytes) = base64::engine::general_purpose::STANDARD.decode(b64_clean) {
-                 if let Ok(img) = image::load_from_memory(&bytes) {
-                     let rgba_img = img.to_rgba8();
-                     let (width, height) = rgba_img.dimensions();
-                     let pixels = rgba_img.into_raw();
-                     
-                     let tauri_image = tauri::image::Image::new(&pixels, width, height);
-                     let _ = clip.write_image(&tauri_image);
-                 }
-             }
-        }
+        crate::paste::paste_item(&app, item);
+    }
+}
 
-        // Hide window
-        if let Some(win) = app.get_webview_window("main") {
-            let _ = win.hide();
-        }
+/// Pastes only the sanitized plain-text form of an item, derived from its
+/// HTML or RTF when it has no text of its own.
+#[tauri::command]
+pub fn paste_plain(app: AppHandle, state: State<DbState>, id: String) -> Result<(), String> {
+    let item = state.get_item(&id).ok_or("Item not found")?;
+    if crate::plaintext::plain_text(&item).is_none() {
+        return Err("Item has no text to paste".to_string());
+    }
+    crate::paste::paste_plain(&app, &item);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_paste_queue(queue: State<QueueState>) -> PasteQueue {
+    queue.get()
+}
+
+/// Replaces the paste queue with `ids`, pasted in that order.
+#[tauri::command]
+pub fn set_paste_queue(app: AppHandle, ids: Vec<String>) -> PasteQueue {
+    crate::queue::set_items(&app, ids)
+}
+
+/// While collecting, every new copy is appended to the paste queue.
+#[tauri::command]
+pub fn set_queue_collecting(app: AppHandle, collecting: bool) -> PasteQueue {
+    crate::queue::set_collecting(&app, collecting)
+}
+
+#[tauri::command]
+pub fn clear_paste_queue(app: AppHandle) -> PasteQueue {
+    crate::queue::clear(&app)
+}
+
+#[tauri::command]
+pub fn paste_next_queued(app: AppHandle) -> Result<(), String> {
+    if !crate::queue::paste_next(&app) {
+        return Err("The paste queue is empty".to_string());
+    }
+    Ok(())
+}
+
+/// Joins the text of `ids`, in the given order, and pastes the result like
+/// `paste_item`. With `save` the joined text is added to the history instead.
+#[tauri::command]
+pub fn merge_items(
+    app: AppHandle,
+    state: State<DbState>,
+    watcher: State<WatcherState>,
+    ids: Vec<String>,
+    separator: Separator,
+    save: bool,
+) -> Result<Vec<ClipboardItem>, String> {
+    if ids.len() < 2 {
+        return Err("Select at least two items to merge".to_string());
+    }
+    let mut parts = Vec::with_capacity(ids.len());
+    for id in &ids {
+        let item = state.get_item(id).ok_or("Item not found")?;
+        parts.push(crate::plaintext::plain_text(&item).ok_or("Only text items can be merged")?);
+    }
+
+    let mut merged = ClipboardItem {
+        id: uuid::Uuid::new_v4().to_string(),
+        r#type: "text".to_string(),
+        content: parts.join(separator.as_str()),
+        timestamp: chrono::Utc::now().timestamp_millis(),
+        ..Default::default()
+    };
+    merged.classify();
+
+    if save {
+        crate::clipboard::store_item(&app, merged);
+        return Ok(state.get_history());
+    }
+    for id in &ids {
+        crate::paste::record_paste(&app, id);
+    }
+    watcher.skip_item(&merged);
+    crate::paste::paste_item(&app, &merged);
+    Ok(state.get_history())
+}
+
+/// Types the item's text into the focused window as keystrokes (Escape cancels).
+#[tauri::command]
+pub fn type_item(app: AppHandle, state: State<DbState>, id: String) -> Result<(), String> {
+    let item = state.get_item(&id).ok_or("Item not found")?;
+    let text = crate::plaintext::plain_text(&item).ok_or("Item has no text to type")?;
+    crate::paste::record_paste(&app, &id);
+    crate::paste::type_out(&app, text);
+    Ok(())
+}
+
+/// Adds a new image item made from image `id` with `edits` applied, encoded as
+/// `output` asks (PNG by default). The source item is left untouched. JPEG and
+/// WebP encodings are kept in `formats` and offered when the item is pasted;
+/// the content stays PNG so the history can show it.
+#[tauri::command]
+pub fn edit_image(
+    app: AppHandle,
+    state: State<DbState>,
+    id: String,
+    edits: Vec<ImageEdit>,
+    output: Option<Output>,
+) -> Result<Vec<ClipboardItem>, String> {
+    use base64::Engine;
+    let engine = base64::engine::general_purpose::STANDARD;
+    let item = state.get_item(&id).ok_or("Item not found")?;
+    if item.r#type != "image" {
+        return Err("Only image items can be edited".to_string());
+    }
+    let output = output.unwrap_or_default();
+    if edits.is_empty() && output.format == OutputFormat::Png {
+        return Err("Nothing to change".to_string());
+    }
+    let image = crate::paste::decode_image(&item.content).ok_or("Could not decode the image")?;
+    let mut image = crate::imageedit::apply_all(image, &edits)?;
+
+    let mut formats = BTreeMap::new();
+    if output.format != OutputFormat::Png {
+        let bytes = crate::imageedit::encode(&image, output)?;
+        // Preview what the lossy encoding actually kept.
+        image = image::load_from_memory(&bytes).map_err(|e| e.to_string())?;
+        formats.insert(output.format.mime().to_string(), engine.encode(&bytes));
+    }
+    let png = crate::imageedit::encode(&image, Output::default())?;
+    let new_item = ClipboardItem {
+        id: uuid::Uuid::new_v4().to_string(),
+        r#type: "image".to_string(),
+        content: engine.encode(png),
+        timestamp: chrono::Utc::now().timestamp_millis(),
+        formats,
+        ..Default::default()
+    };
+    let new_id = new_item.id.clone();
+    state.insert_item(new_item);
+    let _ = state.save();
+    crate::ocr::enqueue(&app, &new_id);
+    crate::barcode::enqueue(&app, &new_id);
+    let history = state.get_history();
+    let _ = app.emit("clipboard-changed", &history);
+    Ok(history)
+}
+
+/// Available transforms for `paste_transformed`, in display order.
+#[tauri::command]
+pub fn get_transforms() -> Vec<Transform> {
+    Transform::ALL.to_vec()
+}
+
+/// Pastes the item's text after running it through `transforms`. The stored item
+/// is left untouched; with `save_as_new` the result is also added to the history.
+#[tauri::command]
+pub fn paste_transformed(
+    app: AppHandle,
+    state: State<DbState>,
+    watcher: State<WatcherState>,
+    id: String,
+    transforms: Vec<Transform>,
+    save_as_new: Option<bool>,
+) -> Result<Vec<ClipboardItem>, String> {
+    let item = state.get_item(&id).ok_or("Item not found")?;
+    if item.r#type != "text" {
+        return Err("Only text items can be transformed".to_string());
+    }
+    let text = crate::transform::apply_all(&item.content, &transforms)?;
 
-        // Simulate Paste
-        tauri::async_runtime::spawn(async move {
-            std::thread::sleep(std::time::Duration::from_millis(100)); // wait for focus switch
-             let shell = app.shell();
-             
-             // Detect active window class
-             let output = shell.command("xdotool")
-                .args(["getactivewindow", "getwindowclassname"])
-                .output()
-                .await;
-                
-             let mut is_terminal = false;
-             if let Ok(out) = output {
-                 if out.status.success() {
-                     let class_name = String::from_utf8_lossy(&out.stdout).to_lowercase();
-                     // Common terminal class names
-                     if class_name.contains("term") || 
-                        class_name.contains("alacritty") || 
-                        class_name.contains("kitty") ||
-                        class_name.contains("konsole") {
-                            is_terminal = true;
-                     }
-                 }
-             }
-
-             if is_terminal {
-                 let _ = shell.command("xdotool")
-                     .args(["key", "--clearmodifiers", "ctrl+shift+v"])
-                     .spawn();
-             } else {
-                 let _ = shell.command("xdotool")
-                     .args(["key", "--clearmodifiers", "ctrl+v"])
-                     .spawn();
-             }
-        });
+    watcher.skip_text(&text);
+    if save_as_new.unwrap_or(false) {
+        let mut new_item = ClipboardItem {
+            id: uuid::Uuid::new_v4().to_string(),
+            r#type: "text".to_string(),
+            content: text.clone(),
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            ..Default::default()
+        };
+        new_item.classify();
+        crate::clipboard::store_item(&app, new_item);
     }
+
+    crate::paste::record_paste(&app, &id);
+    crate::paste::paste_text(&app, &text, 0);
+    Ok(state.get_history())
+}
+
+#[tauri::command]
+pub fn get_snippets(state: State<DbState>) -> Vec<Snippet> {
+    state.get_snippets()
+}
+
+#[tauri::command]
+pub fn create_snippet(state: State<DbState>, name: String, body: String) -> Result<Vec<Snippet>, String> {
+    state.create_snippet(&name, &body)?;
+    let _ = state.save();
+    Ok(state.get_snippets())
+}
+
+#[tauri::command]
+pub fn update_snippet(state: State<DbState>, id: String, name: String, body: String) -> Result<Vec<Snippet>, String> {
+    state.update_snippet(&id, &name, &body)?;
+    let _ = state.save();
+    Ok(state.get_snippets())
+}
+
+#[tauri::command]
+pub fn delete_snippet(state: State<DbState>, id: String) -> Vec<Snippet> {
+    state.delete_snippet(&id);
+    let _ = state.save();
+    state.get_snippets()
+}
+
+/// Labels of the `{input:...}` placeholders the UI must prompt for before `paste_snippet`.
+#[tauri::command]
+pub fn snippet_inputs(state: State<DbState>, id: String) -> Result<Vec<String>, String> {
+    let snippet = state.get_snippet(&id).ok_or("Snippet not found")?;
+    crate::template::input_prompts(&snippet.body).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn paste_snippet(
+    app: AppHandle,
+    state: State<DbState>,
+    watcher: State<WatcherState>,
+    id: String,
+    inputs: HashMap<String, String>,
+) -> Result<(), TemplateError> {
+    let snippet = state.get_snippet(&id).ok_or(TemplateError::NotFound)?;
+    // Read before the expansion replaces the clipboard contents.
+    let clipboard = app.clipboard().read_text().unwrap_or_default();
+    let ctx = crate::template::TemplateContext {
+        clipboard: &clipboard,
+        inputs: &inputs,
+        now: chrono::Local::now(),
+    };
+    let expansion = crate::template::expand(&snippet.body, &ctx)?;
+    // Expansions are not copies; keep them out of the history.
+    watcher.skip_text(&expansion.text);
+    crate::paste::paste_text(&app, &expansion.text, expansion.cursor_back.unwrap_or(0));
+    Ok(())
 }
 
 #[tauri::command]
-pub fn get_settings(state: State<DbState>) -> crate::db::Settings {
+pub fn get_settings(state: State<DbState>) -> Settings {
     state.get_settings()
 }
 
 #[tauri::command]
-pub fn update_setting(app: AppHandle, state: State<DbState>, key: String, value: serde_json::Value) -> crate::db::Settings {
+pub fn update_setting(app: AppHandle, state: State<DbState>, key: String, value: serde_json::Value) -> Result<Settings, SettingsError> {
     // 1. Update State
-    state.update_setting(key.clone(), value.clone());
-    let _ = state.save();
-    
+    let previous = state.get_settings();
+    let settings = state.update_setting(&key, value)?;
+
     // 2. Handle Side Effects
-    if key == "useInternalShortcut" {
-        if let Some(enabled) = value.as_bool() {
-             let shortcut_str = if cfg!(target_os = "macos") { "Command+Control+V" } else { "Control+Alt+V" };
-             if let Ok(shortcut) = shortcut_str.parse::<Shortcut>() {
-                 if enabled {
-                     if let Err(e) = app.global_shortcut().register(shortcut) {
-                         eprintln!("Failed to register internal shortcut: {}", e);
-                     }
-                 } else {
-                     let _ = app.global_shortcut().unregister(shortcut);
-                 }
-             }
+    if matches!(key.as_str(), "useInternalShortcut" | "toggleShortcut" | "hotkeys") {
+        if let Err(message) = crate::shortcuts::apply(&app, &settings) {
+            state.set_settings(previous);
+            return Err(SettingsError::Shortcut { message });
         }
     }
 
-    state.get_settings()
+    let _ = state.save();
+    if matches!(key.as_str(), "ocr" | "ocrLanguages") {
+        crate::ocr::queue_missing(&app);
+    }
+    if key == "historyOrder" {
+        let _ = app.emit("clipboard-changed", state.get_history());
+    }
+    Ok(settings)
 }
 
 #[tauri::command]
@@ -153,51 +556,8 @@ pub fn paste_content(app: AppHandle, _state: State<DbState>, content: String) {
      // For now just write.
      let clip = app.clipboard();
      let _ = clip.write_text(content);
-     
-     if let Some(win) = app.get_webview_window("main") {
-            let _ = win.hide();
-     }
-     
-     tauri::async_runtime::spawn(async move {
-            std::thread::sleep(std::time::Duration::from_millis(100)); 
-             let shell = app.shell();
-             
-             // Detect active window class
-             let output = shell.command("xdotool")
-                .args(["getactivewindow", "getwindowclassname"])
-                .output()
-                .await;
-                
-             let mut is_terminal = false;
-             if let Ok(out) = output {
-                 if out.status.success() {
-                     let class_name = String::from_utf8_lossy(&out.stdout).to_lowercase();
-                     if class_name.contains("term") || 
-                        class_name.contains("alacritty") || 
-                        class_name.contains("kitty") ||
-                        class_name.contains("konsole") {
-                            is_terminal = true;
-                     }
-                 }
-             }
-
-             if is_terminal {
-                 let _ = shell.command("xdotool")
-                     .args(["key", "--clearmodifiers", "ctrl+shift+v"])
-                     .spawn();
-             } else {
-                 let _ = shell.command("xdotool")
-                     .args(["key", "--clearmodifiers", "ctrl+v"])
-                     .spawn();
-                 // Double paste hack? keeping it consistent with previous code if it was intentional, 
-                 // but previous code had double paste only in paste_content. 
-                 // I'll assume single paste is correct unless user complained about missed pastes.
-                 // The previous code had TWO spawn calls for ctrl+v in paste_content. 
-                 // I will mimic that just in case, but usually one is enough. 
-                 // Actually, looking at previous code, it spawned twice. 
-                 // Let's spawn once for now, simpler is better.
-             }
-    });
+
+     crate::paste::send_paste(app);
 }
 
 #[tauri::command]
@@ -238,8 +598,8 @@ pub fn show_window(window: tauri::WebviewWindow) {
     let state: State<DbState> = window.state();
     let settings = state.get_settings();
     
-    match settings.position.as_str() {
-         "cursor" => {
+    match settings.position {
+         WindowPosition::Cursor => {
               let position = Mouse::get_mouse_position();
               match position {
                   Mouse::Position { x, y } => {
@@ -248,10 +608,10 @@ pub fn show_window(window: tauri::WebviewWindow) {
                   _ => {}
               }
          }
-         "center" => {
+         WindowPosition::Center => {
              let _ = window.center(); 
          }
-         _ => {}
+         WindowPosition::Fixed => {}
     }
 
     // 4. Show Window
@@ -1,10 +1,17 @@
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::PathBuf;
 use std::sync::Mutex;
 use serde::{Serialize, Deserialize};
 use tauri::{AppHandle, Manager};
+use crate::archive::{Bundle, ImportMode, ImportSummary};
+use crate::barcode::DecodedCode;
+use crate::classify::{self, ContentKind, ContentMeta};
+use crate::imagehash;
+use crate::settings::{DedupStrategy, HistoryOrder, Settings, SettingsError, SettingsPatch};
+use crate::sync::SyncState;
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct ClipboardItem {
     pub id: String,
@@ -12,56 +19,393 @@ pub struct ClipboardItem {
     pub content: String,
     pub timestamp: i64,
     pub is_pinned: bool,
+    /// Ids of the user-defined tags attached to this item.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Items filed in a collection are never pruned by the history limit.
+    #[serde(default)]
+    pub collection_id: Option<String>,
+    /// Detected content kind; `None` for images.
+    #[serde(default)]
+    pub kind: Option<ContentKind>,
+    #[serde(default)]
+    pub meta: ContentMeta,
+    /// Other representations of the same content keyed by MIME type
+    /// (e.g. `text/html`, `text/rtf`).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub formats: BTreeMap<String, String>,
+    /// How many times this content was copied; repeats are counted instead of
+    /// added when dedup finds the existing item.
+    #[serde(default = "default_copy_count")]
+    pub copy_count: u32,
+    /// How many times this item was pasted (or typed out) from the history.
+    #[serde(default)]
+    pub paste_count: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_pasted_at: Option<i64>,
+    /// Images only; see `imagehash.rs`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub perceptual_hash: Option<u64>,
+    /// User-given name shown instead of the first line of the content.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// Earlier contents replaced by `update_item`, oldest (the original copy) first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub revisions: Vec<Revision>,
+    /// When the item deletes itself (and leaves the clipboard, if still there).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<i64>,
+    /// Passwords and the like; see `Settings::auto_clear`.
+    #[serde(default)]
+    pub is_sensitive: bool,
+    /// Text recognized in an image; empty when OCR found none.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ocr_text: Option<String>,
+    /// QR codes and barcodes found in an image; empty when the scan found none.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decoded: Option<Vec<DecodedCode>>,
+}
+
+/// The content of an item before an edit.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Revision {
+    pub content: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub formats: BTreeMap<String, String>,
+    /// When this content was replaced.
+    pub replaced_at: i64,
 }
 
+/// Changes made by `update_item`; missing fields are left alone and an empty
+/// title or note clears it.
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemUpdate {
+    pub title: Option<String>,
+    pub note: Option<String>,
+    pub content: Option<String>,
+    pub sensitive: Option<bool>,
+}
+
+/// The encoded bytes of an image item's base64 content. A `data:` URL prefix
+/// (e.g. `data:image/png;base64,`) is tolerated.
+pub fn image_bytes(content: &str) -> Option<Vec<u8>> {
+    use base64::Engine;
+    let b64 = content.split_once(',').map_or(content, |(_, data)| data);
+    base64::engine::general_purpose::STANDARD.decode(b64).ok()
+}
+
+fn default_copy_count() -> u32 {
+    1
+}
+
+/// Days after which a copy or paste counts half as much towards frecency.
+const FRECENCY_HALF_LIFE_DAYS: f64 = 7.0;
+/// A paste says more about what is useful than a copy.
+const PASTE_WEIGHT: f64 = 2.0;
+const DAY_MS: f64 = 86_400_000.0;
+
+impl ClipboardItem {
+    /// Fills in `kind` and `meta` from the text content.
+    pub fn classify(&mut self) {
+        let c = classify::classify(&self.content);
+        self.kind = Some(c.kind);
+        self.meta = c.meta;
+    }
+
+    /// Fills in the image hashes used by dedup.
+    pub fn hash_image(&mut self) {
+        if let Some(hashes) = imagehash::hash_image(&self.content) {
+            self.content_hash = Some(hashes.content);
+            self.perceptual_hash = Some(hashes.perceptual);
+        }
+    }
+
+    /// Replaces the text content, keeping the current one as a revision.
+    /// Other formats (HTML, RTF) no longer match the edit and are dropped.
+    fn replace_content(&mut self, content: String, now: i64) {
+        self.revisions.push(Revision {
+            content: std::mem::replace(&mut self.content, content),
+            formats: std::mem::take(&mut self.formats),
+            replaced_at: now,
+        });
+        // The original copy is always kept.
+        if self.revisions.len() > REVISION_LIMIT {
+            self.revisions.remove(1);
+        }
+        self.classify();
+    }
+
+    /// Uses (copies and weighted pastes) decayed by the time since the last one.
+    pub fn frecency(&self, now: i64) -> f64 {
+        let uses = self.copy_count as f64 + PASTE_WEIGHT * self.paste_count as f64;
+        let last_used = self.last_pasted_at.unwrap_or(self.timestamp).max(self.timestamp);
+        let age_days = (now - last_used).max(0) as f64 / DAY_MS;
+        uses * 0.5f64.powf(age_days / FRECENCY_HALF_LIFE_DAYS)
+    }
+
+    /// Whether `other` holds the same content. Images compare by hash when
+    /// both have one, and with `similar_images` by perceptual hash too.
+    fn is_duplicate_of(&self, other: &ClipboardItem, similar_images: bool) -> bool {
+        if self.r#type != other.r#type {
+            return false;
+        }
+        if self.content == other.content {
+            return true;
+        }
+        if let (Some(a), Some(b)) = (&self.content_hash, &other.content_hash) {
+            if a == b {
+                return true;
+            }
+        }
+        match (self.perceptual_hash, other.perceptual_hash) {
+            (Some(a), Some(b)) => similar_images && imagehash::is_similar(a, b),
+            _ => false,
+        }
+    }
+}
+
+/// A user-defined tag or collection (folder).
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
-pub struct Settings {
-    pub position: String,
-    pub grouping: String,
-    pub zoom: i32,
-    pub theme: String,
-    pub language: Option<String>,
-    #[serde(default)]
-    pub use_internal_shortcut: bool,
+pub struct Label {
+    pub id: String,
+    pub name: String,
 }
 
-impl Default for Settings {
-    fn default() -> Self {
-        Self {
-            position: "cursor".to_string(),
-            grouping: "categorized".to_string(),
-            zoom: 100,
-            theme: "dark".to_string(),
-            language: None,
-            use_internal_shortcut: false,
+pub type Tag = Label;
+pub type Collection = Label;
+
+/// A reusable text template, kept apart from the captured history.
+/// See `template.rs` for the placeholder syntax.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Snippet {
+    pub id: String,
+    pub name: String,
+    pub body: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// Optional filter for `get_history`; all given criteria must match.
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryFilter {
+    pub tag_id: Option<String>,
+    pub collection_id: Option<String>,
+    pub kind: Option<ContentKind>,
+}
+
+impl HistoryFilter {
+    pub fn matches(&self, item: &ClipboardItem) -> bool {
+        if let Some(tag) = &self.tag_id {
+            if !item.tags.contains(tag) {
+                return false;
+            }
         }
+        if let Some(collection) = &self.collection_id {
+            if item.collection_id.as_ref() != Some(collection) {
+                return false;
+            }
+        }
+        if let Some(kind) = self.kind {
+            if item.kind != Some(kind) {
+                return false;
+            }
+        }
+        true
     }
 }
 
+/// Usage figures for the statistics view.
+#[derive(Serialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Stats {
+    pub total_items: usize,
+    pub pinned_items: usize,
+    pub trashed_items: usize,
+    pub total_pastes: u64,
+    /// Copies of content that was already in the history.
+    pub total_recopies: u64,
+    /// Item count per type (`text`, `image`).
+    pub by_type: BTreeMap<String, usize>,
+    /// Text item count per detected kind.
+    pub by_kind: BTreeMap<ContentKind, usize>,
+    /// Pasted items, most pasted first.
+    pub most_pasted: Vec<ClipboardItem>,
+    /// Size of the database file on disk.
+    pub storage_bytes: u64,
+}
+
+const MOST_PASTED_LIMIT: usize = 10;
+
+/// A deleted item kept for `trash_retention_days` so it can be restored.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashedItem {
+    pub item: ClipboardItem,
+    pub deleted_at: i64,
+    /// Index in the history at deletion time, used to put it back in place.
+    pub position: usize,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, Default)]
 pub struct Database {
     pub history: Vec<ClipboardItem>,
     pub settings: Settings,
+    #[serde(default)]
+    pub trash: Vec<TrashedItem>,
+    #[serde(default)]
+    pub tags: Vec<Tag>,
+    #[serde(default)]
+    pub collections: Vec<Collection>,
+    #[serde(default)]
+    pub snippets: Vec<Snippet>,
+    #[serde(default)]
+    pub sync: SyncState,
+}
+
+const HISTORY_LIMIT: usize = 100;
+const REVISION_LIMIT: usize = 20;
+const UNDO_LIMIT: usize = 20;
+
+/// Keeps at most `HISTORY_LIMIT` items outside collections, dropping the oldest.
+pub(crate) fn prune(history: &mut Vec<ClipboardItem>) {
+    let mut unprotected = 0;
+    history.retain(|item| {
+        if item.collection_id.is_some() {
+            return true;
+        }
+        unprotected += 1;
+        unprotected <= HISTORY_LIMIT
+    });
+}
+
+/// Reorders unpinned items by descending frecency. Pinned items keep their
+/// positions; the remaining slots are refilled in score order.
+fn order_by_frecency(items: &mut [ClipboardItem], now: i64) {
+    let mut unpinned: Vec<ClipboardItem> = items.iter().filter(|i| !i.is_pinned).cloned().collect();
+    // Stable, so equal scores keep the newest-first order.
+    unpinned.sort_by(|a, b| b.frecency(now).total_cmp(&a.frecency(now)));
+    let mut sorted = unpinned.into_iter();
+    for slot in items.iter_mut().filter(|i| !i.is_pinned) {
+        if let Some(item) = sorted.next() {
+            *slot = item;
+        }
+    }
+}
+
+/// Inserts `item` before the first item older than it, keeping the newest-first order.
+pub(crate) fn insert_by_timestamp(history: &mut Vec<ClipboardItem>, item: ClipboardItem) {
+    let index = history
+        .iter()
+        .position(|i| i.timestamp < item.timestamp)
+        .unwrap_or(history.len());
+    history.insert(index, item);
+}
+
+/// Rejects empty names and names already used by another label (case-insensitive).
+fn check_label_name(labels: &[Label], name: &str, except_id: Option<&str>) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Name cannot be empty".to_string());
+    }
+    let taken = labels.iter().any(|l| {
+        l.name.to_lowercase() == name.to_lowercase() && Some(l.id.as_str()) != except_id
+    });
+    if taken {
+        return Err(format!("\"{}\" already exists", name));
+    }
+    Ok(name.to_string())
+}
+
+fn create_label(labels: &mut Vec<Label>, name: &str) -> Result<Label, String> {
+    let name = check_label_name(labels, name, None)?;
+    let label = Label { id: uuid::Uuid::new_v4().to_string(), name };
+    labels.push(label.clone());
+    Ok(label)
+}
+
+/// Maps each label in `incoming` to the id of the label with the same name in
+/// `labels`, creating the missing ones. Returns the id map and how many were created.
+fn merge_labels(labels: &mut Vec<Label>, incoming: &[Label]) -> (BTreeMap<String, String>, usize) {
+    let mut ids = BTreeMap::new();
+    let mut created = 0;
+    for label in incoming {
+        let name = label.name.trim().to_lowercase();
+        let id = match labels.iter().find(|l| l.name.to_lowercase() == name) {
+            Some(existing) => existing.id.clone(),
+            None => match create_label(labels, &label.name) {
+                Ok(new) => {
+                    created += 1;
+                    new.id
+                }
+                Err(_) => continue,
+            },
+        };
+        ids.insert(label.id.clone(), id);
+    }
+    (ids, created)
+}
+
+fn rename_label(labels: &mut [Label], id: &str, name: &str) -> Result<(), String> {
+    let name = check_label_name(labels, name, Some(id))?;
+    let label = labels.iter_mut().find(|l| l.id == id).ok_or("Not found")?;
+    label.name = name;
+    Ok(())
+}
+
+/// A reversible history operation.
+#[derive(Clone, Debug)]
+enum UndoAction {
+    /// Items moved to the trash by `delete_item` or `clear_all`.
+    Trash { ids: Vec<String> },
+    /// History order before `reorder_items`.
+    Reorder { order: Vec<String> },
+    TogglePin { id: String },
+    /// The item before `update_item` or `restore_revision`.
+    Update { before: Box<ClipboardItem> },
 }
 
 pub struct DbState {
     pub db: Mutex<Database>,
     pub path: PathBuf,
+    undo: Mutex<Vec<UndoAction>>,
 }
 
 impl DbState {
     pub fn new(app: &AppHandle) -> Self {
         let path = app.path().app_config_dir().expect("failed to get app config dir").join("db.json");
-        let db = if path.exists() {
+        let mut db: Database = if path.exists() {
             let content = fs::read_to_string(&path).unwrap_or_default();
             serde_json::from_str(&content).unwrap_or_default()
         } else {
             Database::default()
         };
-        Self {
+        // Items captured before classification existed.
+        for item in db.history.iter_mut().filter(|i| i.r#type == "text" && i.kind.is_none()) {
+            item.classify();
+        }
+        let state = Self {
             db: Mutex::new(db),
             path,
+            undo: Mutex::new(Vec::new()),
+        };
+        state.purge_trash();
+        state
+    }
+
+    fn push_undo(&self, action: UndoAction) {
+        let mut undo = self.undo.lock().unwrap();
+        undo.push(action);
+        if undo.len() > UNDO_LIMIT {
+            undo.remove(0);
         }
     }
 
@@ -77,88 +421,584 @@ impl DbState {
         Ok(())
     }
     
+    /// The history in display order (see `Settings::history_order`).
     pub fn get_history(&self) -> Vec<ClipboardItem> {
+        self.get_history_filtered(&HistoryFilter::default())
+    }
+
+    pub fn get_item(&self, id: &str) -> Option<ClipboardItem> {
+        let db = self.db.lock().unwrap();
+        db.history.iter().find(|i| i.id == id).cloned()
+    }
+
+    pub fn get_history_filtered(&self, filter: &HistoryFilter) -> Vec<ClipboardItem> {
         let db = self.db.lock().unwrap();
-        db.history.clone()
+        let mut items: Vec<ClipboardItem> =
+            db.history.iter().filter(|item| filter.matches(item)).cloned().collect();
+        if db.settings.history_order == HistoryOrder::Frecency {
+            order_by_frecency(&mut items, chrono::Utc::now().timestamp_millis());
+        }
+        items
+    }
+
+    /// Counts a paste of item `id`. Returns false if it is no longer in the history.
+    pub fn record_paste(&self, id: &str, now: i64) -> bool {
+        let mut db = self.db.lock().unwrap();
+        let Some(item) = db.history.iter_mut().find(|i| i.id == id) else {
+            return false;
+        };
+        item.paste_count += 1;
+        item.last_pasted_at = Some(now);
+        true
+    }
+
+    pub fn get_stats(&self) -> Stats {
+        let db = self.db.lock().unwrap();
+        let mut stats = Stats {
+            total_items: db.history.len(),
+            trashed_items: db.trash.len(),
+            storage_bytes: fs::metadata(&self.path).map(|m| m.len()).unwrap_or(0),
+            ..Default::default()
+        };
+        for item in &db.history {
+            if item.is_pinned {
+                stats.pinned_items += 1;
+            }
+            stats.total_pastes += item.paste_count as u64;
+            stats.total_recopies += item.copy_count.saturating_sub(1) as u64;
+            *stats.by_type.entry(item.r#type.clone()).or_default() += 1;
+            if let Some(kind) = item.kind {
+                *stats.by_kind.entry(kind).or_default() += 1;
+            }
+        }
+        let mut pasted: Vec<&ClipboardItem> = db.history.iter().filter(|i| i.paste_count > 0).collect();
+        pasted.sort_by_key(|i| std::cmp::Reverse((i.paste_count, i.last_pasted_at)));
+        stats.most_pasted = pasted.into_iter().take(MOST_PASTED_LIMIT).cloned().collect();
+        stats
     }
     
-    pub fn add_item(&self, item: ClipboardItem) {
+    /// Adds a captured item, or counts it against an existing copy as set by
+    /// the dedup strategy. Returns the id of the item at the top of the
+    /// history afterwards, or `None` when it was a duplicate left in place.
+    pub fn add_item(&self, mut item: ClipboardItem) -> Option<String> {
+        if item.r#type == "image" && item.content_hash.is_none() {
+            item.hash_image();
+        }
+        item.copy_count = item.copy_count.max(1);
+
         let mut db = self.db.lock().unwrap();
-        // Dedup
-        if let Some(first) = db.history.first() {
-            if first.content == item.content && first.r#type == item.r#type {
-                return;
+        let similar = db.settings.similar_images;
+        let strategy = db.settings.dedup;
+        let existing = match strategy {
+            DedupStrategy::NewestOnly => db
+                .history
+                .first()
+                .filter(|first| first.is_duplicate_of(&item, similar))
+                .map(|_| 0),
+            DedupStrategy::MoveToTop | DedupStrategy::Never => {
+                db.history.iter().position(|i| i.is_duplicate_of(&item, similar))
             }
+        };
+
+        let Some(index) = existing else {
+            let id = item.id.clone();
+            db.history.insert(0, item);
+            prune(&mut db.history);
+            return Some(id);
+        };
+        db.history[index].copy_count += 1;
+        db.history[index].is_sensitive |= item.is_sensitive;
+        if strategy != DedupStrategy::MoveToTop || index == 0 {
+            return None;
         }
+        let mut moved = db.history.remove(index);
+        moved.timestamp = item.timestamp;
+        let id = moved.id.clone();
+        db.history.insert(0, moved);
+        Some(id)
+    }
+
+    /// Adds an item the user made on purpose (e.g. an edited image) at the top
+    /// of the history, skipping dedup: it must not be counted against, or
+    /// moved in place of, the item it was made from.
+    pub fn insert_item(&self, mut item: ClipboardItem) {
+        if item.r#type == "image" && item.content_hash.is_none() {
+            item.hash_image();
+        }
+        item.copy_count = item.copy_count.max(1);
+        let mut db = self.db.lock().unwrap();
         db.history.insert(0, item);
-        if db.history.len() > 100 {
-            db.history.truncate(100);
+        prune(&mut db.history);
+    }
+
+    /// Hashes images captured before dedup compared them. Decoding a large
+    /// history takes a while, so this runs off the startup path and without
+    /// holding the lock while hashing. Returns how many images were hashed.
+    pub fn hash_missing_images(&self) -> usize {
+        let pending: Vec<ClipboardItem> = {
+            let db = self.db.lock().unwrap();
+            db.history.iter().filter(|i| i.r#type == "image" && i.content_hash.is_none()).cloned().collect()
+        };
+        let mut hashed = 0;
+        for mut image in pending {
+            image.hash_image();
+            if image.content_hash.is_none() {
+                continue;
+            }
+            let mut db = self.db.lock().unwrap();
+            if let Some(item) = db.history.iter_mut().find(|i| i.id == image.id && i.content == image.content) {
+                item.content_hash = image.content_hash;
+                item.perceptual_hash = image.perceptual_hash;
+                hashed += 1;
+            }
         }
+        hashed
     }
 
     pub fn delete_item(&self, id: &str) {
         let mut db = self.db.lock().unwrap();
         if let Some(index) = db.history.iter().position(|x| x.id == id) {
-            db.history.remove(index);
+            let item = db.history.remove(index);
+            db.trash.push(TrashedItem {
+                item,
+                deleted_at: chrono::Utc::now().timestamp_millis(),
+                position: index,
+            });
+            drop(db);
+            self.push_undo(UndoAction::Trash { ids: vec![id.to_string()] });
         }
+        self.purge_trash();
     }
 
     pub fn clear_all(&self) {
         let mut db = self.db.lock().unwrap();
-        db.history.retain(|x| x.is_pinned);
+        let now = chrono::Utc::now().timestamp_millis();
+        let history = std::mem::take(&mut db.history);
+        let mut ids = Vec::new();
+        for (position, item) in history.into_iter().enumerate() {
+            if item.is_pinned {
+                db.history.push(item);
+            } else {
+                ids.push(item.id.clone());
+                db.trash.push(TrashedItem { item, deleted_at: now, position });
+            }
+        }
+        drop(db);
+        if !ids.is_empty() {
+            self.push_undo(UndoAction::Trash { ids });
+        }
+        self.purge_trash();
     }
     
     pub fn toggle_pin(&self, id: &str) {
          let mut db = self.db.lock().unwrap();
          if let Some(item) = db.history.iter_mut().find(|x| x.id == id) {
              item.is_pinned = !item.is_pinned;
+             db.sync.touch_item(id, chrono::Utc::now().timestamp_millis());
+             drop(db);
+             self.push_undo(UndoAction::TogglePin { id: id.to_string() });
          }
     }
 
-    pub fn get_settings(&self) -> Settings {
+    /// Sets the title and note and edits the text content of item `id`.
+    pub fn update_item(&self, id: &str, update: ItemUpdate, now: i64) -> Result<(), String> {
+        let mut db = self.db.lock().unwrap();
+        let item = db.history.iter_mut().find(|x| x.id == id).ok_or("Item not found")?;
+        let content = update.content.filter(|c| *c != item.content);
+        if let Some(content) = &content {
+            if item.r#type != "text" {
+                return Err("Only text items can be edited".to_string());
+            }
+            if content.is_empty() {
+                return Err("Content cannot be empty".to_string());
+            }
+        }
+        let before = item.clone();
+        if let Some(title) = update.title {
+            item.title = Some(title.trim().to_string()).filter(|t| !t.is_empty());
+        }
+        if let Some(note) = update.note {
+            item.note = Some(note).filter(|n| !n.trim().is_empty());
+        }
+        if let Some(sensitive) = update.sensitive {
+            item.is_sensitive = sensitive;
+        }
+        if let Some(content) = content {
+            item.replace_content(content, now);
+        }
+        let changed = item.title != before.title
+            || item.note != before.note
+            || item.is_sensitive != before.is_sensitive
+            || item.content != before.content;
+        if !changed {
+            return Ok(());
+        }
+        db.sync.touch_item(id, now);
+        drop(db);
+        self.push_undo(UndoAction::Update { before: Box::new(before) });
+        Ok(())
+    }
+
+    /// Brings back revision `index` of item `id`; the current content becomes
+    /// the newest revision, so restoring can itself be reverted.
+    pub fn restore_revision(&self, id: &str, index: usize, now: i64) -> Result<(), String> {
+        let mut db = self.db.lock().unwrap();
+        let item = db.history.iter_mut().find(|x| x.id == id).ok_or("Item not found")?;
+        let revision = item.revisions.get(index).cloned().ok_or("Revision not found")?;
+        let before = item.clone();
+        item.replace_content(revision.content, now);
+        item.formats = revision.formats;
+        db.sync.touch_item(id, now);
+        drop(db);
+        self.push_undo(UndoAction::Update { before: Box::new(before) });
+        Ok(())
+    }
+
+    /// Whether `item`, or an item in the history with the same content, is
+    /// marked sensitive.
+    pub fn is_sensitive(&self, item: &ClipboardItem) -> bool {
         let db = self.db.lock().unwrap();
-        db.settings.clone()
+        item.is_sensitive
+            || db.history.iter().any(|i| {
+                i.is_sensitive && i.r#type == item.r#type && i.content == item.content
+            })
     }
 
-    pub fn update_setting(&self, key: String, value: serde_json::Value) {
+    /// Stores the OCR result for image item `id`. Returns false if it is gone.
+    pub fn set_ocr_text(&self, id: &str, text: String) -> bool {
         let mut db = self.db.lock().unwrap();
-        // Use serde_json to update generic value if possible, or match key
-        match key.as_str() {
-            "zoom" => {
-                if let Some(v) = value.as_i64() {
-                    db.settings.zoom = v as i32;
-                }
+        let Some(item) = db.history.iter_mut().find(|i| i.id == id) else {
+            return false;
+        };
+        item.ocr_text = Some(text);
+        true
+    }
+
+    /// Attaches the codes found in image item `id`. Returns false if it is gone.
+    pub fn set_decoded(&self, id: &str, codes: Vec<DecodedCode>) -> bool {
+        let mut db = self.db.lock().unwrap();
+        let Some(item) = db.history.iter_mut().find(|i| i.id == id) else {
+            return false;
+        };
+        item.decoded = Some(codes);
+        true
+    }
+
+    /// Sets or clears (`None`) the time at which item `id` is deleted.
+    pub fn set_item_expiry(&self, id: &str, at: Option<i64>, now: i64) -> Result<(), String> {
+        if at.is_some_and(|at| at <= now) {
+            return Err("Expiry must be in the future".to_string());
+        }
+        let mut db = self.db.lock().unwrap();
+        let item = db.history.iter_mut().find(|x| x.id == id).ok_or("Item not found")?;
+        item.expires_at = at;
+        db.sync.touch_item(id, now);
+        Ok(())
+    }
+
+    /// Removes and returns the items that expired by `now`, from the history
+    /// and the trash. They skip the trash: expiring content (codes,
+    /// passwords) should not linger.
+    pub fn take_expired(&self, now: i64) -> Vec<ClipboardItem> {
+        let mut db = self.db.lock().unwrap();
+        let is_expired = |item: &ClipboardItem| item.expires_at.is_some_and(|at| at <= now);
+        let (mut expired, kept): (Vec<_>, Vec<_>) =
+            std::mem::take(&mut db.history).into_iter().partition(|item| is_expired(item));
+        db.history = kept;
+        let (trashed, kept): (Vec<_>, Vec<_>) =
+            std::mem::take(&mut db.trash).into_iter().partition(|t| is_expired(&t.item));
+        db.trash = kept;
+        expired.extend(trashed.into_iter().map(|t| t.item));
+        expired
+    }
+
+    pub fn get_trash(&self) -> Vec<TrashedItem> {
+        let db = self.db.lock().unwrap();
+        db.trash.clone()
+    }
+
+    /// Moves trashed items back into the history at their original positions.
+    /// Returns how many items were restored.
+    fn restore_from_trash(db: &mut Database, ids: &[String]) -> usize {
+        let mut restored: Vec<TrashedItem> = Vec::new();
+        db.trash.retain(|t| {
+            if ids.contains(&t.item.id) {
+                restored.push(t.clone());
+                false
+            } else {
+                true
             }
-            "position" => {
-                if let Some(v) = value.as_str() {
-                    db.settings.position = v.to_string();
-                }
+        });
+        // Ascending positions, so earlier inserts keep later indices valid.
+        restored.sort_by_key(|t| t.position);
+        let count = restored.len();
+        for trashed in restored {
+            let index = trashed.position.min(db.history.len());
+            db.history.insert(index, trashed.item);
+        }
+        count
+    }
+
+    pub fn restore_item(&self, id: &str) -> bool {
+        let mut db = self.db.lock().unwrap();
+        Self::restore_from_trash(&mut db, &[id.to_string()]) > 0
+    }
+
+    pub fn empty_trash(&self) {
+        let mut db = self.db.lock().unwrap();
+        db.trash.clear();
+    }
+
+    /// Drops trashed items older than the configured retention window.
+    pub fn purge_trash(&self) {
+        let mut db = self.db.lock().unwrap();
+        let retention_ms = db.settings.trash_retention_days as i64 * 24 * 60 * 60 * 1000;
+        let cutoff = chrono::Utc::now().timestamp_millis() - retention_ms;
+        db.trash.retain(|t| t.deleted_at >= cutoff);
+    }
+
+    /// Reverts the most recent delete, clear, reorder or pin toggle.
+    /// Returns false when there is nothing left to undo.
+    pub fn undo_last_action(&self) -> bool {
+        let mut db = self.db.lock().unwrap();
+        let action = loop {
+            match self.undo.lock().unwrap().pop() {
+                // Its items were restored or purged since; undo the action before it.
+                Some(UndoAction::Trash { ids }) if !db.trash.iter().any(|t| ids.contains(&t.item.id)) => {}
+                Some(action) => break action,
+                None => return false,
             }
-            "grouping" => {
-                if let Some(v) = value.as_str() {
-                    db.settings.grouping = v.to_string();
-                }
+        };
+        let now = chrono::Utc::now().timestamp_millis();
+        match action {
+            UndoAction::Trash { ids } => {
+                Self::restore_from_trash(&mut db, &ids);
             }
-            "theme" => {
-                if let Some(v) = value.as_str() {
-                    db.settings.theme = v.to_string();
+            UndoAction::Reorder { order } => {
+                // Items that appeared since the reorder keep their place at the front.
+                db.history.sort_by_key(|item| {
+                    order.iter().position(|id| *id == item.id).map_or(0, |i| i + 1)
+                });
+            }
+            UndoAction::TogglePin { id } => {
+                if let Some(item) = db.history.iter_mut().find(|x| x.id == id) {
+                    item.is_pinned = !item.is_pinned;
+                    db.sync.touch_item(&id, now);
                 }
             }
-            "language" => {
-                if let Some(v) = value.as_str() {
-                    db.settings.language = Some(v.to_string());
-                } else if value.is_null() {
-                    db.settings.language = None;
+            UndoAction::Update { before } => {
+                if let Some(item) = db.history.iter_mut().find(|x| x.id == before.id) {
+                    item.title = before.title;
+                    item.note = before.note;
+                    item.is_sensitive = before.is_sensitive;
+                    item.content = before.content;
+                    item.formats = before.formats;
+                    item.revisions = before.revisions;
+                    item.kind = before.kind;
+                    item.meta = before.meta;
+                    db.sync.touch_item(&before.id, now);
                 }
             }
+        }
+        true
+    }
+
+    pub fn get_settings(&self) -> Settings {
+        let db = self.db.lock().unwrap();
+        db.settings.clone()
+    }
+
+    pub fn set_settings(&self, settings: Settings) {
+        let mut db = self.db.lock().unwrap();
+        db.settings = settings;
+    }
+
+    /// Applies a single validated setting and returns the updated settings.
+    /// Nothing is changed when the key or value is rejected.
+    pub fn update_setting(&self, key: &str, value: serde_json::Value) -> Result<Settings, SettingsError> {
+        let patch = SettingsPatch::from_entry(key, value)?;
+        let mut db = self.db.lock().unwrap();
+        let updated = patch.apply_to(&db.settings)?;
+        db.settings = updated.clone();
+        Ok(updated)
+    }
+
+    pub fn get_tags(&self) -> Vec<Tag> {
+        let db = self.db.lock().unwrap();
+        db.tags.clone()
+    }
 
-            "useInternalShortcut" => {
-                 if let Some(v) = value.as_bool() {
-                     db.settings.use_internal_shortcut = v;
-                 }
+    pub fn create_tag(&self, name: &str) -> Result<Tag, String> {
+        let mut db = self.db.lock().unwrap();
+        create_label(&mut db.tags, name)
+    }
+
+    pub fn rename_tag(&self, id: &str, name: &str) -> Result<(), String> {
+        let mut db = self.db.lock().unwrap();
+        rename_label(&mut db.tags, id, name)
+    }
+
+    /// Deletes the tag and detaches it from every item, trashed ones included.
+    pub fn delete_tag(&self, id: &str) {
+        let mut guard = self.db.lock().unwrap();
+        let db = &mut *guard;
+        db.tags.retain(|t| t.id != id);
+        for item in db.history.iter_mut().chain(db.trash.iter_mut().map(|t| &mut t.item)) {
+            item.tags.retain(|t| t != id);
+        }
+    }
+
+    pub fn set_item_tags(&self, id: &str, tag_ids: Vec<String>) -> Result<(), String> {
+        let mut db = self.db.lock().unwrap();
+        if let Some(unknown) = tag_ids.iter().find(|t| !db.tags.iter().any(|tag| &tag.id == *t)) {
+            return Err(format!("Unknown tag {}", unknown));
+        }
+        let item = db.history.iter_mut().find(|x| x.id == id).ok_or("Item not found")?;
+        item.tags = tag_ids;
+        item.tags.sort();
+        item.tags.dedup();
+        Ok(())
+    }
+
+    pub fn get_collections(&self) -> Vec<Collection> {
+        let db = self.db.lock().unwrap();
+        db.collections.clone()
+    }
+
+    pub fn create_collection(&self, name: &str) -> Result<Collection, String> {
+        let mut db = self.db.lock().unwrap();
+        create_label(&mut db.collections, name)
+    }
+
+    pub fn rename_collection(&self, id: &str, name: &str) -> Result<(), String> {
+        let mut db = self.db.lock().unwrap();
+        rename_label(&mut db.collections, id, name)?;
+        db.sync.touch_collection(id, chrono::Utc::now().timestamp_millis());
+        Ok(())
+    }
+
+    /// Deletes the collection; its items stay in the history (or trash), unfiled.
+    pub fn delete_collection(&self, id: &str) {
+        let mut guard = self.db.lock().unwrap();
+        let db = &mut *guard;
+        db.collections.retain(|c| c.id != id);
+        for item in db.history.iter_mut().chain(db.trash.iter_mut().map(|t| &mut t.item)) {
+            if item.collection_id.as_deref() == Some(id) {
+                item.collection_id = None;
+            }
+        }
+    }
+
+    pub fn set_item_collection(&self, id: &str, collection_id: Option<String>) -> Result<(), String> {
+        let mut db = self.db.lock().unwrap();
+        if let Some(collection) = &collection_id {
+            if !db.collections.iter().any(|c| &c.id == collection) {
+                return Err(format!("Unknown collection {}", collection));
+            }
+        }
+        let item = db.history.iter_mut().find(|x| x.id == id).ok_or("Item not found")?;
+        item.collection_id = collection_id;
+        db.sync.touch_item(id, chrono::Utc::now().timestamp_millis());
+        Ok(())
+    }
+
+    pub fn get_snippets(&self) -> Vec<Snippet> {
+        let db = self.db.lock().unwrap();
+        db.snippets.clone()
+    }
+
+    pub fn get_snippet(&self, id: &str) -> Option<Snippet> {
+        let db = self.db.lock().unwrap();
+        db.snippets.iter().find(|s| s.id == id).cloned()
+    }
+
+    pub fn create_snippet(&self, name: &str, body: &str) -> Result<Snippet, String> {
+        crate::template::input_prompts(body).map_err(|e| e.to_string())?;
+        let now = chrono::Utc::now().timestamp_millis();
+        let snippet = Snippet {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: name.trim().to_string(),
+            body: body.to_string(),
+            created_at: now,
+            updated_at: now,
+        };
+        let mut db = self.db.lock().unwrap();
+        db.snippets.push(snippet.clone());
+        Ok(snippet)
+    }
+
+    pub fn update_snippet(&self, id: &str, name: &str, body: &str) -> Result<(), String> {
+        crate::template::input_prompts(body).map_err(|e| e.to_string())?;
+        let mut db = self.db.lock().unwrap();
+        let snippet = db.snippets.iter_mut().find(|s| s.id == id).ok_or("Snippet not found")?;
+        snippet.name = name.trim().to_string();
+        snippet.body = body.to_string();
+        snippet.updated_at = chrono::Utc::now().timestamp_millis();
+        Ok(())
+    }
+
+    pub fn delete_snippet(&self, id: &str) {
+        let mut db = self.db.lock().unwrap();
+        db.snippets.retain(|s| s.id != id);
+    }
+
+    /// Adds the items of an exported bundle. Tags and collections are matched
+    /// by name and created when missing; both modes keep existing labels.
+    pub fn import_bundle(&self, bundle: Bundle, mode: ImportMode) -> ImportSummary {
+        let mut summary = ImportSummary::default();
+        let mut db = self.db.lock().unwrap();
+
+        if mode == ImportMode::Replace {
+            let now = chrono::Utc::now().timestamp_millis();
+            let history = std::mem::take(&mut db.history);
+            let ids: Vec<String> = history.iter().map(|i| i.id.clone()).collect();
+            summary.replaced = ids.len();
+            for (position, item) in history.into_iter().enumerate() {
+                db.trash.push(TrashedItem { item, deleted_at: now, position });
+            }
+            if !ids.is_empty() {
+                self.push_undo(UndoAction::Trash { ids });
+            }
+        }
+
+        let (tag_ids, tags_created) = merge_labels(&mut db.tags, &bundle.tags);
+        let (collection_ids, collections_created) = merge_labels(&mut db.collections, &bundle.collections);
+        summary.tags_created = tags_created;
+        summary.collections_created = collections_created;
+
+        for mut item in bundle.items {
+            if item.content.is_empty() || !matches!(item.r#type.as_str(), "text" | "image") {
+                summary.skipped_invalid += 1;
+                continue;
+            }
+            let duplicate = db.history.iter().any(|i| {
+                i.id == item.id || (i.r#type == item.r#type && i.content == item.content)
+            });
+            if duplicate {
+                summary.skipped_duplicates += 1;
+                continue;
+            }
+            // Undoing a replace restores the old items; their ids must stay unique.
+            if db.trash.iter().any(|t| t.item.id == item.id) {
+                item.id = uuid::Uuid::new_v4().to_string();
+            }
+            item.tags = item.tags.iter().filter_map(|id| tag_ids.get(id).cloned()).collect();
+            item.collection_id = item.collection_id.and_then(|id| collection_ids.get(&id).cloned());
+            if item.r#type == "text" {
+                item.classify();
+            } else {
+                item.kind = None;
+                item.hash_image();
             }
-            _ => {}
+            item.copy_count = item.copy_count.max(1);
+            insert_by_timestamp(&mut db.history, item);
+            summary.added += 1;
         }
+
+        let before = db.history.len();
+        prune(&mut db.history);
+        summary.pruned = before - db.history.len();
+        summary
     }
 
     pub fn reorder_items(&self, active_id: &str, over_id: &str) {
@@ -167,8 +1007,448 @@ impl DbState {
         let new_index = db.history.iter().position(|r| r.id == over_id);
 
         if let (Some(old), Some(new)) = (old_index, new_index) {
+            let order = db.history.iter().map(|item| item.id.clone()).collect();
             let item = db.history.remove(old);
             db.history.insert(new, item);
+            drop(db);
+            self.push_undo(UndoAction::Reorder { order });
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(db: Database) -> DbState {
+        DbState {
+            db: Mutex::new(db),
+            path: PathBuf::new(),
+            undo: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn text(id: &str, content: &str, timestamp: i64) -> ClipboardItem {
+        let mut item = ClipboardItem {
+            id: id.to_string(),
+            r#type: "text".to_string(),
+            content: content.to_string(),
+            timestamp,
+            copy_count: 1,
+            ..Default::default()
+        };
+        item.classify();
+        item
+    }
+
+    fn ids(items: &[ClipboardItem]) -> Vec<&str> {
+        items.iter().map(|i| i.id.as_str()).collect()
+    }
+
+    #[test]
+    fn filter_requires_every_criterion() {
+        let mut item = text("a", "https://example.com", 0);
+        item.tags = vec!["t1".to_string(), "t2".to_string()];
+        item.collection_id = Some("c1".to_string());
+
+        assert!(HistoryFilter::default().matches(&item));
+        let by_tag = HistoryFilter { tag_id: Some("t2".to_string()), ..Default::default() };
+        assert!(by_tag.matches(&item));
+        let both = HistoryFilter {
+            tag_id: Some("t1".to_string()),
+            collection_id: Some("c2".to_string()),
+            ..Default::default()
+        };
+        assert!(!both.matches(&item));
+        let by_kind = HistoryFilter { kind: Some(ContentKind::Url), ..Default::default() };
+        assert!(by_kind.matches(&item));
+        let other_kind = HistoryFilter { kind: Some(ContentKind::Email), ..Default::default() };
+        assert!(!other_kind.matches(&item));
+    }
+
+    #[test]
+    fn prune_keeps_filed_items_beyond_the_limit() {
+        let mut history: Vec<ClipboardItem> =
+            (0..HISTORY_LIMIT + 5).map(|i| text(&i.to_string(), "x", -(i as i64))).collect();
+        history[HISTORY_LIMIT + 2].collection_id = Some("c".to_string());
+        prune(&mut history);
+        assert_eq!(history.len(), HISTORY_LIMIT + 1);
+        assert_eq!(history.last().unwrap().id, (HISTORY_LIMIT + 2).to_string());
+        assert_eq!(history[HISTORY_LIMIT - 1].id, (HISTORY_LIMIT - 1).to_string());
+    }
+
+    #[test]
+    fn deleting_labels_cleans_trashed_items() {
+        let mut item = text("a", "hello", 0);
+        item.tags = vec!["t".to_string()];
+        item.collection_id = Some("c".to_string());
+        let state = state(Database {
+            history: vec![item],
+            tags: vec![Label { id: "t".to_string(), name: "Tag".to_string() }],
+            collections: vec![Label { id: "c".to_string(), name: "Folder".to_string() }],
+            ..Default::default()
+        });
+        state.delete_item("a");
+        state.delete_tag("t");
+        state.delete_collection("c");
+        assert!(state.undo_last_action());
+
+        let restored = state.get_item("a").unwrap();
+        assert!(restored.tags.is_empty());
+        assert_eq!(restored.collection_id, None);
+    }
+
+    #[test]
+    fn filtered_history_keeps_display_order() {
+        let mut a = text("a", "one", 3);
+        a.tags = vec!["t".to_string()];
+        let b = text("b", "two", 2);
+        let mut c = text("c", "three", 1);
+        c.tags = vec!["t".to_string()];
+        let state = state(Database { history: vec![a, b, c], ..Default::default() });
+        let filter = HistoryFilter { tag_id: Some("t".to_string()), ..Default::default() };
+        assert_eq!(ids(&state.get_history_filtered(&filter)), ["a", "c"]);
+    }
+
+    fn with_dedup(strategy: DedupStrategy, history: Vec<ClipboardItem>) -> DbState {
+        let mut db = Database { history, ..Default::default() };
+        db.settings.dedup = strategy;
+        state(db)
+    }
+
+    #[test]
+    fn newest_only_drops_a_repeat_of_the_newest_item() {
+        let state = with_dedup(DedupStrategy::NewestOnly, vec![text("a", "one", 2), text("b", "two", 1)]);
+        assert_eq!(state.add_item(text("c", "one", 3)), None);
+        assert_eq!(state.get_item("a").unwrap().copy_count, 2);
+
+        // Older items are copied again as new entries.
+        assert_eq!(state.add_item(text("d", "two", 4)).as_deref(), Some("d"));
+        assert_eq!(ids(&state.get_history()), ["d", "a", "b"]);
+    }
+
+    #[test]
+    fn move_to_top_keeps_pin_tags_and_count() {
+        let mut b = text("b", "two", 1);
+        b.is_pinned = true;
+        b.tags = vec!["t".to_string()];
+        b.copy_count = 3;
+        let state = with_dedup(DedupStrategy::MoveToTop, vec![text("a", "one", 2), b]);
+
+        assert_eq!(state.add_item(text("c", "two", 5)).as_deref(), Some("b"));
+        let history = state.get_history();
+        assert_eq!(ids(&history), ["b", "a"]);
+        assert!(history[0].is_pinned);
+        assert_eq!(history[0].tags, ["t"]);
+        assert_eq!(history[0].copy_count, 4);
+        assert_eq!(history[0].timestamp, 5);
+
+        // A repeat of the newest item stays where it is.
+        assert_eq!(state.add_item(text("d", "two", 6)), None);
+        assert_eq!(state.get_item("b").unwrap().copy_count, 5);
+    }
+
+    #[test]
+    fn never_counts_a_repeat_in_place() {
+        let state = with_dedup(DedupStrategy::Never, vec![text("a", "one", 2), text("b", "two", 1)]);
+        let mut repeat = text("c", "two", 3);
+        repeat.is_sensitive = true;
+        assert_eq!(state.add_item(repeat), None);
+        let history = state.get_history();
+        assert_eq!(ids(&history), ["a", "b"]);
+        assert_eq!(history[1].copy_count, 2);
+        assert!(history[1].is_sensitive);
+    }
+
+    #[test]
+    fn frecency_weighs_pastes_and_halves_per_half_life() {
+        let mut item = text("a", "one", 0);
+        assert_eq!(item.frecency(0), 1.0);
+        item.paste_count = 2;
+        item.last_pasted_at = Some(DAY_MS as i64);
+        let fresh = item.frecency(DAY_MS as i64);
+        assert_eq!(fresh, 1.0 + 2.0 * PASTE_WEIGHT);
+        let later = item.frecency((DAY_MS * (1.0 + FRECENCY_HALF_LIFE_DAYS)) as i64);
+        assert!((later - fresh / 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn frecency_order_keeps_pinned_slots() {
+        let mut pinned = text("pinned", "p", 4);
+        pinned.is_pinned = true;
+        let mut used = text("used", "u", 1);
+        used.paste_count = 5;
+        let mut items = vec![text("new", "n", 5), pinned, text("old", "o", 3), used];
+        order_by_frecency(&mut items, 10);
+        assert_eq!(ids(&items), ["used", "pinned", "new", "old"]);
+    }
+
+    #[test]
+    fn frecency_setting_orders_the_history() {
+        // Recent, so the scores haven't decayed to zero.
+        let now = chrono::Utc::now().timestamp_millis();
+        let mut used = text("used", "u", now - 2000);
+        used.paste_count = 1;
+        let mut db = Database { history: vec![text("new", "n", now - 1000), used], ..Default::default() };
+        assert_eq!(ids(&state(db.clone()).get_history()), ["new", "used"]);
+        db.settings.history_order = HistoryOrder::Frecency;
+        assert_eq!(ids(&state(db).get_history()), ["used", "new"]);
+    }
+
+    #[test]
+    fn stats_count_usage() {
+        let mut a = text("a", "https://example.com", 3);
+        a.is_pinned = true;
+        a.paste_count = 1;
+        a.copy_count = 3;
+        let mut b = text("b", "two", 2);
+        b.paste_count = 4;
+        let image = ClipboardItem { id: "c".to_string(), r#type: "image".to_string(), copy_count: 1, ..Default::default() };
+        let state = state(Database { history: vec![a, b, image], ..Default::default() });
+        state.delete_item("c");
+
+        let stats = state.get_stats();
+        assert_eq!((stats.total_items, stats.pinned_items, stats.trashed_items), (2, 1, 1));
+        assert_eq!(stats.total_pastes, 5);
+        assert_eq!(stats.total_recopies, 2);
+        assert_eq!(stats.by_type["text"], 2);
+        assert_eq!(stats.by_kind[&ContentKind::Url], 1);
+        assert_eq!(ids(&stats.most_pasted), ["b", "a"]);
+    }
+
+    #[test]
+    fn updates_without_changes_are_not_undoable() {
+        let mut item = text("a", "one", 0);
+        item.title = Some("Title".to_string());
+        let state = state(Database { history: vec![item], ..Default::default() });
+        let same = ItemUpdate {
+            title: Some(" Title ".to_string()),
+            content: Some("one".to_string()),
+            ..Default::default()
+        };
+        state.update_item("a", same, 1).unwrap();
+        assert!(!state.undo_last_action());
+        assert!(state.db.lock().unwrap().sync.modified.is_empty());
+    }
+
+    #[test]
+    fn undo_reverts_an_edit() {
+        let state = state(Database { history: vec![text("a", "one", 0)], ..Default::default() });
+        let update = ItemUpdate {
+            note: Some("why".to_string()),
+            content: Some("https://example.com".to_string()),
+            ..Default::default()
+        };
+        state.update_item("a", update, 1).unwrap();
+        let edited = state.get_item("a").unwrap();
+        assert_eq!(edited.kind, Some(ContentKind::Url));
+        assert_eq!(edited.revisions[0].content, "one");
+
+        assert!(state.undo_last_action());
+        let reverted = state.get_item("a").unwrap();
+        assert_eq!(reverted.content, "one");
+        assert_eq!(reverted.note, None);
+        assert!(reverted.revisions.is_empty());
+        assert_ne!(reverted.kind, Some(ContentKind::Url));
+    }
+
+    #[test]
+    fn revisions_are_capped_keeping_the_original() {
+        let state = state(Database { history: vec![text("a", "original", 0)], ..Default::default() });
+        for i in 1..=REVISION_LIMIT + 5 {
+            let update = ItemUpdate { content: Some(format!("edit {}", i)), ..Default::default() };
+            state.update_item("a", update, i as i64).unwrap();
+        }
+        let item = state.get_item("a").unwrap();
+        assert_eq!(item.revisions.len(), REVISION_LIMIT);
+        assert_eq!(item.revisions[0].content, "original");
+        assert_eq!(item.revisions[1].content, "edit 6");
+        assert_eq!(item.revisions.last().unwrap().content, format!("edit {}", REVISION_LIMIT + 4));
+    }
+
+    #[test]
+    fn restoring_a_revision_keeps_the_current_content() {
+        let mut item = text("a", "<b>one</b>", 0);
+        item.formats.insert("text/html".to_string(), "<b>one</b>".to_string());
+        let state = state(Database { history: vec![item], ..Default::default() });
+        let update = ItemUpdate { content: Some("two".to_string()), ..Default::default() };
+        state.update_item("a", update, 1).unwrap();
+        assert!(state.get_item("a").unwrap().formats.is_empty());
+
+        state.restore_revision("a", 0, 2).unwrap();
+        let restored = state.get_item("a").unwrap();
+        assert_eq!(restored.content, "<b>one</b>");
+        assert!(restored.formats.contains_key("text/html"));
+        assert_eq!(restored.revisions.last().unwrap().content, "two");
+        assert!(state.restore_revision("a", 9, 3).is_err());
+
+        assert!(state.undo_last_action());
+        assert_eq!(state.get_item("a").unwrap().content, "two");
+    }
+
+    #[test]
+    fn expired_items_leave_history_and_trash() {
+        let mut history: Vec<ClipboardItem> = ["a", "b", "c", "d"].iter().map(|id| text(id, id, 0)).collect();
+        history[0].expires_at = Some(100);
+        history[1].expires_at = Some(200);
+        history[2].expires_at = Some(100);
+        let state = state(Database { history, ..Default::default() });
+        state.delete_item("c");
+
+        assert!(state.take_expired(99).is_empty());
+        assert_eq!(ids(&state.take_expired(100)), ["a", "c"]);
+        assert_eq!(ids(&state.get_history()), ["b", "d"]);
+        assert!(state.get_trash().is_empty());
+        assert_eq!(ids(&state.take_expired(300)), ["b"]);
+    }
+
+    #[test]
+    fn inserted_items_skip_dedup() {
+        let image = ClipboardItem {
+            id: "source".to_string(),
+            r#type: "image".to_string(),
+            content: "iVBORw0KGgo=".to_string(),
+            copy_count: 1,
+            ..Default::default()
+        };
+        let mut db = Database { history: vec![image.clone()], ..Default::default() };
+        db.settings.dedup = DedupStrategy::MoveToTop;
+        db.settings.similar_images = true;
+        let state = state(db);
+
+        state.insert_item(ClipboardItem { id: "edited".to_string(), timestamp: 1, ..image });
+        let history = state.get_history();
+        assert_eq!(ids(&history), ["edited", "source"]);
+        assert_eq!(history[1].copy_count, 1);
+    }
+
+    fn trashed_ids(state: &DbState) -> Vec<String> {
+        state.get_trash().into_iter().map(|t| t.item.id).collect()
+    }
+
+    fn three() -> DbState {
+        state(Database {
+            history: vec![text("a", "one", 3), text("b", "two", 2), text("c", "three", 1)],
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn undoing_a_delete_restores_the_position() {
+        let state = three();
+        state.delete_item("b");
+        assert_eq!(ids(&state.get_history()), ["a", "c"]);
+        assert_eq!(trashed_ids(&state), ["b"]);
+
+        assert!(state.undo_last_action());
+        assert_eq!(ids(&state.get_history()), ["a", "b", "c"]);
+        assert!(state.get_trash().is_empty());
+        assert!(!state.undo_last_action());
+    }
+
+    #[test]
+    fn undoing_a_clear_brings_back_unpinned_items() {
+        let state = three();
+        state.toggle_pin("b");
+        state.clear_all();
+        assert_eq!(ids(&state.get_history()), ["b"]);
+
+        assert!(state.undo_last_action());
+        assert_eq!(ids(&state.get_history()), ["a", "b", "c"]);
+        assert!(state.get_item("b").unwrap().is_pinned);
+    }
+
+    #[test]
+    fn reorder_and_pin_toggles_are_undone_in_turn() {
+        let state = three();
+        state.reorder_items("c", "a");
+        assert_eq!(ids(&state.get_history()), ["c", "a", "b"]);
+        state.toggle_pin("a");
+
+        assert!(state.undo_last_action());
+        assert!(!state.get_item("a").unwrap().is_pinned);
+        assert_eq!(ids(&state.get_history()), ["c", "a", "b"]);
+        assert!(state.undo_last_action());
+        assert_eq!(ids(&state.get_history()), ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn restoring_from_the_trash_keeps_the_position() {
+        let state = three();
+        state.delete_item("a");
+        state.delete_item("b");
+        assert!(state.restore_item("a"));
+        assert!(!state.restore_item("a"));
+        assert_eq!(ids(&state.get_history()), ["a", "c"]);
+        assert_eq!(trashed_ids(&state), ["b"]);
+    }
+
+    #[test]
+    fn stale_trash_actions_are_skipped() {
+        let state = three();
+        state.toggle_pin("c");
+        state.delete_item("a");
+        assert!(state.restore_item("a"));
+        state.delete_item("b");
+        state.empty_trash();
+
+        // Both deletions are gone from the trash, so undo reaches the pin toggle.
+        assert!(state.undo_last_action());
+        assert!(!state.get_item("c").unwrap().is_pinned);
+        assert_eq!(ids(&state.get_history()), ["a", "c"]);
+        assert!(!state.undo_last_action());
+    }
+
+    #[test]
+    fn trash_older_than_the_retention_is_purged() {
+        let state = three();
+        state.delete_item("a");
+        state.delete_item("b");
+        {
+            let mut db = state.db.lock().unwrap();
+            let days = db.settings.trash_retention_days as i64;
+            db.trash[0].deleted_at -= (days + 1) * 86_400_000;
+        }
+        state.purge_trash();
+        assert_eq!(trashed_ids(&state), ["b"]);
+    }
+
+    #[test]
+    fn missing_image_hashes_are_filled_in() {
+        use base64::Engine;
+        let mut png = Vec::new();
+        image::RgbaImage::new(4, 4)
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+        let image = ClipboardItem {
+            id: "i".to_string(),
+            r#type: "image".to_string(),
+            content: base64::engine::general_purpose::STANDARD.encode(png),
+            ..Default::default()
+        };
+        let broken = ClipboardItem { id: "x".to_string(), content: "!!".to_string(), ..image.clone() };
+        let state = state(Database { history: vec![image, broken, text("t", "one", 0)], ..Default::default() });
+
+        assert_eq!(state.hash_missing_images(), 1);
+        assert!(state.get_item("i").unwrap().perceptual_hash.is_some());
+        assert!(state.get_item("x").unwrap().content_hash.is_none());
+        assert_eq!(state.hash_missing_images(), 0, "unreadable images are not counted");
+    }
+
+    #[test]
+    fn edits_at_the_revision_cap_can_be_undone() {
+        let state = state(Database { history: vec![text("a", "edit 0", 0)], ..Default::default() });
+        for i in 1..=REVISION_LIMIT {
+            let update = ItemUpdate { content: Some(format!("edit {}", i)), ..Default::default() };
+            state.update_item("a", update, i as i64).unwrap();
+        }
+        assert_eq!(state.get_item("a").unwrap().revisions.len(), REVISION_LIMIT);
+        state.db.lock().unwrap().sync.modified.clear();
+
+        let update = ItemUpdate { content: Some("latest".to_string()), ..Default::default() };
+        state.update_item("a", update, 100).unwrap();
+        assert_eq!(state.db.lock().unwrap().sync.modified.get("item/a"), Some(&100));
+        assert!(state.undo_last_action());
+        assert_eq!(state.get_item("a").unwrap().content, format!("edit {}", REVISION_LIMIT));
+    }
+}
@@ -0,0 +1,30 @@
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use crate::db::DbState;
+
+const SWEEP_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Deletes expired items, clearing the clipboard of any that are still on it.
+/// Sends `item-expired` with the id of each deleted item.
+pub fn sweep(app: &AppHandle) {
+    let state = app.state::<DbState>();
+    let expired = state.take_expired(chrono::Utc::now().timestamp_millis());
+    if expired.is_empty() {
+        return;
+    }
+    let _ = state.save();
+    for item in &expired {
+        crate::clipboard::clear_if_holding(app, item);
+        let _ = app.emit("item-expired", &item.id);
+    }
+    let _ = app.emit("clipboard-changed", state.get_history());
+}
+
+/// Checks for expired items every second.
+pub fn start(app: AppHandle) {
+    thread::spawn(move || loop {
+        sweep(&app);
+        thread::sleep(SWEEP_INTERVAL);
+    });
+}
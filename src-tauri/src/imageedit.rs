@@ -0,0 +1,363 @@
+//! Edits for image items: crop, resize, rotate, simple annotations and
+//! re-encoding. Edits produce a new image; the source item is left alone.
+
+use std::collections::HashSet;
+use std::io::Cursor;
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::webp::WebPEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageFormat, Rgba, RgbaImage};
+use serde::Deserialize;
+
+/// JPEG quality used when none is given.
+const DEFAULT_QUALITY: u8 = 85;
+const QUALITY_RANGE: (u8, u8) = (1, 100);
+/// Largest width or height a resize may produce.
+const MAX_DIMENSION: u32 = 16384;
+/// Percentages a resize accepts.
+const PERCENT_RANGE: (f32, f32) = (1.0, 1000.0);
+const THICKNESS_RANGE: (u32, u32) = (1, 64);
+/// How far outside the image annotation points may lie. Drawing steps along
+/// the whole line, so this also bounds the work a single annotation costs.
+const MAX_OVERHANG: i64 = MAX_DIMENSION as i64;
+
+/// One step of an edit, applied in order.
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "op", rename_all = "camelCase")]
+pub enum ImageEdit {
+    /// Keeps the given rectangle. It must overlap the image and is clipped to it.
+    Crop { x: u32, y: u32, width: u32, height: u32 },
+    Resize { by: Resize },
+    /// Clockwise; only quarter turns are supported.
+    Rotate { degrees: u32 },
+    /// The outline of a rectangle.
+    Rectangle { x: i32, y: i32, width: u32, height: u32, #[serde(default)] style: Stroke },
+    /// A line from the first point with an arrowhead at the second.
+    Arrow { x1: i32, y1: i32, x2: i32, y2: i32, #[serde(default)] style: Stroke },
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum Resize {
+    /// Scales both sides, e.g. 50 for half size.
+    Percent(f32),
+    /// Shrinks so neither side exceeds this many pixels, keeping the aspect
+    /// ratio. Smaller images are left as they are.
+    MaxDimension(u32),
+}
+
+/// How annotations are drawn.
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Stroke {
+    /// `#rrggbb` or `#rrggbbaa`.
+    pub color: String,
+    pub thickness: u32,
+}
+
+impl Default for Stroke {
+    fn default() -> Self {
+        Stroke { color: "#ff0000".to_string(), thickness: 4 }
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    #[default]
+    Png,
+    Jpeg,
+    /// Always lossless; the `image` crate has no lossy WebP encoder.
+    Webp,
+}
+
+impl OutputFormat {
+    pub fn mime(&self) -> &'static str {
+        match self {
+            OutputFormat::Png => "image/png",
+            OutputFormat::Jpeg => "image/jpeg",
+            OutputFormat::Webp => "image/webp",
+        }
+    }
+}
+
+/// The encoding of an edited image.
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Output {
+    #[serde(default)]
+    pub format: OutputFormat,
+    /// JPEG quality, 1-100. Rejected for PNG and WebP, which are lossless.
+    #[serde(default)]
+    pub quality: Option<u8>,
+}
+
+/// Applies `edits` in order.
+pub fn apply_all(image: DynamicImage, edits: &[ImageEdit]) -> Result<DynamicImage, String> {
+    edits.iter().try_fold(image, apply)
+}
+
+/// Applies a single edit.
+pub fn apply(image: DynamicImage, edit: &ImageEdit) -> Result<DynamicImage, String> {
+    match edit {
+        ImageEdit::Crop { x, y, width, height } => crop(image, *x, *y, *width, *height),
+        ImageEdit::Resize { by } => resize(image, *by),
+        ImageEdit::Rotate { degrees } => match degrees % 360 {
+            0 => Ok(image),
+            90 => Ok(image.rotate90()),
+            180 => Ok(image.rotate180()),
+            270 => Ok(image.rotate270()),
+            _ => Err("Images can only be rotated by multiples of 90 degrees".to_string()),
+        },
+        ImageEdit::Rectangle { x, y, width, height, style } => {
+            let (color, thickness) = parse_stroke(style)?;
+            let (right, bottom) = (*x as i64 + *width as i64, *y as i64 + *height as i64);
+            check_points(&image, &[(*x as i64, *y as i64), (right, bottom)])?;
+            let (right, bottom) = (right as i32, bottom as i32);
+            let mut canvas = image.into_rgba8();
+            for (a, b) in [((*x, *y), (right, *y)), ((right, *y), (right, bottom)), ((right, bottom), (*x, bottom)), ((*x, bottom), (*x, *y))] {
+                draw_line(&mut canvas, a, b, color, thickness);
+            }
+            Ok(DynamicImage::ImageRgba8(canvas))
+        }
+        ImageEdit::Arrow { x1, y1, x2, y2, style } => {
+            let (color, thickness) = parse_stroke(style)?;
+            check_points(&image, &[(*x1 as i64, *y1 as i64), (*x2 as i64, *y2 as i64)])?;
+            let mut canvas = image.into_rgba8();
+            draw_arrow(&mut canvas, (*x1, *y1), (*x2, *y2), color, thickness);
+            Ok(DynamicImage::ImageRgba8(canvas))
+        }
+    }
+}
+
+/// Encodes `image` as `output` asks.
+pub fn encode(image: &DynamicImage, output: Output) -> Result<Vec<u8>, String> {
+    let mut bytes: Vec<u8> = Vec::new();
+    let result = match output.format {
+        OutputFormat::Png | OutputFormat::Webp if output.quality.is_some() => {
+            return Err(format!("{:?} is lossless and takes no quality setting", output.format));
+        }
+        OutputFormat::Png => image.write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png),
+        OutputFormat::Jpeg => {
+            let quality = output.quality.unwrap_or(DEFAULT_QUALITY);
+            if !(QUALITY_RANGE.0..=QUALITY_RANGE.1).contains(&quality) {
+                return Err(format!("Quality must be between {} and {}", QUALITY_RANGE.0, QUALITY_RANGE.1));
+            }
+            // JPEG has no alpha channel.
+            image.to_rgb8().write_with_encoder(JpegEncoder::new_with_quality(&mut bytes, quality))
+        }
+        OutputFormat::Webp => image.to_rgba8().write_with_encoder(WebPEncoder::new_lossless(&mut bytes)),
+    };
+    result.map_err(|e| e.to_string())?;
+    Ok(bytes)
+}
+
+fn crop(image: DynamicImage, x: u32, y: u32, width: u32, height: u32) -> Result<DynamicImage, String> {
+    let width = width.min(image.width().saturating_sub(x));
+    let height = height.min(image.height().saturating_sub(y));
+    if width == 0 || height == 0 {
+        return Err("The crop rectangle is outside the image".to_string());
+    }
+    Ok(image.crop_imm(x, y, width, height))
+}
+
+fn resize(image: DynamicImage, by: Resize) -> Result<DynamicImage, String> {
+    let (width, height) = (image.width() as f32, image.height() as f32);
+    let scale = match by {
+        Resize::Percent(percent) => {
+            if !(PERCENT_RANGE.0..=PERCENT_RANGE.1).contains(&percent) {
+                return Err(format!("Resize percentage must be between {} and {}", PERCENT_RANGE.0, PERCENT_RANGE.1));
+            }
+            percent / 100.0
+        }
+        Resize::MaxDimension(max) => {
+            if max == 0 {
+                return Err("Maximum dimension must be at least 1 pixel".to_string());
+            }
+            (max as f32 / width.max(height)).min(1.0)
+        }
+    };
+    let new_width = (width * scale).round().max(1.0) as u32;
+    let new_height = (height * scale).round().max(1.0) as u32;
+    if new_width > MAX_DIMENSION || new_height > MAX_DIMENSION {
+        return Err(format!("Images cannot be larger than {} pixels on a side", MAX_DIMENSION));
+    }
+    if (new_width, new_height) == (image.width(), image.height()) {
+        return Ok(image);
+    }
+    Ok(image.resize_exact(new_width, new_height, FilterType::Lanczos3))
+}
+
+/// Rejects annotation points more than `MAX_OVERHANG` pixels outside the image.
+fn check_points(image: &DynamicImage, points: &[(i64, i64)]) -> Result<(), String> {
+    let (width, height) = (image.width() as i64, image.height() as i64);
+    let inside = |value: i64, size: i64| (-MAX_OVERHANG..=size + MAX_OVERHANG).contains(&value);
+    if points.iter().all(|&(x, y)| inside(x, width) && inside(y, height)) {
+        Ok(())
+    } else {
+        Err("Annotation lies too far outside the image".to_string())
+    }
+}
+
+fn parse_stroke(stroke: &Stroke) -> Result<(Rgba<u8>, u32), String> {
+    if !(THICKNESS_RANGE.0..=THICKNESS_RANGE.1).contains(&stroke.thickness) {
+        return Err(format!("Thickness must be between {} and {}", THICKNESS_RANGE.0, THICKNESS_RANGE.1));
+    }
+    let invalid = || format!("Invalid color: {}", stroke.color);
+    let hex = stroke.color.strip_prefix('#').ok_or_else(invalid)?;
+    if !matches!(hex.len(), 6 | 8) || !hex.is_ascii() {
+        return Err(invalid());
+    }
+    let mut channels = [255u8; 4];
+    for (i, channel) in channels.iter_mut().enumerate().take(hex.len() / 2) {
+        *channel = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).map_err(|_| invalid())?;
+    }
+    Ok((Rgba(channels), stroke.thickness))
+}
+
+/// Draws an arrow from `from` to `to`, with a head sized to the stroke.
+fn draw_arrow(canvas: &mut RgbaImage, from: (i32, i32), to: (i32, i32), color: Rgba<u8>, thickness: u32) {
+    draw_line(canvas, from, to, color, thickness);
+    let (dx, dy) = ((to.0 - from.0) as f32, (to.1 - from.1) as f32);
+    let length = dx.hypot(dy);
+    if length == 0.0 {
+        return;
+    }
+    let head = (thickness as f32 * 4.0).max(10.0).min(length);
+    let angle = dy.atan2(dx);
+    for side in [-1.0f32, 1.0] {
+        let wing = angle + std::f32::consts::PI + side * std::f32::consts::FRAC_PI_6;
+        let end = (to.0 + (head * wing.cos()).round() as i32, to.1 + (head * wing.sin()).round() as i32);
+        draw_line(canvas, to, end, color, thickness);
+    }
+}
+
+/// Draws a line `thickness` pixels wide, blending `color` over the image.
+/// Parts outside the image are skipped.
+fn draw_line(canvas: &mut RgbaImage, from: (i32, i32), to: (i32, i32), color: Rgba<u8>, thickness: u32) {
+    let (dx, dy) = ((to.0 - from.0) as f32, (to.1 - from.1) as f32);
+    let steps = dx.abs().max(dy.abs()).ceil().max(1.0) as i32;
+    let radius = thickness as f32 / 2.0;
+    let reach = radius.ceil() as i32;
+    // Pixels covered by the brush at consecutive steps overlap; mark them so
+    // translucent colors are blended once.
+    let mut covered = HashSet::new();
+    for step in 0..=steps {
+        let t = step as f32 / steps as f32;
+        let (cx, cy) = (from.0 as f32 + dx * t, from.1 as f32 + dy * t);
+        for oy in -reach..=reach {
+            for ox in -reach..=reach {
+                let (px, py) = (cx.round() as i32 + ox, cy.round() as i32 + oy);
+                if px < 0 || py < 0 || px as u32 >= canvas.width() || py as u32 >= canvas.height() {
+                    continue;
+                }
+                if ((ox * ox + oy * oy) as f32).sqrt() > radius.max(0.5) {
+                    continue;
+                }
+                if covered.insert((px, py)) {
+                    blend(canvas.get_pixel_mut(px as u32, py as u32), color);
+                }
+            }
+        }
+    }
+}
+
+fn blend(pixel: &mut Rgba<u8>, color: Rgba<u8>) {
+    let alpha = color.0[3] as u32;
+    for i in 0..3 {
+        pixel.0[i] = ((color.0[i] as u32 * alpha + pixel.0[i] as u32 * (255 - alpha)) / 255) as u8;
+    }
+    pixel.0[3] = (alpha + pixel.0[3] as u32 * (255 - alpha) / 255) as u8;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blank(width: u32, height: u32) -> DynamicImage {
+        DynamicImage::ImageRgba8(RgbaImage::from_pixel(width, height, Rgba([255, 255, 255, 255])))
+    }
+
+    #[test]
+    fn crop_is_clipped_to_the_image() {
+        let out = apply(blank(100, 50), &ImageEdit::Crop { x: 80, y: 10, width: 50, height: 20 }).unwrap();
+        assert_eq!((out.width(), out.height()), (20, 20));
+        assert!(apply(blank(100, 50), &ImageEdit::Crop { x: 100, y: 0, width: 5, height: 5 }).is_err());
+    }
+
+    #[test]
+    fn resize_by_percent_and_max_dimension() {
+        let half = apply(blank(200, 100), &ImageEdit::Resize { by: Resize::Percent(50.0) }).unwrap();
+        assert_eq!((half.width(), half.height()), (100, 50));
+        let fitted = apply(blank(400, 100), &ImageEdit::Resize { by: Resize::MaxDimension(200) }).unwrap();
+        assert_eq!((fitted.width(), fitted.height()), (200, 50));
+        let small = apply(blank(40, 10), &ImageEdit::Resize { by: Resize::MaxDimension(200) }).unwrap();
+        assert_eq!((small.width(), small.height()), (40, 10));
+    }
+
+    #[test]
+    fn rotate_quarter_turns_only() {
+        let out = apply(blank(30, 10), &ImageEdit::Rotate { degrees: 90 }).unwrap();
+        assert_eq!((out.width(), out.height()), (10, 30));
+        assert!(apply(blank(30, 10), &ImageEdit::Rotate { degrees: 45 }).is_err());
+    }
+
+    #[test]
+    fn rectangle_draws_outline_only() {
+        let style = Stroke { color: "#0000ff".to_string(), thickness: 1 };
+        let out = apply(blank(20, 20), &ImageEdit::Rectangle { x: 2, y: 2, width: 10, height: 10, style }).unwrap().into_rgba8();
+        assert_eq!(out.get_pixel(2, 7), &Rgba([0, 0, 255, 255]));
+        assert_eq!(out.get_pixel(12, 12), &Rgba([0, 0, 255, 255]));
+        assert_eq!(out.get_pixel(7, 7), &Rgba([255, 255, 255, 255]));
+    }
+
+    #[test]
+    fn arrow_may_leave_the_image() {
+        let out = apply(blank(20, 20), &ImageEdit::Arrow { x1: -10, y1: 10, x2: 15, y2: 10, style: Stroke::default() }).unwrap().into_rgba8();
+        assert_eq!(out.get_pixel(5, 10), &Rgba([255, 0, 0, 255]));
+    }
+
+    #[test]
+    fn rejects_bad_colors() {
+        let style = Stroke { color: "red".to_string(), thickness: 2 };
+        assert!(apply(blank(5, 5), &ImageEdit::Arrow { x1: 0, y1: 0, x2: 4, y2: 4, style }).is_err());
+    }
+
+    #[test]
+    fn encodes_each_format() {
+        let image = blank(8, 8);
+        for (format, magic) in [(OutputFormat::Png, &b"\x89PNG"[..]), (OutputFormat::Jpeg, &b"\xff\xd8"[..]), (OutputFormat::Webp, &b"RIFF"[..])] {
+            let bytes = encode(&image, Output { format, quality: None }).unwrap();
+            assert!(bytes.starts_with(magic), "{:?}", format);
+        }
+        assert!(encode(&image, Output { format: OutputFormat::Jpeg, quality: Some(70) }).is_ok());
+        assert!(encode(&image, Output { format: OutputFormat::Jpeg, quality: Some(0) }).is_err());
+    }
+
+    #[test]
+    fn lossless_formats_reject_a_quality() {
+        let err = encode(&blank(8, 8), Output { format: OutputFormat::Webp, quality: Some(70) }).unwrap_err();
+        assert!(err.contains("lossless"), "{}", err);
+        assert!(encode(&blank(8, 8), Output { format: OutputFormat::Png, quality: Some(70) }).is_err());
+    }
+
+    #[test]
+    fn annotations_far_outside_the_image_are_rejected() {
+        let far = ImageEdit::Arrow { x1: 0, y1: 0, x2: 2_000_000_000, y2: 0, style: Stroke::default() };
+        assert!(apply(blank(20, 20), &far).is_err());
+        let huge = ImageEdit::Rectangle { x: i32::MAX - 5, y: 0, width: u32::MAX, height: 5, style: Stroke::default() };
+        assert!(apply(blank(20, 20), &huge).is_err());
+        let wide = ImageEdit::Rectangle { x: -100, y: -100, width: 300, height: 300, style: Stroke::default() };
+        assert!(apply(blank(20, 20), &wide).is_ok());
+    }
+
+    #[test]
+    fn edits_deserialize_from_camel_case() {
+        let edits: Vec<ImageEdit> = serde_json::from_str(
+            r#"[{"op":"resize","by":{"maxDimension":800}},{"op":"arrow","x1":0,"y1":0,"x2":5,"y2":5}]"#,
+        )
+        .unwrap();
+        assert_eq!(edits[0], ImageEdit::Resize { by: Resize::MaxDimension(800) });
+        assert_eq!(edits[1], ImageEdit::Arrow { x1: 0, y1: 0, x2: 5, y2: 5, style: Stroke::default() });
+    }
+}
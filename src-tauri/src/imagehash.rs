@@ -0,0 +1,104 @@
+//! Hashes used to spot duplicate images: an exact hash of the decoded pixels
+//! (so the same picture re-encoded differently still matches) and a
+//! difference hash (dHash) that survives small changes such as a blinking
+//! cursor in a screenshot.
+
+use image::imageops::FilterType;
+use sha2::{Digest, Sha256};
+
+/// Images whose perceptual hashes differ in at most this many of the 64 bits
+/// are considered the same picture.
+const SIMILAR_BITS: u32 = 4;
+
+pub struct ImageHashes {
+    /// SHA-256 over the dimensions and RGBA pixels, hex encoded.
+    pub content: String,
+    pub perceptual: u64,
+}
+
+/// Hashes a base64 encoded image as stored in `ClipboardItem::content`.
+pub fn hash_image(content: &str) -> Option<ImageHashes> {
+    let bytes = crate::db::image_bytes(content)?;
+    let image = image::load_from_memory(&bytes).ok()?;
+
+    let rgba = image.to_rgba8();
+    let mut hasher = Sha256::new();
+    hasher.update(rgba.width().to_le_bytes());
+    hasher.update(rgba.height().to_le_bytes());
+    hasher.update(rgba.as_raw());
+    let content = hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect();
+
+    // dHash: one bit per horizontally adjacent pixel pair of a 9x8 thumbnail.
+    let thumb = image.resize_exact(9, 8, FilterType::Triangle).to_luma8();
+    let mut perceptual = 0u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            perceptual <<= 1;
+            if thumb.get_pixel(x, y)[0] < thumb.get_pixel(x + 1, y)[0] {
+                perceptual |= 1;
+            }
+        }
+    }
+    Some(ImageHashes { content, perceptual })
+}
+
+pub fn is_similar(a: u64, b: u64) -> bool {
+    (a ^ b).count_ones() <= SIMILAR_BITS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::Engine;
+    use image::{ImageFormat, RgbaImage};
+    use std::io::Cursor;
+
+    /// A diagonal gradient, so the dHash has bits set.
+    fn gradient() -> RgbaImage {
+        RgbaImage::from_fn(32, 32, |x, y| image::Rgba([(x * 8) as u8, (y * 8) as u8, 128, 255]))
+    }
+
+    fn encode(image: &RgbaImage, format: ImageFormat) -> String {
+        let mut bytes = Vec::new();
+        image.write_to(&mut Cursor::new(&mut bytes), format).unwrap();
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    }
+
+    #[test]
+    fn reencoding_keeps_the_content_hash() {
+        let png = hash_image(&encode(&gradient(), ImageFormat::Png)).unwrap();
+        let bmp = hash_image(&encode(&gradient(), ImageFormat::Bmp)).unwrap();
+        assert_eq!(png.content, bmp.content);
+        assert_eq!(png.perceptual, bmp.perceptual);
+
+        let data_url = format!("data:image/png;base64,{}", encode(&gradient(), ImageFormat::Png));
+        assert_eq!(hash_image(&data_url).unwrap().content, png.content);
+    }
+
+    #[test]
+    fn small_changes_are_similar_but_not_identical() {
+        let original = hash_image(&encode(&gradient(), ImageFormat::Png)).unwrap();
+        let mut cursor = gradient();
+        for y in 10..12 {
+            cursor.put_pixel(20, y, image::Rgba([0, 0, 0, 255]));
+        }
+        let changed = hash_image(&encode(&cursor, ImageFormat::Png)).unwrap();
+        assert_ne!(original.content, changed.content);
+        assert!(is_similar(original.perceptual, changed.perceptual));
+    }
+
+    #[test]
+    fn different_pictures_are_not_similar() {
+        let original = hash_image(&encode(&gradient(), ImageFormat::Png)).unwrap();
+        let mut mirrored = gradient();
+        image::imageops::flip_horizontal_in_place(&mut mirrored);
+        let other = hash_image(&encode(&mirrored, ImageFormat::Png)).unwrap();
+        assert!(!is_similar(original.perceptual, other.perceptual));
+    }
+
+    #[test]
+    fn invalid_content_has_no_hashes() {
+        assert!(hash_image("not base64!").is_none());
+        assert!(hash_image("aGVsbG8=").is_none());
+    }
+}
@@ -0,0 +1,180 @@
+//! cliphist's database, a bbolt (BoltDB) file with one bucket `b` mapping
+//! big-endian sequence numbers to the raw copied bytes. Only the parts of the
+//! bbolt page layout needed to walk a bucket read-only are implemented here.
+
+use super::{image_item, text_item, Imported};
+
+const MAGIC: u32 = 0xED0C_DAED;
+const PAGE_HEADER: usize = 16;
+const ELEMENT: usize = 16;
+const BRANCH_PAGE: u16 = 0x01;
+const LEAF_PAGE: u16 = 0x02;
+const BUCKET_LEAF: u32 = 0x01;
+const BUCKET: &[u8] = b"b";
+/// Guards against cycles in a corrupt file.
+const MAX_DEPTH: usize = 32;
+
+fn u16_at(data: &[u8], at: usize) -> Result<u16, String> {
+    data.get(at..at + 2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]))
+        .ok_or_else(corrupt)
+}
+
+fn u32_at(data: &[u8], at: usize) -> Result<u32, String> {
+    data.get(at..at + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or_else(corrupt)
+}
+
+fn u64_at(data: &[u8], at: usize) -> Result<u64, String> {
+    data.get(at..at + 8)
+        .map(|b| u64::from_le_bytes(b.try_into().unwrap()))
+        .ok_or_else(corrupt)
+}
+
+fn slice(data: &[u8], at: usize, len: usize) -> Result<&[u8], String> {
+    data.get(at..at.checked_add(len).ok_or_else(corrupt)?).ok_or_else(corrupt)
+}
+
+fn corrupt() -> String {
+    "The cliphist database is damaged".to_string()
+}
+
+struct Entry<'a> {
+    key: &'a [u8],
+    value: &'a [u8],
+    is_bucket: bool,
+}
+
+struct Db<'a> {
+    data: &'a [u8],
+    page_size: usize,
+}
+
+impl<'a> Db<'a> {
+    /// Reads the newer of the two meta pages and returns the root bucket's page.
+    fn open(data: &'a [u8]) -> Result<(Self, u64), String> {
+        if u32_at(data, PAGE_HEADER).ok() != Some(MAGIC) {
+            return Err("Not a cliphist database".to_string());
+        }
+        let page_size = u32_at(data, PAGE_HEADER + 8)? as usize;
+        if page_size < 512 {
+            return Err(corrupt());
+        }
+        let db = Self { data, page_size };
+
+        let mut best: Option<(u64, u64)> = None;
+        for id in 0..2 {
+            let Ok(page) = slice(data, id * page_size, page_size) else { continue };
+            let meta = PAGE_HEADER;
+            if u32_at(page, meta)? != MAGIC {
+                continue;
+            }
+            let root = u64_at(page, meta + 16)?;
+            let txid = u64_at(page, meta + 48)?;
+            if !matches!(best, Some((_, best_txid)) if best_txid >= txid) {
+                best = Some((root, txid));
+            }
+        }
+        let (root, _) = best.ok_or_else(corrupt)?;
+        Ok((db, root))
+    }
+
+    /// The page with `id`, including its overflow pages.
+    fn page(&self, id: u64) -> Result<&'a [u8], String> {
+        let start = (id as usize).checked_mul(self.page_size).ok_or_else(corrupt)?;
+        let overflow = u32_at(self.data, start + 12)? as usize;
+        slice(self.data, start, (overflow + 1) * self.page_size)
+    }
+
+    /// Collects the leaf entries of the tree rooted at `page`, in key order.
+    fn walk(&self, page: &'a [u8], depth: usize, out: &mut Vec<Entry<'a>>) -> Result<(), String> {
+        if depth > MAX_DEPTH {
+            return Err(corrupt());
+        }
+        let flags = u16_at(page, 8)?;
+        let count = u16_at(page, 10)? as usize;
+        for i in 0..count {
+            let element = PAGE_HEADER + i * ELEMENT;
+            if flags & BRANCH_PAGE != 0 {
+                let child = u64_at(page, element + 8)?;
+                self.walk(self.page(child)?, depth + 1, out)?;
+            } else if flags & LEAF_PAGE != 0 {
+                let entry_flags = u32_at(page, element)?;
+                let pos = element + u32_at(page, element + 4)? as usize;
+                let key_len = u32_at(page, element + 8)? as usize;
+                let value_len = u32_at(page, element + 12)? as usize;
+                out.push(Entry {
+                    key: slice(page, pos, key_len)?,
+                    value: slice(page, pos + key_len, value_len)?,
+                    is_bucket: entry_flags & BUCKET_LEAF != 0,
+                });
+            } else {
+                return Err(corrupt());
+            }
+        }
+        Ok(())
+    }
+
+    /// Entries of a bucket given its value in the parent; small buckets are
+    /// stored inline right after the 16-byte bucket header.
+    fn bucket(&self, value: &'a [u8]) -> Result<Vec<Entry<'a>>, String> {
+        let root = u64_at(value, 0)?;
+        let page = if root == 0 { slice(value, 16, value.len().saturating_sub(16))? } else { self.page(root)? };
+        let mut entries = Vec::new();
+        self.walk(page, 0, &mut entries)?;
+        Ok(entries)
+    }
+}
+
+pub fn parse(bytes: &[u8], newest: i64) -> Result<Imported, String> {
+    let (db, root) = Db::open(bytes)?;
+    let mut buckets = Vec::new();
+    db.walk(db.page(root)?, 0, &mut buckets)?;
+    let Some(bucket) = buckets.iter().find(|e| e.is_bucket && e.key == BUCKET) else {
+        return Ok(Imported::default());
+    };
+
+    let mut entries = db.bucket(bucket.value)?;
+    // Keys are big-endian sequence numbers, so the newest sorts last.
+    entries.sort_by(|a, b| b.key.cmp(a.key));
+
+    let mut imported = Imported::default();
+    for (i, entry) in entries.iter().enumerate() {
+        let timestamp = newest - i as i64 * 1000;
+        let item = match std::str::from_utf8(entry.value) {
+            Ok(text) => text_item(text.to_string(), timestamp),
+            Err(_) => image_item(entry.value, timestamp),
+        };
+        match item {
+            Some(item) => imported.items.push(item),
+            None => imported.skipped += 1,
+        }
+    }
+    Ok(imported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_bolt_database() {
+        let imported = parse(include_bytes!("../../tests/fixtures/cliphist.db"), 10_000).unwrap();
+        let items = &imported.items;
+        assert_eq!(imported.skipped, 1, "binary data that is not an image");
+        assert_eq!(items.len(), 3);
+
+        assert_eq!(items[0].content, "último");
+        assert_eq!(items[0].timestamp, 10_000);
+        // The BMP is converted to PNG.
+        assert_eq!(items[1].r#type, "image");
+        assert!(items[1].content.starts_with("iVBOR"));
+        assert_eq!(items[2].content, "first copy");
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        assert!(parse(&[0u8; 8192], 0).is_err());
+    }
+}
@@ -0,0 +1,30 @@
+//! clipman's history file, a JSON array of strings with the newest last.
+
+use super::{text_item, Imported};
+
+pub fn parse(bytes: &[u8], newest: i64) -> Result<Imported, String> {
+    let entries: Vec<String> =
+        serde_json::from_slice(bytes).map_err(|e| format!("Not a clipman history file: {}", e))?;
+    let mut imported = Imported::default();
+    for (i, text) in entries.into_iter().rev().enumerate() {
+        match text_item(text, newest - i as i64 * 1000) {
+            Some(item) => imported.items.push(item),
+            None => imported.skipped += 1,
+        }
+    }
+    Ok(imported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_history_json() {
+        let imported = parse(include_bytes!("../../tests/fixtures/clipman.json"), 10_000).unwrap();
+        let contents: Vec<&str> = imported.items.iter().map(|i| i.content.as_str()).collect();
+        assert_eq!(contents, ["newest", "middle", "oldest"]);
+        assert_eq!(imported.skipped, 1);
+        assert_eq!(imported.items[2].timestamp, 10_000 - 3 * 1000);
+    }
+}
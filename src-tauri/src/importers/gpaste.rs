@@ -0,0 +1,134 @@
+//! GPaste's `history.xml`. Items are listed newest first; version 2 files wrap
+//! the content in a `<value>` element, version 1 files put it directly in the
+//! `<item>`. Image items hold the path of a PNG saved next to the history.
+
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use super::{image_item, text_item, Imported};
+
+#[derive(Default)]
+struct Entry {
+    kind: String,
+    /// Unix seconds; only written for images.
+    date: Option<i64>,
+    value: String,
+    direct: String,
+    has_value: bool,
+}
+
+impl Entry {
+    fn content(self) -> String {
+        if self.has_value { self.value } else { self.direct }
+    }
+}
+
+/// Text carried by a text, CDATA or entity reference event.
+fn event_text(event: &Event) -> Result<Option<String>, String> {
+    let text = match event {
+        Event::Text(t) => t.decode().map_err(|e| e.to_string())?.into_owned(),
+        Event::CData(t) => t.decode().map_err(|e| e.to_string())?.into_owned(),
+        Event::GeneralRef(r) => match r.resolve_char_ref().map_err(|e| e.to_string())? {
+            Some(c) => c.to_string(),
+            None => {
+                let name = r.decode().map_err(|e| e.to_string())?;
+                quick_xml::escape::resolve_predefined_entity(&name).unwrap_or_default().to_string()
+            }
+        },
+        _ => return Ok(None),
+    };
+    Ok(Some(text))
+}
+
+pub fn parse(bytes: &[u8], newest: i64) -> Result<Imported, String> {
+    let xml = std::str::from_utf8(bytes).map_err(|_| "Not a GPaste history file".to_string())?;
+    let mut reader = Reader::from_str(xml);
+    let mut entries = Vec::new();
+    let mut entry: Option<Entry> = None;
+    let mut in_value = false;
+    let mut saw_history = false;
+
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| format!("Invalid GPaste history at {}: {}", reader.error_position(), e))?;
+        match event {
+            Event::Start(tag) | Event::Empty(tag) if tag.name().as_ref() == b"history" => saw_history = true,
+            Event::Start(tag) if tag.name().as_ref() == b"item" => {
+                let mut new = Entry::default();
+                for attr in tag.attributes().flatten() {
+                    let value = attr.unescape_value().map_err(|e| e.to_string())?;
+                    match attr.key.as_ref() {
+                        b"kind" => new.kind = value.into_owned(),
+                        b"date" => new.date = value.trim().parse().ok(),
+                        _ => {}
+                    }
+                }
+                entry = Some(new);
+            }
+            Event::Start(tag) if tag.name().as_ref() == b"value" => {
+                in_value = true;
+                if let Some(entry) = entry.as_mut() {
+                    entry.has_value = true;
+                }
+            }
+            Event::End(tag) if tag.name().as_ref() == b"value" => in_value = false,
+            Event::End(tag) if tag.name().as_ref() == b"item" => entries.extend(entry.take()),
+            Event::Eof => break,
+            other => {
+                let (Some(entry), Some(text)) = (entry.as_mut(), event_text(&other)?) else { continue };
+                if in_value { entry.value.push_str(&text) } else { entry.direct.push_str(&text) }
+            }
+        }
+    }
+    if !saw_history {
+        return Err("Not a GPaste history file".to_string());
+    }
+
+    let mut imported = Imported::default();
+    for (i, entry) in entries.into_iter().enumerate() {
+        let timestamp = entry.date.map_or(newest - i as i64 * 1000, |d| d * 1000);
+        let kind = entry.kind.clone();
+        let content = entry.content();
+        let item = match kind.as_str() {
+            "Text" | "Uris" => text_item(content, timestamp),
+            "Image" => std::fs::read(content.trim()).ok().and_then(|png| image_item(&png, timestamp)),
+            // Passwords are left behind on purpose.
+            _ => None,
+        };
+        match item {
+            Some(item) => imported.items.push(item),
+            None => imported.skipped += 1,
+        }
+    }
+    Ok(imported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_history_xml() {
+        let imported = parse(include_bytes!("../../tests/fixtures/gpaste_history.xml"), 10_000).unwrap();
+        let items = &imported.items;
+        assert_eq!(imported.skipped, 2, "the password and the missing image");
+        assert_eq!(items.len(), 3);
+
+        assert_eq!(items[0].content, "echo \"a\" && echo b");
+        assert_eq!(items[1].content, "/home/me/notes.txt\n/home/me/todo.txt");
+        assert_eq!(items[2].content, "Fish & chips é");
+        assert_eq!(items[2].timestamp, 10_000 - 4 * 1000);
+    }
+
+    #[test]
+    fn reads_version_1_items() {
+        let xml = br#"<history version="1.0"><item kind="Text"><![CDATA[old style]]></item></history>"#;
+        let imported = parse(xml, 0).unwrap();
+        assert_eq!(imported.items[0].content, "old style");
+    }
+
+    #[test]
+    fn rejects_other_xml() {
+        assert!(parse(b"<html><body/></html>", 0).is_err());
+    }
+}
@@ -0,0 +1,100 @@
+//! Klipper's `history2.lst`. The file is a `QDataStream` holding a checksum
+//! and a byte array; the array starts with Klipper's version string followed
+//! by the items, newest first, each tagged `"string"`, `"url"` or `"image"`.
+
+use super::qdatastream::Reader;
+use super::{image_item, text_item, Imported};
+
+/// Returns the length of the PNG at the start of `data` by walking its chunks
+/// up to IEND, since `QImage` is written without a length prefix.
+fn png_len(data: &[u8]) -> Result<usize, String> {
+    let mut reader = Reader::new(data);
+    if reader.bytes(8)? != b"\x89PNG\r\n\x1a\n" {
+        return Err("Klipper image is not a PNG".to_string());
+    }
+    loop {
+        let len = reader.u32()? as usize;
+        let kind = reader.bytes(4)?;
+        reader.skip(len + 4)?;
+        if kind == b"IEND" {
+            return Ok(data.len() - reader.remaining().len());
+        }
+    }
+}
+
+pub fn parse(bytes: &[u8], newest: i64) -> Result<Imported, String> {
+    let mut file = Reader::new(bytes);
+    // A damaged file fails below instead; the checksum algorithm changed
+    // between Klipper versions.
+    let _checksum = file.u32()?;
+    let data = file.byte_array()?;
+
+    let mut reader = Reader::new(data);
+    let version = reader.c_string()?;
+    if version.is_empty() || !version.starts_with(|c: char| c.is_ascii_digit()) {
+        return Err("Not a Klipper history file".to_string());
+    }
+
+    let mut imported = Imported::default();
+    let mut index = 0;
+    while !reader.at_end() {
+        let timestamp = newest - index * 1000;
+        index += 1;
+        let item = match reader.string()?.as_str() {
+            "string" => text_item(reader.string()?, timestamp),
+            "url" => {
+                let count = reader.u32()?;
+                let mut urls = Vec::new();
+                for _ in 0..count {
+                    urls.push(String::from_utf8_lossy(reader.byte_array()?).into_owned());
+                }
+                // Extra metadata map and the "cut" flag.
+                for _ in 0..reader.u32()? * 2 {
+                    reader.string()?;
+                }
+                reader.i32()?;
+                text_item(urls.join("\n"), timestamp)
+            }
+            "image" => {
+                if reader.i32()? == 0 {
+                    None
+                } else {
+                    let len = png_len(reader.remaining())?;
+                    image_item(reader.bytes(len)?, timestamp)
+                }
+            }
+            other => return Err(format!("Unknown Klipper item type \"{}\"", other)),
+        };
+        match item {
+            Some(item) => imported.items.push(item),
+            None => imported.skipped += 1,
+        }
+    }
+    Ok(imported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_history2_lst() {
+        let imported = parse(include_bytes!("../../tests/fixtures/klipper_history2.lst"), 10_000).unwrap();
+        let items = &imported.items;
+        assert_eq!(imported.skipped, 1, "blank text is skipped");
+        assert_eq!(items.len(), 4);
+
+        assert_eq!(items[0].content, "Klipper text");
+        assert_eq!(items[1].content, "file:///home/me/a.txt\nhttps://example.com/");
+        assert_eq!(items[2].r#type, "image");
+        assert!(items[2].content.starts_with("iVBOR"));
+        assert_eq!(items[3].content, "oldest");
+        // The skipped item still takes its place in the order.
+        assert_eq!(items[3].timestamp, 10_000 - 4 * 1000);
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        assert!(parse(b"not a klipper file", 0).is_err());
+    }
+}
@@ -0,0 +1,99 @@
+//! Reader for the Qt `QDataStream` encoding (big-endian, Qt 4.7+ layout) used
+//! by CopyQ and Klipper to save their history.
+
+/// Length marker Qt writes for null strings and byte arrays.
+const NULL_LENGTH: u32 = 0xFFFF_FFFF;
+
+pub struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    pub fn at_end(&self) -> bool {
+        self.pos >= self.data.len()
+    }
+
+    pub fn remaining(&self) -> &'a [u8] {
+        &self.data[self.pos.min(self.data.len())..]
+    }
+
+    pub fn skip(&mut self, len: usize) -> Result<(), String> {
+        self.bytes(len).map(|_| ())
+    }
+
+    pub fn bytes(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|end| *end <= self.data.len())
+            .ok_or("Unexpected end of file")?;
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    pub fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    pub fn bool(&mut self) -> Result<bool, String> {
+        Ok(self.u8()? != 0)
+    }
+
+    pub fn u32(&mut self) -> Result<u32, String> {
+        let b = self.bytes(4)?;
+        Ok(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    pub fn i32(&mut self) -> Result<i32, String> {
+        Ok(self.u32()? as i32)
+    }
+
+    /// `QByteArray`; null arrays read as empty.
+    pub fn byte_array(&mut self) -> Result<&'a [u8], String> {
+        match self.u32()? {
+            NULL_LENGTH => Ok(&[]),
+            len => self.bytes(len as usize),
+        }
+    }
+
+    /// `QString`: a byte length followed by UTF-16BE; null strings read as empty.
+    pub fn string(&mut self) -> Result<String, String> {
+        let len = match self.u32()? {
+            NULL_LENGTH => return Ok(String::new()),
+            len if len % 2 != 0 => return Err("Invalid string length".to_string()),
+            len => len as usize,
+        };
+        let units: Vec<u16> = self
+            .bytes(len)?
+            .chunks_exact(2)
+            .map(|b| u16::from_be_bytes([b[0], b[1]]))
+            .collect();
+        Ok(String::from_utf16_lossy(&units))
+    }
+
+    /// `const char *`: a length including the trailing NUL, then the bytes.
+    pub fn c_string(&mut self) -> Result<String, String> {
+        let bytes = self.byte_array()?;
+        let bytes = bytes.strip_suffix(&[0]).unwrap_or(bytes);
+        Ok(String::from_utf8_lossy(bytes).into_owned())
+    }
+}
+
+/// Undoes `qCompress`: a big-endian length prefix followed by a zlib stream.
+pub fn uncompress(data: &[u8]) -> Result<Vec<u8>, String> {
+    use std::io::Read;
+    if data.len() < 4 {
+        return Err("Invalid compressed data".to_string());
+    }
+    let mut out = Vec::new();
+    flate2::read::ZlibDecoder::new(&data[4..])
+        .read_to_end(&mut out)
+        .map_err(|e| e.to_string())?;
+    Ok(out)
+}
@@ -4,12 +4,55 @@ use tauri::{
     tray::{MouseButton, TrayIconBuilder, TrayIconEvent},
     image::Image, // IMPORTANTE: Adicionado para carregar o ícone manualmente
 };
-use tauri_plugin_global_shortcut::{ShortcutState, Shortcut, GlobalShortcutExt};
+use tauri_plugin_global_shortcut::ShortcutState;
+use crate::clipboard::WatcherState;
 use crate::db::DbState;
+use crate::queue::QueueState;
+use crate::shortcuts::ShortcutRegistry;
+use crate::typeout::TypeOutState;
 
+mod archive;
+mod barcode;
+mod classify;
 mod clipboard;
 mod commands;
 mod db;
+mod expiry;
+mod imageedit;
+mod imagehash;
+mod importers;
+mod ocr;
+mod paste;
+mod picker;
+mod plaintext;
+mod queue;
+mod selection;
+mod settings;
+mod shortcuts;
+mod sync;
+mod template;
+mod transform;
+mod typeout;
+
+/// Shows capture pause and paste queue progress in the tray tooltip.
+pub(crate) fn refresh_tray_tooltip(app: &tauri::AppHandle) {
+    let mut tooltip = "Linux Clipboard".to_string();
+    if app.state::<WatcherState>().is_paused() {
+        tooltip.push_str(" (paused)");
+    }
+    let queue = app.state::<QueueState>().get();
+    if !queue.item_ids.is_empty() || queue.collecting {
+        tooltip.push_str(&format!(
+            " - queue {}/{}, {} left",
+            queue.position,
+            queue.item_ids.len(),
+            queue.remaining()
+        ));
+    }
+    if let Some(tray) = app.tray_by_id("tray") {
+        let _ = tray.set_tooltip(Some(tooltip));
+    }
+}
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -28,7 +71,13 @@ pub fn run() {
         .plugin(tauri_plugin_store::Builder::default().build())
         .plugin(tauri_plugin_positioner::init())
         .plugin(tauri_plugin_autostart::init(tauri_plugin_autostart::MacosLauncher::LaunchAgent, Some(vec![])))
-        .plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
+        .plugin(tauri_plugin_single_instance::init(|app, args, _cwd| {
+            // `linux-clipboard pick ...` from a second instance: run the launcher picker
+            // in the running instance instead of toggling the window.
+            if let Some(options) = picker::parse_args(&args) {
+                picker::spawn(app.clone(), options);
+                return;
+            }
             if let Some(win) = app.get_webview_window("main") {
                  commands::show_window(win);
             }
@@ -45,45 +94,49 @@ pub fn run() {
             let state = DbState::new(app.handle());
             let settings = state.get_settings(); // Capture settings before moving state
             app.manage(state);
+            app.manage(WatcherState::default());
+            app.manage(ShortcutRegistry::default());
+            app.manage(TypeOutState::default());
+            app.manage(QueueState::default());
             
+            // Before the watcher, which queues captured images for OCR and code scanning.
+            ocr::start(app.handle());
+            barcode::start(app.handle());
             clipboard::start_watcher(app.handle().clone());
+            expiry::start(app.handle().clone());
+            sync::start(app.handle().clone());
+
+            let handle = app.handle().clone();
+            std::thread::spawn(move || {
+                let state = handle.state::<DbState>();
+                if state.hash_missing_images() > 0 {
+                    let _ = state.save();
+                }
+            });
+
+            let args: Vec<String> = std::env::args().collect();
+            if let Some(options) = picker::parse_args(&args) {
+                picker::spawn(app.handle().clone(), options);
+            }
             
             let quit_i = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
             let show_i = MenuItem::with_id(app, "show", "Show Clipboard", true, None::<&str>)?;
             let menu = Menu::with_items(app, &[&show_i, &quit_i])?;
 
-            let shortcut_str = if cfg!(target_os = "macos") { "Command+Control+V" } else { "Control+Alt+V" };
+            // Global shortcuts: one handler for every binding, dispatched by shortcuts::handle
+            app.handle().plugin(
+                tauri_plugin_global_shortcut::Builder::new()
+                    .with_handler(move |app, shortcut, event| {
+                        if event.state == ShortcutState::Pressed {
+                            shortcuts::handle(app, shortcut);
+                        }
+                    })
+                    .build(),
+            )?;
 
             // PROTEÇÃO 1: Registro de atalho seguro (sem unwrap)
-            if let Ok(shortcut) = shortcut_str.parse::<Shortcut>() {
-                app.handle().plugin(
-                    tauri_plugin_global_shortcut::Builder::new()
-                        .with_handler(move |app, _shortcut, event| {
-                            if event.state == ShortcutState::Pressed {
-                                if let Some(win) = app.get_webview_window("main") {
-                                    // LOGIC: Simplified Toggle
-                                    // If focused -> Hide
-                                    // Else (Hidden or Blurred) -> Show and Focus
-                                    if win.is_focused().unwrap_or(false) {
-                                        let _ = win.hide();
-                                    } else {
-                                        commands::show_window(win);
-                                    }
-                                }
-                            }
-                        })
-                        .build(),
-                )?;
-
-                
-                // CONDITIONAL REGISTRATION based on Settings
-                if settings.use_internal_shortcut {
-                    if let Err(e) = app.global_shortcut().register(shortcut) {
-                        log::error!("Erro ao registrar atalho global: {}", e);
-                    }
-                }
-            } else {
-                log::error!("Formato de atalho inválido: {}", shortcut_str);
+            if let Err(e) = shortcuts::apply(app.handle(), &settings) {
+                log::error!("Erro ao registrar atalhos globais: {}", e);
             }
 
             // PROTEÇÃO 2: Carregamento do Ícone Embutido (Resolve o crash e o ícone quebrado)
@@ -131,11 +184,52 @@ pub fn run() {
             commands::delete_item,
             commands::clear_all,
             commands::toggle_pin,
+            commands::update_item,
+            commands::restore_revision,
+            commands::set_item_expiry,
+            commands::ocr_available,
+            commands::copy_ocr_text,
+            commands::copy_decoded,
+            commands::edit_image,
+            commands::undo_last_action,
+            commands::get_trash,
+            commands::restore_item,
+            commands::empty_trash,
+            commands::export_history,
+            commands::import_history,
+            commands::sync_now,
+            commands::get_stats,
+            commands::get_tags,
+            commands::create_tag,
+            commands::rename_tag,
+            commands::delete_tag,
+            commands::set_item_tags,
+            commands::get_collections,
+            commands::create_collection,
+            commands::rename_collection,
+            commands::delete_collection,
+            commands::set_item_collection,
             commands::hide_window,
             commands::start_dragging,
             commands::set_zoom,
             commands::show_window,
             commands::paste_item,
+            commands::paste_plain,
+            commands::merge_items,
+            commands::get_paste_queue,
+            commands::set_paste_queue,
+            commands::set_queue_collecting,
+            commands::clear_paste_queue,
+            commands::paste_next_queued,
+            commands::type_item,
+            commands::get_transforms,
+            commands::paste_transformed,
+            commands::get_snippets,
+            commands::create_snippet,
+            commands::update_snippet,
+            commands::delete_snippet,
+            commands::snippet_inputs,
+            commands::paste_snippet,
             commands::get_settings,
             commands::update_setting,
             commands::reorder_items,
@@ -0,0 +1,101 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Sender};
+use std::sync::Mutex;
+use std::thread;
+use tauri::{AppHandle, Emitter, Manager};
+use crate::db::DbState;
+
+/// Feeds image item ids to the OCR worker.
+pub struct OcrQueue(Mutex<Sender<String>>);
+
+/// Whether the `tesseract` command is installed.
+pub fn available() -> bool {
+    Command::new("tesseract")
+        .arg("--version")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// Runs tesseract on a PNG and returns the recognized text.
+fn recognize(png: &[u8], languages: &str) -> Result<String, String> {
+    let mut child = Command::new("tesseract")
+        .args(["stdin", "stdout", "-l", languages])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run tesseract: {}", e))?;
+    // Tesseract reads the whole image before writing anything.
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(png).map_err(|e| e.to_string())?;
+    }
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Recognizes the text of image item `id` unless OCR is off or it was done already.
+fn process(app: &AppHandle, id: &str) -> Result<(), String> {
+    let state = app.state::<DbState>();
+    let settings = state.get_settings();
+    if !settings.ocr {
+        return Ok(());
+    }
+    let Some(item) = state.get_item(id) else { return Ok(()) };
+    if item.r#type != "image" || item.ocr_text.is_some() {
+        return Ok(());
+    }
+    let png = crate::db::image_bytes(&item.content).ok_or("Image is not valid base64")?;
+    let text = recognize(&png, &settings.ocr_languages)?;
+    if state.set_ocr_text(id, text) {
+        let _ = state.save();
+        let _ = app.emit("clipboard-changed", state.get_history());
+    }
+    Ok(())
+}
+
+/// Queues image item `id` for recognition.
+pub fn enqueue(app: &AppHandle, id: &str) {
+    let _ = app.state::<OcrQueue>().0.lock().unwrap().send(id.to_string());
+}
+
+/// Queues every image that has not been through OCR yet.
+pub fn queue_missing(app: &AppHandle) {
+    if !app.state::<DbState>().get_settings().ocr {
+        return;
+    }
+    for item in app.state::<DbState>().get_history() {
+        if item.r#type == "image" && item.ocr_text.is_none() {
+            enqueue(app, &item.id);
+        }
+    }
+}
+
+/// Starts the worker that runs OCR on queued images one at a time, off the
+/// capture path, and queues images captured before OCR was turned on.
+pub fn start(app: &AppHandle) {
+    let (tx, rx) = mpsc::channel::<String>();
+    app.manage(OcrQueue(Mutex::new(tx)));
+    let worker = app.clone();
+    thread::spawn(move || {
+        let mut warned = false;
+        for id in rx {
+            if !available() {
+                if !warned {
+                    log::warn!("OCR is on but tesseract is not installed");
+                    warned = true;
+                }
+                continue;
+            }
+            if let Err(e) = process(&worker, &id) {
+                log::warn!("OCR failed for {}: {}", id, e);
+            }
+        }
+    });
+    queue_missing(app);
+}
@@ -0,0 +1,250 @@
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tauri_plugin_shell::ShellExt;
+use crate::clipboard::WatcherState;
+use crate::db::{ClipboardItem, DbState};
+use crate::selection::Snapshot;
+use crate::settings::PasteMode;
+
+/// Decodes the base64 payload of an image item.
+pub fn decode_image(content: &str) -> Option<image::DynamicImage> {
+    let bytes = crate::db::image_bytes(content)?;
+    image::load_from_memory(&bytes).ok()
+}
+
+/// Writes a history item to the system clipboard.
+pub fn write_item(app: &AppHandle, item: &ClipboardItem) {
+    let clip = app.clipboard();
+    if item.r#type == "text" {
+        let _ = clip.write_text(item.content.clone());
+    } else if item.r#type == "image" {
+        if item.formats.keys().any(|mime| mime.starts_with("image/")) && serve_image(item) {
+            return;
+        }
+        if let Some(img) = decode_image(&item.content) {
+            let rgba_img = img.to_rgba8();
+            let (width, height) = rgba_img.dimensions();
+            let pixels = rgba_img.into_raw();
+
+            let tauri_image = tauri::image::Image::new(&pixels, width, height);
+            let _ = clip.write_image(&tauri_image);
+        }
+    }
+}
+
+/// Offers an image item's converted encodings (JPEG, WebP) ahead of PNG so the
+/// target can pick one. Returns false if the clipboard could not be taken.
+fn serve_image(item: &ClipboardItem) -> bool {
+    use base64::Engine;
+    let engine = base64::engine::general_purpose::STANDARD;
+    let mut snapshot = Snapshot::default();
+    for (mime, data) in item.formats.iter().filter(|(mime, _)| mime.starts_with("image/")) {
+        if let Ok(bytes) = engine.decode(data) {
+            snapshot.formats.push((mime.clone(), bytes));
+        }
+    }
+    if let Some(png) = crate::db::image_bytes(&item.content) {
+        snapshot.formats.push(("image/png".to_string(), png));
+    }
+    match crate::selection::serve(snapshot) {
+        Ok(()) => true,
+        Err(e) => {
+            log::warn!("Could not offer converted image formats: {}", e);
+            false
+        }
+    }
+}
+
+/// Writes the sanitized plain-text form of an item to the clipboard.
+/// Returns false when the item has no textual representation.
+pub fn write_plain(app: &AppHandle, item: &ClipboardItem) -> bool {
+    match crate::plaintext::plain_text(item) {
+        Some(text) => {
+            app.state::<WatcherState>().skip_text(&text);
+            let _ = app.clipboard().write_text(text);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Counts a paste of history item `id` towards its usage statistics.
+/// Items that aren't in the history (e.g. an unsaved merge) are ignored.
+pub fn record_paste(app: &AppHandle, id: &str) {
+    let state = app.state::<DbState>();
+    if state.record_paste(id, chrono::Utc::now().timestamp_millis()) {
+        let _ = state.save();
+        let _ = app.emit("clipboard-changed", state.get_history());
+    }
+}
+
+/// How the content reaches the target window once it has focus again.
+enum Delivery {
+    /// Simulate ctrl+v (ctrl+shift+v in terminals).
+    Keystroke,
+    /// Type the text key by key.
+    Type(String),
+    /// Paste, then put the saved clipboard contents back.
+    KeystrokeAndRestore(Snapshot),
+}
+
+/// Saves everything on the clipboard before a transient paste. The watcher is
+/// held until the contents are restored so neither the pasted item nor the
+/// restored contents are captured again.
+fn save_clipboard(app: &AppHandle) -> Option<Snapshot> {
+    let watcher = app.state::<WatcherState>();
+    watcher.hold();
+    match crate::selection::read_all() {
+        Ok(snapshot) if !snapshot.is_empty() => Some(snapshot),
+        Ok(_) => {
+            watcher.release();
+            None
+        }
+        Err(e) => {
+            log::warn!("Could not save clipboard for transient paste: {}", e);
+            watcher.release();
+            None
+        }
+    }
+}
+
+/// Writes the item to the clipboard and pastes it into the previously focused
+/// window, honouring the paste rule for that window. With transient paste
+/// enabled the previous clipboard contents are restored afterwards.
+pub fn paste_item(app: &AppHandle, item: &ClipboardItem) {
+    record_paste(app, &item.id);
+    let item = item.clone();
+    paste_with(app.clone(), 0, move |app, window_class| {
+        let settings = app.state::<DbState>().get_settings();
+        let mode = settings.paste_mode_for(window_class);
+        let saved = if settings.transient_paste && mode != PasteMode::TypeOut {
+            save_clipboard(app)
+        } else {
+            None
+        };
+        match mode {
+            PasteMode::TypeOut => {
+                if let Some(text) = crate::plaintext::plain_text(&item) {
+                    return Delivery::Type(text);
+                }
+                write_item(app, &item);
+            }
+            PasteMode::PlainText => {
+                if !write_plain(app, &item) {
+                    write_item(app, &item);
+                }
+            }
+            PasteMode::Default => write_item(app, &item),
+        }
+        match saved {
+            Some(snapshot) => Delivery::KeystrokeAndRestore(snapshot),
+            None => {
+                crate::clipboard::schedule_written(app, &item);
+                Delivery::Keystroke
+            }
+        }
+    });
+}
+
+/// Pastes only the sanitized `text/plain` form of the item.
+pub fn paste_plain(app: &AppHandle, item: &ClipboardItem) {
+    record_paste(app, &item.id);
+    let item = item.clone();
+    paste_with(app.clone(), 0, move |app, _| {
+        write_plain(app, &item);
+        crate::clipboard::schedule_written(app, &item);
+        Delivery::Keystroke
+    });
+}
+
+/// Types `text` into the previously focused window instead of pasting,
+/// leaving the clipboard untouched.
+pub fn type_out(app: &AppHandle, text: String) {
+    paste_with(app.clone(), 0, move |_, _| Delivery::Type(text));
+}
+
+/// Writes plain text to the clipboard, pastes it, then moves the caret
+/// `cursor_back` characters to the left (used by snippet `{cursor}` markers).
+pub fn paste_text(app: &AppHandle, text: &str, cursor_back: usize) {
+    let text = text.to_string();
+    paste_with(app.clone(), cursor_back, move |app, _| {
+        let _ = app.clipboard().write_text(text);
+        Delivery::Keystroke
+    });
+}
+
+/// Pastes whatever is already on the clipboard.
+pub fn send_paste(app: AppHandle) {
+    paste_with(app, 0, |_, _| Delivery::Keystroke);
+}
+
+/// Hides the main window and, once focus is back on the target window, calls
+/// `prepare` with that window's class and delivers the content the way it asks.
+/// Terminals get ctrl+shift+v, everything else ctrl+v.
+fn paste_with<F>(app: AppHandle, cursor_back: usize, prepare: F)
+where
+    F: FnOnce(&AppHandle, &str) -> Delivery + Send + 'static,
+{
+    if let Some(win) = app.get_webview_window("main") {
+        let _ = win.hide();
+    }
+
+    tauri::async_runtime::spawn(async move {
+        std::thread::sleep(std::time::Duration::from_millis(100)); // wait for focus switch
+         let shell = app.shell();
+
+         // Detect active window class
+         let output = shell.command("xdotool")
+            .args(["getactivewindow", "getwindowclassname"])
+            .output()
+            .await;
+
+         let mut class_name = String::new();
+         if let Ok(out) = output {
+             if out.status.success() {
+                 class_name = String::from_utf8_lossy(&out.stdout).trim().to_lowercase();
+             }
+         }
+         // Common terminal class names
+         let is_terminal = class_name.contains("term") ||
+            class_name.contains("alacritty") ||
+            class_name.contains("kitty") ||
+            class_name.contains("konsole");
+
+         let paste_key = if is_terminal { "ctrl+shift+v" } else { "ctrl+v" };
+         match prepare(&app, &class_name) {
+             Delivery::Keystroke => {
+                 let _ = shell.command("xdotool")
+                     .args(["key", "--clearmodifiers", paste_key])
+                     .output()
+                     .await;
+             }
+             Delivery::Type(text) => {
+                 if let Err(e) = crate::typeout::type_text(&app, &text).await {
+                     log::error!("Failed to type text: {}", e);
+                 }
+             }
+             Delivery::KeystrokeAndRestore(snapshot) => {
+                 let _ = shell.command("xdotool")
+                     .args(["key", "--clearmodifiers", paste_key])
+                     .output()
+                     .await;
+                 // The target reads the clipboard asynchronously after the keystroke.
+                 let delay = app.state::<DbState>().get_settings().restore_delay_ms;
+                 std::thread::sleep(std::time::Duration::from_millis(delay as u64));
+                 if let Err(e) = crate::selection::serve(snapshot) {
+                     log::error!("Failed to restore clipboard: {}", e);
+                 }
+                 crate::clipboard::mark_seen(&app);
+                 app.state::<WatcherState>().release();
+             }
+         }
+
+         if cursor_back > 0 {
+             let repeat = cursor_back.to_string();
+             let _ = shell.command("xdotool")
+                 .args(["key", "--clearmodifiers", "--delay", "0", "--repeat", repeat.as_str(), "Left"])
+                 .spawn();
+         }
+    });
+}
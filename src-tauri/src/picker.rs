@@ -0,0 +1,293 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use tauri::{AppHandle, Manager};
+use crate::db::{ClipboardItem, DbState};
+
+const PREVIEW_WIDTH: usize = 80;
+const THUMBNAIL_SIZE: u32 = 64;
+
+/// dmenu-compatible programs the picker knows how to drive.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Launcher {
+    Dmenu,
+    Rofi,
+    Fuzzel,
+    Wofi,
+    Bemenu,
+    /// Any other command line that reads entries on stdin and prints the choice.
+    Custom(String),
+}
+
+impl Launcher {
+    fn parse(name: &str) -> Self {
+        match name {
+            "dmenu" => Launcher::Dmenu,
+            "rofi" => Launcher::Rofi,
+            "fuzzel" => Launcher::Fuzzel,
+            "wofi" => Launcher::Wofi,
+            "bemenu" => Launcher::Bemenu,
+            other => Launcher::Custom(other.to_string()),
+        }
+    }
+
+    fn command(&self) -> Option<Command> {
+        let (program, args): (&str, &[&str]) = match self {
+            Launcher::Dmenu => ("dmenu", &["-i", "-l", "15", "-p", "Clipboard"]),
+            Launcher::Rofi => ("rofi", &["-dmenu", "-i", "-show-icons", "-p", "Clipboard"]),
+            Launcher::Fuzzel => ("fuzzel", &["--dmenu", "--prompt", "Clipboard: "]),
+            Launcher::Wofi => ("wofi", &["--dmenu", "--insensitive", "--prompt", "Clipboard"]),
+            Launcher::Bemenu => ("bemenu", &["-i", "-l", "15", "-p", "Clipboard"]),
+            Launcher::Custom(cmdline) => {
+                if cmdline.trim().is_empty() {
+                    return None;
+                }
+                // Through the shell, so quoted arguments (`-p "Clip board"`) survive.
+                let mut cmd = Command::new("sh");
+                cmd.arg("-c").arg(cmdline);
+                return Some(cmd);
+            }
+        };
+        let mut cmd = Command::new(program);
+        cmd.args(args);
+        Some(cmd)
+    }
+
+    /// Launchers that understand the rofi `\0icon\x1f<path>` row extension.
+    fn supports_icons(&self) -> bool {
+        matches!(self, Launcher::Rofi | Launcher::Fuzzel)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PickerOptions {
+    pub launcher: Launcher,
+    pub width: usize,
+}
+
+/// Parses `pick [--launcher <name|command>] [--width <chars>]` out of the
+/// process arguments. Returns `None` when the `pick` subcommand is absent.
+pub fn parse_args(args: &[String]) -> Option<PickerOptions> {
+    let pos = args.iter().skip(1).position(|a| a == "pick")? + 1;
+
+    let mut options = PickerOptions {
+        launcher: Launcher::Dmenu,
+        width: PREVIEW_WIDTH,
+    };
+
+    let mut rest = args[pos + 1..].iter();
+    while let Some(arg) = rest.next() {
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) => (flag, Some(value.to_string())),
+            None => (arg.as_str(), None),
+        };
+        let value = inline_value.or_else(|| rest.next().cloned());
+        match (flag, value) {
+            ("--launcher", Some(v)) => options.launcher = Launcher::parse(&v),
+            ("--width", Some(v)) => {
+                if let Ok(w) = v.parse::<usize>() {
+                    options.width = w.max(10);
+                }
+            }
+            _ => log::warn!("Ignoring unknown picker argument: {}", arg),
+        }
+    }
+
+    Some(options)
+}
+
+/// Runs the picker on a background thread so it never blocks the event loop.
+pub fn spawn(app: AppHandle, options: PickerOptions) {
+    thread::spawn(move || {
+        if let Err(e) = run(&app, &options) {
+            log::error!("Picker failed: {}", e);
+        }
+    });
+}
+
+fn run(app: &AppHandle, options: &PickerOptions) -> Result<(), String> {
+    let state = app.state::<DbState>();
+    let history = state.get_history();
+    if history.is_empty() {
+        return Ok(());
+    }
+
+    let thumb_dir = if options.launcher.supports_icons() {
+        app.path().app_cache_dir().ok().map(|dir| dir.join("thumbnails"))
+    } else {
+        None
+    };
+
+    let input = history
+        .iter()
+        .map(|item| format_entry(item, options.width, thumb_dir.as_deref()))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let mut cmd = options.launcher.command().ok_or("Empty launcher command")?;
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to start launcher: {}", e))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(input.as_bytes()).map_err(|e| e.to_string())?;
+    }
+
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        // Launcher dismissed (Escape) - nothing to paste.
+        return Ok(());
+    }
+
+    let choice = String::from_utf8_lossy(&output.stdout);
+    if let Some(item) = parse_selection(choice.trim_end_matches('\n'), &history) {
+        crate::paste::paste_item(app, item);
+    }
+    Ok(())
+}
+
+/// Builds one launcher row: `<short id>  <pin marker><preview>`, plus an icon
+/// hint for images when a thumbnail directory is given.
+fn format_entry(item: &ClipboardItem, width: usize, thumb_dir: Option<&Path>) -> String {
+    let marker = if item.is_pinned { "★ " } else { "" };
+
+    if item.r#type == "image" {
+        let image = crate::paste::decode_image(&item.content);
+        let label = match &image {
+            Some(img) => format!("[image {}x{}]", img.width(), img.height()),
+            None => "[image]".to_string(),
+        };
+        let mut line = format!("{}  {}{}", short_id(&item.id), marker, label);
+        if let (Some(dir), Some(img)) = (thumb_dir, image) {
+            if let Some(path) = write_thumbnail(dir, &item.id, &img) {
+                line.push_str(&format!("\0icon\x1f{}", path.display()));
+            }
+        }
+        return line;
+    }
+
+    format!("{}  {}{}", short_id(&item.id), marker, preview(&item.content, width))
+}
+
+/// Resolves the row printed by the launcher back to a history item.
+fn parse_selection<'a>(line: &str, history: &'a [ClipboardItem]) -> Option<&'a ClipboardItem> {
+    let id = line.split_whitespace().next()?;
+    history.iter().find(|item| short_id(&item.id) == id)
+}
+
+fn short_id(id: &str) -> &str {
+    id.get(..8).unwrap_or(id)
+}
+
+/// Collapses whitespace onto a single line and truncates to `width` chars.
+fn preview(content: &str, width: usize) -> String {
+    let flat = content.split_whitespace().collect::<Vec<_>>().join(" ");
+    if flat.chars().count() <= width {
+        return flat;
+    }
+    let mut truncated: String = flat.chars().take(width - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+fn write_thumbnail(dir: &Path, id: &str, img: &image::DynamicImage) -> Option<PathBuf> {
+    let path = dir.join(format!("{}.png", id));
+    if path.exists() {
+        return Some(path);
+    }
+    std::fs::create_dir_all(dir).ok()?;
+    img.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE)
+        .save_with_format(&path, image::ImageFormat::Png)
+        .ok()?;
+    Some(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|a| a.to_string()).collect()
+    }
+
+    fn item(id: &str, content: &str) -> ClipboardItem {
+        ClipboardItem {
+            id: id.to_string(),
+            r#type: "text".to_string(),
+            content: content.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn parse_args_needs_the_pick_subcommand() {
+        assert!(parse_args(&args(&["clipboard"])).is_none());
+        let options = parse_args(&args(&["clipboard", "pick"])).unwrap();
+        assert_eq!(options.launcher, Launcher::Dmenu);
+        assert_eq!(options.width, PREVIEW_WIDTH);
+    }
+
+    #[test]
+    fn parse_args_reads_both_flag_forms() {
+        let options = parse_args(&args(&["clipboard", "pick", "--launcher", "rofi", "--width=40"])).unwrap();
+        assert_eq!(options.launcher, Launcher::Rofi);
+        assert_eq!(options.width, 40);
+
+        let options =
+            parse_args(&args(&["clipboard", "pick", "--launcher=rofi -dmenu -p \"Clip board\"", "--width", "2"])).unwrap();
+        assert_eq!(options.launcher, Launcher::Custom("rofi -dmenu -p \"Clip board\"".to_string()));
+        assert_eq!(options.width, 10, "widths are clamped to a usable minimum");
+    }
+
+    #[test]
+    fn custom_launchers_run_through_the_shell() {
+        let command = Launcher::Custom("rofi -p \"Clip board\"".to_string()).command().unwrap();
+        assert_eq!(command.get_program(), "sh");
+        let args: Vec<_> = command.get_args().collect();
+        assert_eq!(args, ["-c", "rofi -p \"Clip board\""]);
+        assert!(Launcher::Custom("  ".to_string()).command().is_none());
+    }
+
+    #[test]
+    fn text_entries_are_flattened_and_truncated() {
+        let mut pinned = item("0123456789abcdef", "first line\n  second\tline");
+        pinned.is_pinned = true;
+        assert_eq!(format_entry(&pinned, 80, None), "01234567  ★ first line second line");
+        assert_eq!(format_entry(&item("abc", "a long line of text"), 10, None), "abc  a long li…");
+    }
+
+    #[test]
+    fn image_entries_carry_a_rofi_icon() {
+        use base64::Engine;
+        let mut png = Vec::new();
+        image::RgbaImage::new(3, 2)
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+        let image = ClipboardItem {
+            r#type: "image".to_string(),
+            content: base64::engine::general_purpose::STANDARD.encode(png),
+            ..item(&uuid::Uuid::new_v4().to_string(), "")
+        };
+        let dir = std::env::temp_dir().join(format!("clipboard-thumbs-{}", uuid::Uuid::new_v4()));
+
+        let plain = format_entry(&image, 80, None);
+        assert_eq!(plain, format!("{}  [image 3x2]", short_id(&image.id)));
+        let with_icon = format_entry(&image, 80, Some(&dir));
+        let thumbnail = dir.join(format!("{}.png", image.id));
+        assert_eq!(with_icon, format!("{}\0icon\x1f{}", plain, thumbnail.display()));
+        assert!(thumbnail.exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn picked_lines_resolve_to_items() {
+        let history = vec![item("0123456789", "one"), item("abcdefghij", "two")];
+        let line = format_entry(&history[1], 80, None);
+        assert_eq!(parse_selection(&line, &history).unwrap().id, "abcdefghij");
+        assert!(parse_selection("zzzzzzzz  three", &history).is_none());
+        assert!(parse_selection("", &history).is_none());
+    }
+}
@@ -0,0 +1,351 @@
+use crate::db::ClipboardItem;
+
+pub const MIME_HTML: &str = "text/html";
+pub const MIME_RTF: &str = "text/rtf";
+
+/// The sanitized `text/plain` form of an item: its own text when it has some,
+/// otherwise text extracted from its HTML or RTF representation.
+pub fn plain_text(item: &ClipboardItem) -> Option<String> {
+    let raw = if item.r#type == "text" && !item.content.is_empty() {
+        item.content.clone()
+    } else if let Some(html) = item.formats.get(MIME_HTML) {
+        html_to_text(html)
+    } else if let Some(rtf) = item.formats.get(MIME_RTF) {
+        rtf_to_text(rtf)
+    } else {
+        return None;
+    };
+    Some(sanitize(&raw))
+}
+
+/// Drops invisible and control characters (other than newlines and tabs),
+/// turns non-breaking spaces into plain ones and trims trailing whitespace.
+pub fn sanitize(text: &str) -> String {
+    let cleaned: String = text
+        .chars()
+        .filter_map(|c| match c {
+            '\u{200B}' | '\u{200C}' | '\u{200D}' | '\u{2060}' | '\u{FEFF}' | '\u{00AD}' => None,
+            '\u{00A0}' | '\u{202F}' => Some(' '),
+            '\n' | '\t' => Some(c),
+            c if c.is_control() => None,
+            c => Some(c),
+        })
+        .collect();
+    let lines: Vec<&str> = cleaned.lines().map(str::trim_end).collect();
+    lines.join("\n").trim_matches('\n').to_string()
+}
+
+const BLOCK_TAGS: &[&str] = &[
+    "p", "div", "br", "li", "tr", "h1", "h2", "h3", "h4", "h5", "h6", "blockquote", "pre", "ul", "ol", "table", "hr",
+];
+
+/// Extracts readable text from an HTML fragment: tags are dropped, block
+/// elements become line breaks and `<script>`/`<style>` bodies are skipped.
+pub fn html_to_text(html: &str) -> String {
+    let mut out = String::new();
+    let mut rest = html;
+    let mut skip_until: Option<&str> = None;
+
+    while !rest.is_empty() {
+        if let Some(waiting) = skip_until.take() {
+            // Raw text elements end only at their own closing tag.
+            let closing = format!("</{}", waiting);
+            let Some(at) = rest.to_ascii_lowercase().find(&closing) else { break };
+            rest = &rest[at..];
+            rest = rest.find('>').map_or("", |end| &rest[end + 1..]);
+            continue;
+        }
+        let Some(start) = rest.find('<') else {
+            push_html_text(&mut out, rest);
+            break;
+        };
+        push_html_text(&mut out, &rest[..start]);
+        rest = &rest[start..];
+
+        if rest.starts_with("<!--") {
+            rest = rest.find("-->").map_or("", |end| &rest[end + 3..]);
+            continue;
+        }
+        let Some(end) = rest.find('>') else { break };
+        let tag = rest[1..end].trim();
+        rest = &rest[end + 1..];
+
+        let closing = tag.starts_with('/');
+        let name = tag
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or("")
+            .to_lowercase();
+
+        match name.as_str() {
+            "script" if !closing => skip_until = Some("script"),
+            "style" if !closing => skip_until = Some("style"),
+            "td" | "th" if closing => out.push('\t'),
+            n if BLOCK_TAGS.contains(&n) && !out.ends_with('\n') && !out.is_empty() => out.push('\n'),
+            _ => {}
+        }
+    }
+
+    out.lines().map(|l| l.trim_end_matches('\t').trim_end()).collect::<Vec<_>>().join("\n")
+}
+
+/// Appends a text node, collapsing whitespace the way a browser would and
+/// decoding the common character entities.
+fn push_html_text(out: &mut String, text: &str) {
+    let decoded = decode_entities(text);
+    for c in decoded.chars() {
+        if c.is_whitespace() && c != '\u{00A0}' {
+            if !out.is_empty() && !out.ends_with([' ', '\n', '\t']) {
+                out.push(' ');
+            }
+        } else {
+            out.push(c);
+        }
+    }
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let decoded = rest.find(';').filter(|&end| end <= 10).and_then(|end| {
+            let entity = &rest[1..end];
+            let c = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some('\u{00A0}'),
+                _ => entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                    .and_then(char::from_u32),
+            };
+            c.map(|c| (c, end))
+        });
+        match decoded {
+            Some((c, end)) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Destinations whose content is never visible text.
+const RTF_SKIPPED_GROUPS: &[&str] = &["fonttbl", "colortbl", "stylesheet", "info", "pict", "header", "footer", "generator"];
+
+/// Extracts the visible text of an RTF document.
+pub fn rtf_to_text(rtf: &str) -> String {
+    let mut out = String::new();
+    let chars: Vec<char> = rtf.chars().collect();
+    let mut i = 0;
+    // Depth of each open group and whether its content is skipped.
+    let mut skipped: Vec<bool> = vec![false];
+    // Characters to drop after a `\uN` escape (its ANSI fallback), per `\ucN`.
+    let mut fallback_len = 1;
+    let mut pending_fallback = 0;
+
+    while i < chars.len() {
+        let skipping = *skipped.last().unwrap_or(&false);
+        match chars[i] {
+            '{' => {
+                skipped.push(skipping);
+                i += 1;
+            }
+            '}' => {
+                skipped.pop();
+                i += 1;
+            }
+            '\\' => {
+                i += 1;
+                let Some(&next) = chars.get(i) else { break };
+                if !next.is_ascii_alphabetic() {
+                    i += 1;
+                    match next {
+                        '*' => {
+                            if let Some(s) = skipped.last_mut() {
+                                *s = true;
+                            }
+                        }
+                        '\'' => {
+                            let hex: String = chars[i..chars.len().min(i + 2)].iter().collect();
+                            i += hex.len();
+                            if pending_fallback > 0 {
+                                pending_fallback -= 1;
+                            } else if !skipping {
+                                if let Ok(byte) = u8::from_str_radix(&hex, 16) {
+                                    out.push(windows_1252(byte));
+                                }
+                            }
+                        }
+                        '\\' | '{' | '}' if !skipping => out.push(next),
+                        '~' if !skipping => out.push('\u{00A0}'),
+                        '\n' | '\r' if !skipping => out.push('\n'),
+                        _ => {}
+                    }
+                    continue;
+                }
+
+                let word_start = i;
+                while i < chars.len() && chars[i].is_ascii_alphabetic() {
+                    i += 1;
+                }
+                let word: String = chars[word_start..i].iter().collect();
+                let num_start = i;
+                if i < chars.len() && chars[i] == '-' {
+                    i += 1;
+                }
+                while i < chars.len() && chars[i].is_ascii_digit() {
+                    i += 1;
+                }
+                let param: Option<i32> = chars[num_start..i].iter().collect::<String>().parse().ok();
+                if i < chars.len() && chars[i] == ' ' {
+                    i += 1;
+                }
+
+                if RTF_SKIPPED_GROUPS.contains(&word.as_str()) {
+                    if let Some(s) = skipped.last_mut() {
+                        *s = true;
+                    }
+                    continue;
+                }
+                if skipping {
+                    continue;
+                }
+                match word.as_str() {
+                    "par" | "line" | "row" => out.push('\n'),
+                    "tab" | "cell" => out.push('\t'),
+                    "emdash" => out.push('\u{2014}'),
+                    "endash" => out.push('\u{2013}'),
+                    "lquote" => out.push('\u{2018}'),
+                    "rquote" => out.push('\u{2019}'),
+                    "ldblquote" => out.push('\u{201C}'),
+                    "rdblquote" => out.push('\u{201D}'),
+                    "bullet" => out.push('\u{2022}'),
+                    "uc" => fallback_len = param.unwrap_or(1).max(0) as usize,
+                    "u" => {
+                        if let Some(code) = param {
+                            // Values above 32767 are written as negative numbers.
+                            let code = if code < 0 { code + 65536 } else { code } as u32;
+                            if let Some(c) = char::from_u32(code) {
+                                out.push(c);
+                            }
+                            pending_fallback = fallback_len;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            '\r' | '\n' => i += 1,
+            c => {
+                i += 1;
+                if pending_fallback > 0 {
+                    pending_fallback -= 1;
+                } else if !skipping {
+                    out.push(c);
+                }
+            }
+        }
+    }
+    out
+}
+
+/// RTF `\'hh` escapes use the document code page, which is almost always 1252.
+fn windows_1252(byte: u8) -> char {
+    const HIGH: [char; 32] = [
+        '€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8D}', 'Ž', '\u{8F}',
+        '\u{90}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{9D}', 'ž', 'Ÿ',
+    ];
+    match byte {
+        0x80..=0x9F => HIGH[(byte - 0x80) as usize],
+        _ => byte as char,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn item(r#type: &str, content: &str, formats: &[(&str, &str)]) -> ClipboardItem {
+        ClipboardItem {
+            r#type: r#type.to_string(),
+            content: content.to_string(),
+            formats: formats.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect::<BTreeMap<_, _>>(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn sanitize_drops_invisible_characters() {
+        assert_eq!(sanitize("\n a\u{200B}b\u{00A0}c \t\u{7}\n\nd  \n\n"), " ab c\n\nd");
+    }
+
+    #[test]
+    fn plain_text_prefers_own_text_then_html_then_rtf() {
+        let html = item("image", "", &[(MIME_HTML, "<b>bold</b>"), (MIME_RTF, r"{\rtf1 rich}")]);
+        assert_eq!(plain_text(&html).as_deref(), Some("bold"));
+        let rtf = item("image", "", &[(MIME_RTF, r"{\rtf1 rich}")]);
+        assert_eq!(plain_text(&rtf).as_deref(), Some("rich"));
+        let text = item("text", "own ", &[(MIME_HTML, "<i>other</i>")]);
+        assert_eq!(plain_text(&text).as_deref(), Some("own"));
+        assert_eq!(plain_text(&item("image", "png", &[])), None);
+    }
+
+    #[test]
+    fn html_entities_are_decoded() {
+        assert_eq!(
+            html_to_text("a &amp; b &lt;c&gt; &quot;d&quot; &#39;e&#x27; &eacute; &#129408;"),
+            "a & b <c> \"d\" 'e' &eacute; 🦀"
+        );
+    }
+
+    #[test]
+    fn html_blocks_and_breaks_become_lines() {
+        let html = "<div>one <span>two</span></div><p>three<br>four<br/>\n  five</p><ul><li>six</li><li>seven</li></ul>";
+        assert_eq!(html_to_text(html), "one two\nthree\nfour\nfive\nsix\nseven");
+    }
+
+    #[test]
+    fn html_whitespace_collapses_and_tables_use_tabs() {
+        let html = "<table><tr><td>a</td><td>b</td></tr><tr><th>c</th><td>d</td></tr></table>";
+        assert_eq!(html_to_text(html), "a\tb\nc\td");
+        assert_eq!(html_to_text("  lots \n\n of   space  "), "lots of space");
+    }
+
+    #[test]
+    fn html_skips_scripts_styles_and_comments() {
+        let html = "<style>p { color: red }</style>x<!-- <b>hidden</b> --><SCRIPT>if (a < b) {}</SCRIPT>y";
+        assert_eq!(html_to_text(html), "xy");
+    }
+
+    #[test]
+    fn rtf_escapes_and_control_words() {
+        let rtf = r"{\rtf1\ansi{\fonttbl{\f0 Arial;}}\f0 caf\'e9 \'93q\'94\par \{x\}\tab y\emdash z}";
+        assert_eq!(rtf_to_text(rtf), "café “q”\n{x}\ty—z");
+    }
+
+    #[test]
+    fn rtf_unicode_skips_its_fallback() {
+        assert_eq!(rtf_to_text(r"{\rtf1 \u8364?\uc2\u-3972 ??x}"), "€\u{f07c}x");
+    }
+
+    #[test]
+    fn rtf_nested_skipped_groups_end_with_their_group() {
+        let rtf = r"{\rtf1{\*\generator Word{\b inner}}{\info{\title T}}a{\b b}c}";
+        assert_eq!(rtf_to_text(rtf), "abc");
+    }
+}
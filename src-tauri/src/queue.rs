@@ -0,0 +1,100 @@
+use std::sync::Mutex;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+use crate::clipboard::WatcherState;
+use crate::db::DbState;
+
+/// Items waiting to be pasted one after another with the "paste next" shortcut.
+#[derive(Serialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PasteQueue {
+    pub item_ids: Vec<String>,
+    /// Index of the next item to paste; equal to the length once everything was pasted.
+    pub position: usize,
+    /// While on, every newly captured item is appended to the queue.
+    pub collecting: bool,
+}
+
+impl PasteQueue {
+    pub fn remaining(&self) -> usize {
+        self.item_ids.len().saturating_sub(self.position)
+    }
+}
+
+#[derive(Default)]
+pub struct QueueState {
+    queue: Mutex<PasteQueue>,
+}
+
+impl QueueState {
+    pub fn get(&self) -> PasteQueue {
+        self.queue.lock().unwrap().clone()
+    }
+}
+
+/// Applies `change` to the queue, then refreshes the tray and notifies the UI.
+fn update(app: &AppHandle, change: impl FnOnce(&mut PasteQueue)) -> PasteQueue {
+    let queue = {
+        let state = app.state::<QueueState>();
+        let mut queue = state.queue.lock().unwrap();
+        change(&mut queue);
+        queue.clone()
+    };
+    crate::refresh_tray_tooltip(app);
+    let _ = app.emit("paste-queue-changed", queue.clone());
+    queue
+}
+
+/// Replaces the queue with `ids`, in that order, and starts from the first.
+pub fn set_items(app: &AppHandle, ids: Vec<String>) -> PasteQueue {
+    update(app, |queue| {
+        let mut seen = std::collections::HashSet::new();
+        queue.item_ids = ids.into_iter().filter(|id| seen.insert(id.clone())).collect();
+        queue.position = 0;
+    })
+}
+
+pub fn set_collecting(app: &AppHandle, collecting: bool) -> PasteQueue {
+    update(app, |queue| queue.collecting = collecting)
+}
+
+pub fn clear(app: &AppHandle) -> PasteQueue {
+    update(app, |queue| {
+        queue.item_ids.clear();
+        queue.position = 0;
+    })
+}
+
+/// Called for every captured item; appends it while collecting.
+pub fn on_captured(app: &AppHandle, id: &str) {
+    if app.state::<QueueState>().get().collecting {
+        update(app, |queue| queue.item_ids.push(id.to_string()));
+    }
+}
+
+/// Pastes the item at the current position and advances. Items deleted from
+/// the history in the meantime are skipped. Returns false when nothing is left.
+pub fn paste_next(app: &AppHandle) -> bool {
+    let db = app.state::<DbState>();
+    let mut next = None;
+    update(app, |queue| {
+        while queue.position < queue.item_ids.len() {
+            let id = &queue.item_ids[queue.position];
+            queue.position += 1;
+            if let Some(item) = db.get_item(id) {
+                next = Some(item);
+                break;
+            }
+        }
+    });
+
+    match next {
+        Some(item) => {
+            // Pasting a queued item must not feed it back into the queue.
+            app.state::<WatcherState>().skip_item(&item);
+            crate::paste::paste_item(app, &item);
+            true
+        }
+        None => false,
+    }
+}
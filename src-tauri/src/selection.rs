@@ -0,0 +1,311 @@
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+use x11rb::connection::{Connection, RequestConnection};
+use x11rb::protocol::xproto::{
+    Atom, AtomEnum, ConnectionExt as _, CreateWindowAux, EventMask, PropMode, Property,
+    SelectionNotifyEvent, SelectionRequestEvent, Window, WindowClass, SELECTION_NOTIFY_EVENT,
+};
+use x11rb::protocol::Event;
+use x11rb::rust_connection::RustConnection;
+use x11rb::wrapper::ConnectionExt as _;
+use x11rb::{COPY_DEPTH_FROM_PARENT, CURRENT_TIME, NONE};
+
+/// How long the current owner gets to answer a single conversion.
+const CONVERT_TIMEOUT: Duration = Duration::from_secs(2);
+/// Formats beyond this combined size are left out of a snapshot.
+const SNAPSHOT_LIMIT: usize = 64 * 1024 * 1024;
+/// Text targets without a MIME-style name that are still worth keeping.
+const TEXT_TARGETS: &[&str] = &["UTF8_STRING", "STRING", "TEXT"];
+/// Targets that describe the selection or act on it rather than hold data.
+/// They are never converted; `serve` answers the first two itself.
+const META_TARGETS: &[&str] = &[
+    "TARGETS", "TIMESTAMP", "MULTIPLE", "SAVE_TARGETS", "DELETE", "INSERT_SELECTION", "INSERT_PROPERTY",
+];
+
+/// Every format the clipboard offered at one point in time, in the owner's order.
+#[derive(Clone, Debug, Default)]
+pub struct Snapshot {
+    pub formats: Vec<(String, Vec<u8>)>,
+    /// Whether every data format offered was read and `serve` can hand each
+    /// one out in a single request, i.e. serving the snapshot loses nothing.
+    pub complete: bool,
+}
+
+impl Snapshot {
+    pub fn is_empty(&self) -> bool {
+        self.formats.is_empty()
+    }
+
+    pub fn get(&self, target: &str) -> Option<&[u8]> {
+        self.formats.iter().find(|(t, _)| t == target).map(|(_, d)| d.as_slice())
+    }
+}
+
+struct Client {
+    conn: RustConnection,
+    window: Window,
+    clipboard: Atom,
+}
+
+impl Client {
+    fn connect() -> Result<Self, String> {
+        let (conn, screen_num) = x11rb::connect(None).map_err(|e| e.to_string())?;
+        let root = conn.setup().roots[screen_num].root;
+        let window = conn.generate_id().map_err(|e| e.to_string())?;
+        conn.create_window(
+            COPY_DEPTH_FROM_PARENT,
+            window,
+            root,
+            0, 0, 1, 1, 0,
+            WindowClass::INPUT_OUTPUT,
+            0,
+            &CreateWindowAux::new().event_mask(EventMask::PROPERTY_CHANGE),
+        )
+        .map_err(|e| e.to_string())?;
+        let mut client = Self { conn, window, clipboard: NONE };
+        client.clipboard = client.atom("CLIPBOARD")?;
+        Ok(client)
+    }
+
+    fn atom(&self, name: &str) -> Result<Atom, String> {
+        Ok(self
+            .conn
+            .intern_atom(false, name.as_bytes())
+            .map_err(|e| e.to_string())?
+            .reply()
+            .map_err(|e| e.to_string())?
+            .atom)
+    }
+
+    fn atom_name(&self, atom: Atom) -> Result<String, String> {
+        let reply = self
+            .conn
+            .get_atom_name(atom)
+            .map_err(|e| e.to_string())?
+            .reply()
+            .map_err(|e| e.to_string())?;
+        Ok(String::from_utf8_lossy(&reply.name).into_owned())
+    }
+
+    fn wait_for<T>(&self, mut pick: impl FnMut(Event) -> Option<T>) -> Result<T, String> {
+        let deadline = Instant::now() + CONVERT_TIMEOUT;
+        loop {
+            match self.conn.poll_for_event().map_err(|e| e.to_string())? {
+                Some(event) => {
+                    if let Some(found) = pick(event) {
+                        return Ok(found);
+                    }
+                }
+                None if Instant::now() >= deadline => {
+                    return Err("Clipboard owner did not respond".to_string())
+                }
+                None => thread::sleep(Duration::from_millis(5)),
+            }
+        }
+    }
+
+    /// Asks the owner to convert the clipboard to `target` and returns the data,
+    /// following the INCR protocol for large transfers.
+    fn convert(&self, target: Atom) -> Result<Option<Vec<u8>>, String> {
+        let property = self.atom("LINUX_CLIPBOARD_DATA")?;
+        let incr = self.atom("INCR")?;
+        self.conn
+            .convert_selection(self.window, self.clipboard, target, property, CURRENT_TIME)
+            .map_err(|e| e.to_string())?;
+        self.conn.flush().map_err(|e| e.to_string())?;
+
+        let window = self.window;
+        let notify = self.wait_for(|event| match event {
+            Event::SelectionNotify(e) if e.requestor == window => Some(e),
+            _ => None,
+        })?;
+        if notify.property == NONE {
+            return Ok(None);
+        }
+
+        let reply = self.read_property(property)?;
+        if reply.0 != incr {
+            return Ok(Some(reply.1));
+        }
+
+        // INCR: deleting the property (done by read_property) asks for the next chunk;
+        // a zero-length chunk ends the transfer.
+        let mut data = Vec::new();
+        loop {
+            self.wait_for(|event| match event {
+                Event::PropertyNotify(e)
+                    if e.window == window && e.atom == property && e.state == Property::NEW_VALUE =>
+                {
+                    Some(())
+                }
+                _ => None,
+            })?;
+            let (_, chunk) = self.read_property(property)?;
+            if chunk.is_empty() {
+                return Ok(Some(data));
+            }
+            data.extend_from_slice(&chunk);
+            if data.len() > SNAPSHOT_LIMIT {
+                return Err("Clipboard contents are too large".to_string());
+            }
+        }
+    }
+
+    /// The formats the current owner offers.
+    fn targets(&self) -> Result<Vec<Atom>, String> {
+        let targets_atom = self.atom("TARGETS")?;
+        let raw = self.convert(targets_atom)?.unwrap_or_default();
+        Ok(raw.chunks_exact(4).map(|b| u32::from_ne_bytes([b[0], b[1], b[2], b[3]])).collect())
+    }
+
+    /// Reads and deletes a property on our window, returning its type and bytes.
+    fn read_property(&self, property: Atom) -> Result<(Atom, Vec<u8>), String> {
+        let reply = self
+            .conn
+            .get_property(true, self.window, property, AtomEnum::ANY, 0, u32::MAX)
+            .map_err(|e| e.to_string())?
+            .reply()
+            .map_err(|e| e.to_string())?;
+        self.conn.flush().map_err(|e| e.to_string())?;
+        Ok((reply.type_, reply.value))
+    }
+}
+
+fn is_worth_keeping(target: &str) -> bool {
+    target.contains('/') || TEXT_TARGETS.contains(&target)
+}
+
+/// Reads every data format currently offered on the clipboard.
+pub fn read_all() -> Result<Snapshot, String> {
+    let client = Client::connect()?;
+    // Larger formats would need INCR, which `serve` does not implement.
+    let max_bytes = client.conn.maximum_request_bytes().saturating_sub(64);
+    let mut snapshot = Snapshot { complete: true, ..Default::default() };
+    let mut total = 0;
+    for atom in client.targets()? {
+        let name = client.atom_name(atom)?;
+        if META_TARGETS.contains(&name.as_str()) || snapshot.get(&name).is_some() {
+            continue;
+        }
+        if !is_worth_keeping(&name) {
+            snapshot.complete = false;
+            continue;
+        }
+        match client.convert(atom) {
+            Ok(Some(data)) if total + data.len() <= SNAPSHOT_LIMIT => {
+                total += data.len();
+                snapshot.complete &= data.len() <= max_bytes;
+                snapshot.formats.push((name, data));
+            }
+            Ok(_) => snapshot.complete = false,
+            Err(e) => {
+                log::warn!("Skipping clipboard format {}: {}", name, e);
+                snapshot.complete = false;
+            }
+        }
+    }
+    Ok(snapshot)
+}
+
+/// Whether the clipboard currently offers `target`, without fetching any data.
+pub fn offers(target: &str) -> Result<bool, String> {
+    let client = Client::connect()?;
+    let wanted = client.atom(target)?;
+    Ok(client.targets()?.contains(&wanted))
+}
+
+/// Takes ownership of the clipboard and serves `snapshot` from a background
+/// thread until another application takes it over. Returns once ownership
+/// has been acquired.
+pub fn serve(snapshot: Snapshot) -> Result<(), String> {
+    let (ready_tx, ready_rx) = mpsc::channel();
+    thread::spawn(move || {
+        let client = match Client::connect() {
+            Ok(client) => client,
+            Err(e) => {
+                let _ = ready_tx.send(Err(e));
+                return;
+            }
+        };
+        if let Err(e) = serve_on(&client, &snapshot, &ready_tx) {
+            // Only reaches the caller if ownership was never acquired.
+            if ready_tx.send(Err(e.clone())).is_err() {
+                log::error!("Clipboard owner stopped: {}", e);
+            }
+        }
+    });
+    ready_rx
+        .recv_timeout(CONVERT_TIMEOUT)
+        .map_err(|_| "Timed out taking clipboard ownership".to_string())?
+}
+
+fn serve_on(
+    client: &Client,
+    snapshot: &Snapshot,
+    ready: &mpsc::Sender<Result<(), String>>,
+) -> Result<(), String> {
+    let conn = &client.conn;
+    let targets_atom = client.atom("TARGETS")?;
+    let timestamp_atom = client.atom("TIMESTAMP")?;
+    let mut offered: Vec<(Atom, &[u8])> = Vec::new();
+    for (name, data) in &snapshot.formats {
+        offered.push((client.atom(name)?, data));
+    }
+    let mut target_list: Vec<Atom> = vec![targets_atom, timestamp_atom];
+    target_list.extend(offered.iter().map(|(atom, _)| *atom));
+    let max_bytes = conn.maximum_request_bytes().saturating_sub(64);
+
+    conn.set_selection_owner(client.window, client.clipboard, CURRENT_TIME)
+        .map_err(|e| e.to_string())?;
+    let owner = conn
+        .get_selection_owner(client.clipboard)
+        .map_err(|e| e.to_string())?
+        .reply()
+        .map_err(|e| e.to_string())?
+        .owner;
+    if owner != client.window {
+        return Err("Could not take clipboard ownership".to_string());
+    }
+    let _ = ready.send(Ok(()));
+
+    loop {
+        match conn.wait_for_event().map_err(|e| e.to_string())? {
+            Event::SelectionRequest(req) => {
+                let property = if req.property == NONE { req.target } else { req.property };
+                let answered = if req.target == targets_atom {
+                    conn.change_property32(PropMode::REPLACE, req.requestor, property, AtomEnum::ATOM, &target_list)
+                        .is_ok()
+                } else if req.target == timestamp_atom {
+                    conn.change_property32(PropMode::REPLACE, req.requestor, property, AtomEnum::INTEGER, &[CURRENT_TIME])
+                        .is_ok()
+                } else {
+                    match offered.iter().find(|(atom, _)| *atom == req.target) {
+                        Some((atom, data)) if data.len() <= max_bytes => conn
+                            .change_property8(PropMode::REPLACE, req.requestor, property, *atom, data)
+                            .is_ok(),
+                        _ => false,
+                    }
+                };
+                notify(conn, &req, if answered { property } else { NONE })?;
+            }
+            Event::SelectionClear(e) if e.selection == client.clipboard => return Ok(()),
+            _ => {}
+        }
+    }
+}
+
+fn notify(conn: &RustConnection, req: &SelectionRequestEvent, property: Atom) -> Result<(), String> {
+    let event = SelectionNotifyEvent {
+        response_type: SELECTION_NOTIFY_EVENT,
+        sequence: 0,
+        time: req.time,
+        requestor: req.requestor,
+        selection: req.selection,
+        target: req.target,
+        property,
+    };
+    conn.send_event(false, req.requestor, EventMask::NO_EVENT, event)
+        .map_err(|e| e.to_string())?;
+    conn.flush().map_err(|e| e.to_string())
+}
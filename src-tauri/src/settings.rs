@@ -0,0 +1,694 @@
+use std::fmt;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+pub const ZOOM_RANGE: (i32, i32) = (70, 200);
+pub const TRASH_RETENTION_RANGE: (u32, u32) = (1, 90);
+pub const TYPE_DELAY_RANGE: (u32, u32) = (0, 1000);
+pub const RESTORE_DELAY_RANGE: (u32, u32) = (100, 10_000);
+pub const SYNC_INTERVAL_RANGE: (u32, u32) = (5, 3600);
+pub const AUTO_CLEAR_RANGE: (u32, u32) = (5, 3600);
+pub const SUPPORTED_LANGUAGES: &[&str] = &["en", "pt-BR"];
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum WindowPosition {
+    #[default]
+    Cursor,
+    Fixed,
+    Center,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Grouping {
+    #[default]
+    Categorized,
+    #[serde(rename = "none")]
+    Flat,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    #[default]
+    Dark,
+    Light,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Settings {
+    #[serde(deserialize_with = "lenient")]
+    pub position: WindowPosition,
+    #[serde(deserialize_with = "lenient")]
+    pub grouping: Grouping,
+    pub zoom: i32,
+    #[serde(deserialize_with = "lenient")]
+    pub theme: Theme,
+    pub language: Option<String>,
+    #[serde(default)]
+    pub use_internal_shortcut: bool,
+    #[serde(default = "default_toggle_shortcut")]
+    pub toggle_shortcut: String,
+    #[serde(default)]
+    pub hotkeys: Hotkeys,
+    /// Days a deleted item stays in the trash before it is purged.
+    #[serde(default = "default_trash_retention_days")]
+    pub trash_retention_days: u32,
+    /// How pastes into specific applications are performed.
+    #[serde(default)]
+    pub paste_rules: Vec<PasteRule>,
+    /// Delay between keystrokes when typing an item out.
+    #[serde(default = "default_type_delay_ms")]
+    pub type_delay_ms: u32,
+    /// Put the previous clipboard contents back after pasting a history item.
+    #[serde(default)]
+    pub transient_paste: bool,
+    #[serde(default = "default_restore_delay_ms")]
+    pub restore_delay_ms: u32,
+    /// Shared folder (Syncthing, NFS, ...) holding every device's change log;
+    /// `None` turns sync off.
+    #[serde(default)]
+    pub sync_dir: Option<String>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub sync_scope: SyncScope,
+    #[serde(default = "default_sync_interval_secs")]
+    pub sync_interval_secs: u32,
+    #[serde(default, deserialize_with = "lenient")]
+    pub dedup: DedupStrategy,
+    /// Treat nearly identical images (by perceptual hash) as duplicates.
+    #[serde(default)]
+    pub similar_images: bool,
+    #[serde(default, deserialize_with = "lenient")]
+    pub history_order: HistoryOrder,
+    /// Which copies are wiped from the system clipboard after `auto_clear_secs`.
+    #[serde(default, deserialize_with = "lenient")]
+    pub auto_clear: AutoClear,
+    #[serde(default = "default_auto_clear_secs")]
+    pub auto_clear_secs: u32,
+    /// Re-offer every captured copy from this app so it outlives its source (X11).
+    #[serde(default)]
+    pub take_ownership: bool,
+    /// Recognize text in captured images with tesseract, for search.
+    #[serde(default)]
+    pub ocr: bool,
+    /// Tesseract `-l` value, e.g. `eng+por`.
+    #[serde(default = "default_ocr_languages")]
+    pub ocr_languages: String,
+}
+
+/// What happens when a copied item is already in the history.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum DedupStrategy {
+    /// Only an exact repeat of the newest item is dropped.
+    #[default]
+    NewestOnly,
+    /// The existing item moves to the top, keeping its pin, tags and copy count.
+    MoveToTop,
+    /// The existing item stays where it is.
+    Never,
+}
+
+/// How unpinned history items are ordered.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum HistoryOrder {
+    /// Newest first, or as arranged by dragging.
+    #[default]
+    Recent,
+    /// Most and most recently used first; see `ClipboardItem::frecency`.
+    Frecency,
+}
+
+/// Which copies `auto_clear` removes from the system clipboard. The history
+/// keeps them either way, except copies with a password manager hint, which
+/// are never stored.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum AutoClear {
+    #[default]
+    Off,
+    All,
+    /// Items marked sensitive, or offered with a password manager hint.
+    Sensitive,
+}
+
+/// Which items are written to the sync folder.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncScope {
+    /// Pinned items and items filed in collections.
+    #[default]
+    Pinned,
+    All,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum PasteMode {
+    #[default]
+    Default,
+    /// Only a sanitized `text/plain` version is offered to the target.
+    PlainText,
+    /// The text is typed as keystrokes, for targets that ignore paste.
+    TypeOut,
+}
+
+/// Applies `mode` when the focused window's class contains `app` (case-insensitive).
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PasteRule {
+    pub app: String,
+    pub mode: PasteMode,
+}
+
+impl Settings {
+    /// The mode of the first rule matching `window_class`.
+    pub fn paste_mode_for(&self, window_class: &str) -> PasteMode {
+        let class = window_class.to_lowercase();
+        self.paste_rules
+            .iter()
+            .find(|rule| class.contains(&rule.app.to_lowercase()))
+            .map(|rule| rule.mode)
+            .unwrap_or_default()
+    }
+}
+
+/// Optional global bindings for actions that work without opening the window.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Hotkeys {
+    pub paste_previous: Option<String>,
+    pub paste_pinned: Vec<PinnedHotkey>,
+    pub pause_capture: Option<String>,
+    pub clear_history: Option<String>,
+    pub paste_next_queued: Option<String>,
+}
+
+/// Pastes the `slot`-th pinned item (1-based, in history order).
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PinnedHotkey {
+    pub slot: usize,
+    pub shortcut: String,
+}
+
+fn default_toggle_shortcut() -> String {
+    if cfg!(target_os = "macos") { "Command+Control+V" } else { "Control+Alt+V" }.to_string()
+}
+
+fn default_trash_retention_days() -> u32 {
+    7
+}
+
+fn default_type_delay_ms() -> u32 {
+    12
+}
+
+fn default_restore_delay_ms() -> u32 {
+    500
+}
+
+fn default_sync_interval_secs() -> u32 {
+    30
+}
+
+fn default_auto_clear_secs() -> u32 {
+    30
+}
+
+fn default_ocr_languages() -> String {
+    "eng".to_string()
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            position: WindowPosition::Cursor,
+            grouping: Grouping::Categorized,
+            zoom: 100,
+            theme: Theme::Dark,
+            language: None,
+            use_internal_shortcut: false,
+            toggle_shortcut: default_toggle_shortcut(),
+            hotkeys: Hotkeys::default(),
+            trash_retention_days: default_trash_retention_days(),
+            paste_rules: Vec::new(),
+            type_delay_ms: default_type_delay_ms(),
+            transient_paste: false,
+            restore_delay_ms: default_restore_delay_ms(),
+            sync_dir: None,
+            sync_scope: SyncScope::Pinned,
+            sync_interval_secs: default_sync_interval_secs(),
+            dedup: DedupStrategy::NewestOnly,
+            similar_images: false,
+            history_order: HistoryOrder::Recent,
+            auto_clear: AutoClear::Off,
+            auto_clear_secs: default_auto_clear_secs(),
+            take_ownership: false,
+            ocr: false,
+            ocr_languages: default_ocr_languages(),
+        }
+    }
+}
+
+/// Stored values from older versions may no longer be valid; fall back to the
+/// default instead of failing to load the whole database.
+fn lenient<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned + Default,
+{
+    let value = serde_json::Value::deserialize(deserializer)?;
+    Ok(serde_json::from_value(value).unwrap_or_default())
+}
+
+/// Distinguishes an explicit `null` (clear the value) from a missing key.
+fn nullable<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+/// A typed, partial settings update. Every `Some` field replaces the stored value.
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct SettingsPatch {
+    pub position: Option<WindowPosition>,
+    pub grouping: Option<Grouping>,
+    pub zoom: Option<i32>,
+    pub theme: Option<Theme>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub language: Option<Option<String>>,
+    pub use_internal_shortcut: Option<bool>,
+    pub toggle_shortcut: Option<String>,
+    pub hotkeys: Option<Hotkeys>,
+    pub trash_retention_days: Option<u32>,
+    pub paste_rules: Option<Vec<PasteRule>>,
+    pub type_delay_ms: Option<u32>,
+    pub transient_paste: Option<bool>,
+    pub restore_delay_ms: Option<u32>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub sync_dir: Option<Option<String>>,
+    pub sync_scope: Option<SyncScope>,
+    pub sync_interval_secs: Option<u32>,
+    pub dedup: Option<DedupStrategy>,
+    pub similar_images: Option<bool>,
+    pub history_order: Option<HistoryOrder>,
+    pub auto_clear: Option<AutoClear>,
+    pub auto_clear_secs: Option<u32>,
+    pub take_ownership: Option<bool>,
+    pub ocr: Option<bool>,
+    pub ocr_languages: Option<String>,
+}
+
+impl SettingsPatch {
+    pub const KEYS: &'static [&'static str] = &[
+        "position",
+        "grouping",
+        "zoom",
+        "theme",
+        "language",
+        "useInternalShortcut",
+        "toggleShortcut",
+        "hotkeys",
+        "trashRetentionDays",
+        "pasteRules",
+        "typeDelayMs",
+        "transientPaste",
+        "restoreDelayMs",
+        "syncDir",
+        "syncScope",
+        "syncIntervalSecs",
+        "dedup",
+        "similarImages",
+        "historyOrder",
+        "autoClear",
+        "autoClearSecs",
+        "takeOwnership",
+        "ocr",
+        "ocrLanguages",
+    ];
+
+    /// Builds a patch for a single `key`/`value` pair as sent by the UI.
+    pub fn from_entry(key: &str, value: serde_json::Value) -> Result<Self, SettingsError> {
+        if !Self::KEYS.contains(&key) {
+            return Err(SettingsError::UnknownKey { key: key.to_string() });
+        }
+        let mut object = serde_json::Map::new();
+        object.insert(key.to_string(), value);
+        serde_json::from_value(serde_json::Value::Object(object)).map_err(|e| {
+            SettingsError::InvalidValue { key: key.to_string(), message: e.to_string() }
+        })
+    }
+
+    /// Validates the patch and returns `current` with it applied.
+    pub fn apply_to(self, current: &Settings) -> Result<Settings, SettingsError> {
+        let mut settings = current.clone();
+
+        if let Some(position) = self.position {
+            settings.position = position;
+        }
+        if let Some(grouping) = self.grouping {
+            settings.grouping = grouping;
+        }
+        if let Some(zoom) = self.zoom {
+            settings.zoom = in_range("zoom", zoom, ZOOM_RANGE)?;
+        }
+        if let Some(theme) = self.theme {
+            settings.theme = theme;
+        }
+        if let Some(language) = self.language {
+            if let Some(code) = &language {
+                if !SUPPORTED_LANGUAGES.contains(&code.as_str()) {
+                    return Err(SettingsError::UnsupportedLanguage { value: code.clone() });
+                }
+            }
+            settings.language = language;
+        }
+        if let Some(enabled) = self.use_internal_shortcut {
+            settings.use_internal_shortcut = enabled;
+        }
+        if let Some(shortcut) = self.toggle_shortcut {
+            let shortcut = shortcut.trim().to_string();
+            if shortcut.is_empty() {
+                return Err(SettingsError::InvalidValue {
+                    key: "toggleShortcut".to_string(),
+                    message: "shortcut cannot be empty".to_string(),
+                });
+            }
+            settings.toggle_shortcut = shortcut;
+        }
+        if let Some(hotkeys) = self.hotkeys {
+            settings.hotkeys = hotkeys;
+        }
+        if let Some(days) = self.trash_retention_days {
+            settings.trash_retention_days = in_range("trashRetentionDays", days, TRASH_RETENTION_RANGE)?;
+        }
+        if let Some(rules) = self.paste_rules {
+            let mut cleaned = Vec::with_capacity(rules.len());
+            for rule in rules {
+                let app = rule.app.trim().to_string();
+                if app.is_empty() {
+                    return Err(SettingsError::InvalidValue {
+                        key: "pasteRules".to_string(),
+                        message: "application cannot be empty".to_string(),
+                    });
+                }
+                cleaned.push(PasteRule { app, ..rule });
+            }
+            settings.paste_rules = cleaned;
+        }
+        if let Some(delay) = self.type_delay_ms {
+            settings.type_delay_ms = in_range("typeDelayMs", delay, TYPE_DELAY_RANGE)?;
+        }
+        if let Some(enabled) = self.transient_paste {
+            settings.transient_paste = enabled;
+        }
+        if let Some(delay) = self.restore_delay_ms {
+            settings.restore_delay_ms = in_range("restoreDelayMs", delay, RESTORE_DELAY_RANGE)?;
+        }
+        if let Some(dir) = self.sync_dir {
+            let dir = dir.map(|d| d.trim().to_string()).filter(|d| !d.is_empty());
+            if let Some(dir) = &dir {
+                if !std::path::Path::new(dir).is_absolute() {
+                    return Err(SettingsError::InvalidValue {
+                        key: "syncDir".to_string(),
+                        message: "sync folder must be an absolute path".to_string(),
+                    });
+                }
+            }
+            settings.sync_dir = dir;
+        }
+        if let Some(scope) = self.sync_scope {
+            settings.sync_scope = scope;
+        }
+        if let Some(secs) = self.sync_interval_secs {
+            settings.sync_interval_secs = in_range("syncIntervalSecs", secs, SYNC_INTERVAL_RANGE)?;
+        }
+        if let Some(dedup) = self.dedup {
+            settings.dedup = dedup;
+        }
+        if let Some(enabled) = self.similar_images {
+            settings.similar_images = enabled;
+        }
+        if let Some(order) = self.history_order {
+            settings.history_order = order;
+        }
+        if let Some(mode) = self.auto_clear {
+            settings.auto_clear = mode;
+        }
+        if let Some(secs) = self.auto_clear_secs {
+            settings.auto_clear_secs = in_range("autoClearSecs", secs, AUTO_CLEAR_RANGE)?;
+        }
+        if let Some(enabled) = self.take_ownership {
+            settings.take_ownership = enabled;
+        }
+        if let Some(enabled) = self.ocr {
+            settings.ocr = enabled;
+        }
+        if let Some(languages) = self.ocr_languages {
+            let languages = languages.trim().to_string();
+            let valid = !languages.is_empty()
+                && languages.split('+').all(|l| !l.is_empty() && l.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'));
+            if !valid {
+                return Err(SettingsError::InvalidValue {
+                    key: "ocrLanguages".to_string(),
+                    message: "use tesseract language codes joined by '+', e.g. eng+por".to_string(),
+                });
+            }
+            settings.ocr_languages = languages;
+        }
+
+        // Accelerator syntax and duplicate bindings.
+        crate::shortcuts::bindings(&settings)
+            .map_err(|message| SettingsError::Shortcut { message })?;
+
+        Ok(settings)
+    }
+}
+
+fn in_range<T>(key: &str, value: T, (min, max): (T, T)) -> Result<T, SettingsError>
+where
+    T: Into<i64> + PartialOrd + Copy,
+{
+    if value < min || value > max {
+        return Err(SettingsError::OutOfRange {
+            key: key.to_string(),
+            min: min.into(),
+            max: max.into(),
+            value: value.into(),
+        });
+    }
+    Ok(value)
+}
+
+/// Why a settings update was rejected.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SettingsError {
+    UnknownKey { key: String },
+    InvalidValue { key: String, message: String },
+    OutOfRange { key: String, min: i64, max: i64, value: i64 },
+    UnsupportedLanguage { value: String },
+    Shortcut { message: String },
+}
+
+impl SettingsError {
+    fn kind(&self) -> &'static str {
+        match self {
+            SettingsError::UnknownKey { .. } => "unknownKey",
+            SettingsError::InvalidValue { .. } => "invalidValue",
+            SettingsError::OutOfRange { .. } => "outOfRange",
+            SettingsError::UnsupportedLanguage { .. } => "unsupportedLanguage",
+            SettingsError::Shortcut { .. } => "shortcut",
+        }
+    }
+
+    fn key(&self) -> Option<&str> {
+        match self {
+            SettingsError::UnknownKey { key }
+            | SettingsError::InvalidValue { key, .. }
+            | SettingsError::OutOfRange { key, .. } => Some(key),
+            SettingsError::UnsupportedLanguage { .. } => Some("language"),
+            SettingsError::Shortcut { .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SettingsError::UnknownKey { key } => write!(f, "Unknown setting \"{}\"", key),
+            SettingsError::InvalidValue { key, message } => {
+                write!(f, "Invalid value for \"{}\": {}", key, message)
+            }
+            SettingsError::OutOfRange { key, min, max, value } => {
+                write!(f, "\"{}\" must be between {} and {} (got {})", key, min, max, value)
+            }
+            SettingsError::UnsupportedLanguage { value } => write!(
+                f,
+                "Unsupported language \"{}\" (supported: {})",
+                value,
+                SUPPORTED_LANGUAGES.join(", ")
+            ),
+            SettingsError::Shortcut { message } => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for SettingsError {}
+
+/// Sent to the UI as `{ kind, key, message }`.
+impl Serialize for SettingsError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct Repr<'a> {
+            kind: &'a str,
+            key: Option<&'a str>,
+            message: String,
+        }
+        Repr { kind: self.kind(), key: self.key(), message: self.to_string() }.serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn apply(key: &str, value: serde_json::Value) -> Result<Settings, SettingsError> {
+        SettingsPatch::from_entry(key, value)?.apply_to(&Settings::default())
+    }
+
+    #[test]
+    fn every_key_is_a_patch_field() {
+        for key in SettingsPatch::KEYS {
+            let value = serde_json::to_value(Settings::default()).unwrap()[key].clone();
+            assert!(SettingsPatch::from_entry(key, value).is_ok(), "{}", key);
+        }
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected() {
+        let err = apply("fontSize", json!(12)).unwrap_err();
+        assert_eq!(err, SettingsError::UnknownKey { key: "fontSize".to_string() });
+    }
+
+    #[test]
+    fn ranges_are_enforced() {
+        assert_eq!(apply("zoom", json!(150)).unwrap().zoom, 150);
+        assert_eq!(
+            apply("zoom", json!(300)).unwrap_err(),
+            SettingsError::OutOfRange { key: "zoom".to_string(), min: 70, max: 200, value: 300 }
+        );
+        assert_eq!(apply("trashRetentionDays", json!(90)).unwrap().trash_retention_days, 90);
+        assert!(apply("trashRetentionDays", json!(0)).is_err());
+        assert_eq!(apply("typeDelayMs", json!(0)).unwrap().type_delay_ms, 0);
+        assert!(apply("typeDelayMs", json!(1001)).is_err());
+        assert_eq!(apply("restoreDelayMs", json!(100)).unwrap().restore_delay_ms, 100);
+        assert!(apply("restoreDelayMs", json!(99)).is_err());
+        assert_eq!(apply("syncIntervalSecs", json!(3600)).unwrap().sync_interval_secs, 3600);
+        assert!(apply("syncIntervalSecs", json!(4)).is_err());
+        assert_eq!(apply("autoClearSecs", json!(5)).unwrap().auto_clear_secs, 5);
+        assert!(apply("autoClearSecs", json!(3601)).is_err());
+    }
+
+    #[test]
+    fn values_of_the_wrong_type_are_invalid() {
+        assert!(matches!(apply("zoom", json!("big")), Err(SettingsError::InvalidValue { .. })));
+        assert!(matches!(apply("zoom", json!(-5_000_000_000i64)), Err(SettingsError::InvalidValue { .. })));
+    }
+
+    #[test]
+    fn enum_values_are_checked() {
+        assert_eq!(apply("dedup", json!("moveToTop")).unwrap().dedup, DedupStrategy::MoveToTop);
+        assert_eq!(apply("grouping", json!("none")).unwrap().grouping, Grouping::Flat);
+        assert_eq!(apply("historyOrder", json!("frecency")).unwrap().history_order, HistoryOrder::Frecency);
+        assert_eq!(apply("autoClear", json!("sensitive")).unwrap().auto_clear, AutoClear::Sensitive);
+        let err = apply("theme", json!("solarized")).unwrap_err();
+        assert!(matches!(err, SettingsError::InvalidValue { ref key, .. } if key == "theme"));
+        assert!(apply("syncScope", json!("everything")).is_err());
+    }
+
+    #[test]
+    fn language_must_be_supported_or_null() {
+        assert_eq!(apply("language", json!("pt-BR")).unwrap().language.as_deref(), Some("pt-BR"));
+        assert_eq!(apply("language", json!(null)).unwrap().language, None);
+        assert_eq!(
+            apply("language", json!("de")).unwrap_err(),
+            SettingsError::UnsupportedLanguage { value: "de".to_string() }
+        );
+    }
+
+    #[test]
+    fn toggle_shortcut_cannot_be_blank() {
+        assert_eq!(apply("toggleShortcut", json!(" Control+Alt+C ")).unwrap().toggle_shortcut, "Control+Alt+C");
+        assert!(apply("toggleShortcut", json!("  ")).is_err());
+    }
+
+    #[test]
+    fn paste_rules_need_an_application() {
+        let settings = apply("pasteRules", json!([{ "app": " Kitty ", "mode": "typeOut" }])).unwrap();
+        assert_eq!(settings.paste_rules[0].app, "Kitty");
+        assert_eq!(settings.paste_mode_for("kitty"), PasteMode::TypeOut);
+        assert_eq!(settings.paste_mode_for("firefox"), PasteMode::Default);
+        assert!(apply("pasteRules", json!([{ "app": "", "mode": "plainText" }])).is_err());
+        assert!(apply("pasteRules", json!([{ "app": "kitty", "mode": "shout" }])).is_err());
+    }
+
+    #[test]
+    fn sync_dir_must_be_absolute() {
+        assert_eq!(apply("syncDir", json!("/srv/sync")).unwrap().sync_dir.as_deref(), Some("/srv/sync"));
+        assert_eq!(apply("syncDir", json!("  ")).unwrap().sync_dir, None);
+        assert_eq!(apply("syncDir", json!(null)).unwrap().sync_dir, None);
+        let err = apply("syncDir", json!("Sync")).unwrap_err();
+        assert!(matches!(err, SettingsError::InvalidValue { ref key, .. } if key == "syncDir"));
+    }
+
+    #[test]
+    fn ocr_languages_are_tesseract_codes() {
+        assert_eq!(apply("ocrLanguages", json!("eng+chi_sim")).unwrap().ocr_languages, "eng+chi_sim");
+        assert!(apply("ocrLanguages", json!("eng+")).is_err());
+        assert!(apply("ocrLanguages", json!("eng; rm -rf")).is_err());
+    }
+
+    #[test]
+    fn errors_serialize_as_kind_key_message() {
+        let err = apply("zoom", json!(10)).unwrap_err();
+        assert_eq!(
+            serde_json::to_value(&err).unwrap(),
+            json!({ "kind": "outOfRange", "key": "zoom", "message": "\"zoom\" must be between 70 and 200 (got 10)" })
+        );
+        let shortcut = SettingsError::Shortcut { message: "taken".to_string() };
+        assert_eq!(
+            serde_json::to_value(&shortcut).unwrap(),
+            json!({ "kind": "shortcut", "key": null, "message": "taken" })
+        );
+    }
+
+    #[test]
+    fn legacy_settings_still_load() {
+        let stored = json!({
+            "position": "mouse",
+            "grouping": "none",
+            "zoom": 110,
+            "theme": "sepia",
+            "language": "en",
+            "dedup": "always",
+            "historyOrder": 3,
+        });
+        let settings: Settings = serde_json::from_value(stored).unwrap();
+        assert_eq!(settings.position, WindowPosition::Cursor);
+        assert_eq!(settings.grouping, Grouping::Flat);
+        assert_eq!(settings.zoom, 110);
+        assert_eq!(settings.theme, Theme::Dark);
+        assert_eq!(settings.dedup, DedupStrategy::NewestOnly);
+        assert_eq!(settings.history_order, HistoryOrder::Recent);
+        assert_eq!(settings.trash_retention_days, default_trash_retention_days());
+        assert_eq!(settings.ocr_languages, "eng");
+    }
+}
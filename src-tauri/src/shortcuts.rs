@@ -0,0 +1,178 @@
+use std::cmp::Reverse;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
+use crate::clipboard::WatcherState;
+use crate::db::DbState;
+use crate::settings::Settings;
+
+/// What a registered global shortcut does when pressed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ShortcutAction {
+    ToggleWindow,
+    PastePrevious,
+    /// 1-based index into the pinned items.
+    PastePinned(usize),
+    PauseCapture,
+    ClearHistory,
+    PasteNextQueued,
+}
+
+impl ShortcutAction {
+    fn label(&self) -> String {
+        match self {
+            ShortcutAction::ToggleWindow => "open window".to_string(),
+            ShortcutAction::PastePrevious => "paste previous item".to_string(),
+            ShortcutAction::PastePinned(n) => format!("paste pinned item {}", n),
+            ShortcutAction::PauseCapture => "pause capture".to_string(),
+            ShortcutAction::ClearHistory => "clear history".to_string(),
+            ShortcutAction::PasteNextQueued => "paste next queued item".to_string(),
+        }
+    }
+}
+
+/// Shortcuts currently registered with the OS, so they can be swapped atomically.
+#[derive(Default)]
+pub struct ShortcutRegistry {
+    registered: Mutex<Vec<(Shortcut, ShortcutAction)>>,
+}
+
+/// Parses every binding requested by `settings`, rejecting invalid accelerators
+/// and the same accelerator being assigned to two actions.
+pub fn bindings(settings: &Settings) -> Result<Vec<(Shortcut, ShortcutAction)>, String> {
+    let mut requested: Vec<(&str, ShortcutAction)> = Vec::new();
+    if settings.use_internal_shortcut {
+        requested.push((settings.toggle_shortcut.as_str(), ShortcutAction::ToggleWindow));
+    }
+    let hotkeys = &settings.hotkeys;
+    if let Some(s) = &hotkeys.paste_previous {
+        requested.push((s.as_str(), ShortcutAction::PastePrevious));
+    }
+    for pinned in &hotkeys.paste_pinned {
+        if pinned.slot == 0 {
+            return Err("Pinned item shortcuts start at slot 1".to_string());
+        }
+        requested.push((pinned.shortcut.as_str(), ShortcutAction::PastePinned(pinned.slot)));
+    }
+    if let Some(s) = &hotkeys.pause_capture {
+        requested.push((s.as_str(), ShortcutAction::PauseCapture));
+    }
+    if let Some(s) = &hotkeys.clear_history {
+        requested.push((s.as_str(), ShortcutAction::ClearHistory));
+    }
+    if let Some(s) = &hotkeys.paste_next_queued {
+        requested.push((s.as_str(), ShortcutAction::PasteNextQueued));
+    }
+
+    let mut parsed: Vec<(Shortcut, ShortcutAction)> = Vec::new();
+    for (accelerator, action) in requested {
+        if accelerator.trim().is_empty() {
+            continue;
+        }
+        let shortcut = accelerator.parse::<Shortcut>().map_err(|e| {
+            format!("Invalid shortcut \"{}\" for {}: {}", accelerator, action.label(), e)
+        })?;
+        if let Some((_, other)) = parsed.iter().find(|(s, _)| *s == shortcut) {
+            return Err(format!(
+                "Shortcut \"{}\" is assigned to both {} and {}",
+                accelerator, other.label(), action.label()
+            ));
+        }
+        parsed.push((shortcut, action));
+    }
+    Ok(parsed)
+}
+
+/// Replaces the registered shortcuts with the ones from `settings`. If the OS
+/// refuses one of them (usually because another application owns it), the
+/// previous set is restored and the error is returned.
+pub fn apply(app: &AppHandle, settings: &Settings) -> Result<(), String> {
+    let wanted = bindings(settings)?;
+    let registry = app.state::<ShortcutRegistry>();
+    // (Un)registering runs on the main thread, where `handle` takes this lock;
+    // holding it across those calls could deadlock with a shortcut press.
+    let previous = registry.registered.lock().unwrap().clone();
+    let gs = app.global_shortcut();
+
+    for (shortcut, _) in &previous {
+        let _ = gs.unregister(*shortcut);
+    }
+
+    for (i, (shortcut, action)) in wanted.iter().enumerate() {
+        if let Err(e) = gs.register(*shortcut) {
+            for (done, _) in &wanted[..i] {
+                let _ = gs.unregister(*done);
+            }
+            for (old, _) in &previous {
+                if let Err(e) = gs.register(*old) {
+                    log::error!("Failed to restore shortcut {}: {}", old, e);
+                }
+            }
+            return Err(format!(
+                "Shortcut \"{}\" for {} is already in use: {}",
+                shortcut, action.label(), e
+            ));
+        }
+    }
+
+    *registry.registered.lock().unwrap() = wanted;
+    Ok(())
+}
+
+/// Global shortcut handler: dispatches a pressed shortcut to its action.
+pub fn handle(app: &AppHandle, shortcut: &Shortcut) {
+    if crate::typeout::is_cancel_shortcut(shortcut) {
+        crate::typeout::cancel(app);
+        return;
+    }
+
+    let action = {
+        let registry = app.state::<ShortcutRegistry>();
+        let registered = registry.registered.lock().unwrap();
+        registered.iter().find(|(s, _)| s == shortcut).map(|(_, a)| *a)
+    };
+
+    match action {
+        Some(ShortcutAction::ToggleWindow) => {
+            if let Some(win) = app.get_webview_window("main") {
+                // LOGIC: Simplified Toggle
+                // If focused -> Hide
+                // Else (Hidden or Blurred) -> Show and Focus
+                if win.is_focused().unwrap_or(false) {
+                    let _ = win.hide();
+                } else {
+                    crate::commands::show_window(win);
+                }
+            }
+        }
+        Some(ShortcutAction::PastePrevious) => {
+            let mut history = app.state::<DbState>().get_history();
+            // The newest item is what is on the clipboard right now.
+            history.sort_by_key(|i| Reverse(i.timestamp));
+            if let Some(item) = history.get(1) {
+                crate::paste::paste_item(app, item);
+            }
+        }
+        Some(ShortcutAction::PastePinned(slot)) => {
+            let history = app.state::<DbState>().get_history();
+            if let Some(item) = history.iter().filter(|i| i.is_pinned).nth(slot - 1) {
+                crate::paste::paste_item(app, item);
+            }
+        }
+        Some(ShortcutAction::PauseCapture) => {
+            let paused = app.state::<WatcherState>().toggle_paused();
+            crate::refresh_tray_tooltip(app);
+            let _ = app.emit("capture-paused", paused);
+        }
+        Some(ShortcutAction::ClearHistory) => {
+            let state = app.state::<DbState>();
+            state.clear_all();
+            let _ = state.save();
+            let _ = app.emit("clipboard-changed", state.get_history());
+        }
+        Some(ShortcutAction::PasteNextQueued) => {
+            crate::queue::paste_next(app);
+        }
+        None => {}
+    }
+}
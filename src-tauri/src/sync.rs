@@ -0,0 +1,552 @@
+//! Multi-device sync through a shared folder (Syncthing, NFS, ...).
+//!
+//! Every device appends its changes to `<device id>.jsonl` in the folder and
+//! never touches the other logs. A sync reads all logs, keeps the newest
+//! change per record (last writer wins, ties broken by device id) and
+//! reconciles it with the local database. Deletions are written as
+//! tombstones so they win over older edits.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+use crate::db::{self, ClipboardItem, Collection, Database, DbState, TrashedItem};
+use crate::settings::SyncScope;
+
+const LOG_EXTENSION: &str = "jsonl";
+
+/// Per-device bookkeeping, saved with the database.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncState {
+    /// Names this device's log; generated on the first sync.
+    pub device_id: String,
+    /// Version (`at`) of each record as of the last sync, by key.
+    pub seen: BTreeMap<String, i64>,
+    /// When records were last edited here, by key; cleared by the next sync.
+    /// A pending edit newer than a remote change wins over it.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub modified: BTreeMap<String, i64>,
+}
+
+impl SyncState {
+    pub fn touch_item(&mut self, id: &str, now: i64) {
+        self.modified.insert(item_key(id), now);
+    }
+
+    pub fn touch_collection(&mut self, id: &str, now: i64) {
+        self.modified.insert(collection_key(id), now);
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+enum Record {
+    Item(Box<ClipboardItem>),
+    Collection(Collection),
+}
+
+/// One line of a device log.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Change {
+    key: String,
+    at: i64,
+    device: String,
+    /// `None` marks a deletion.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    record: Option<Record>,
+}
+
+impl Change {
+    fn wins_over(&self, other: &Change) -> bool {
+        (self.at, &self.device) > (other.at, &other.device)
+    }
+}
+
+/// What one sync did.
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncReport {
+    /// Local changes written to this device's log.
+    pub pushed: usize,
+    /// Records added or updated from other devices.
+    pub pulled: usize,
+    /// Records deleted because another device deleted them.
+    pub removed: usize,
+}
+
+fn item_key(id: &str) -> String {
+    format!("item/{}", id)
+}
+
+fn collection_key(id: &str) -> String {
+    format!("collection/{}", id)
+}
+
+fn in_scope(item: &ClipboardItem, scope: SyncScope) -> bool {
+    scope == SyncScope::All || item.is_pinned || item.collection_id.is_some()
+}
+
+/// Sensitive items never leave this device, and changes from elsewhere don't
+/// touch them either.
+fn private_keys(db: &Database) -> BTreeSet<String> {
+    db.history.iter().filter(|i| i.is_sensitive).map(|i| item_key(&i.id)).collect()
+}
+
+/// Compares the fields that are synced; tags, kind, meta and usage stay local.
+fn same(a: &Record, b: &Record) -> bool {
+    match (a, b) {
+        (Record::Item(a), Record::Item(b)) => {
+            a.r#type == b.r#type
+                && a.content == b.content
+                && a.timestamp == b.timestamp
+                && a.is_pinned == b.is_pinned
+                && a.collection_id == b.collection_id
+                && a.formats == b.formats
+                && a.title == b.title
+                && a.note == b.note
+                && a.expires_at == b.expires_at
+                && a.is_sensitive == b.is_sensitive
+        }
+        (Record::Collection(a), Record::Collection(b)) => a.name == b.name,
+        _ => false,
+    }
+}
+
+/// The newest change per key across all logs in `dir`. Lines that fail to
+/// parse, e.g. a partially synced last line, are ignored.
+fn read_logs(dir: &Path) -> Result<BTreeMap<String, Change>, String> {
+    if !dir.is_dir() {
+        return Err(format!("Sync folder {} not found", dir.display()));
+    }
+    let mut winners: BTreeMap<String, Change> = BTreeMap::new();
+    for entry in fs::read_dir(dir).map_err(|e| e.to_string())? {
+        let path = entry.map_err(|e| e.to_string())?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some(LOG_EXTENSION) {
+            continue;
+        }
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) => {
+                log::warn!("Skipping sync log {}: {}", path.display(), e);
+                continue;
+            }
+        };
+        for change in content.lines().filter_map(|line| serde_json::from_str::<Change>(line).ok()) {
+            match winners.get(&change.key) {
+                Some(current) if !change.wins_over(current) => {}
+                _ => {
+                    winners.insert(change.key.clone(), change);
+                }
+            }
+        }
+    }
+    Ok(winners)
+}
+
+/// Records this device takes part in syncing: every collection, the items in
+/// scope, and items synced before (so unpinning one is synced too).
+fn local_records(db: &Database, scope: SyncScope) -> BTreeMap<String, Record> {
+    let mut records = BTreeMap::new();
+    for collection in &db.collections {
+        records.insert(collection_key(&collection.id), Record::Collection(collection.clone()));
+    }
+    for item in db.history.iter().filter(|i| !i.is_sensitive) {
+        let key = item_key(&item.id);
+        if in_scope(item, scope) || db.sync.seen.contains_key(&key) {
+            let mut item = item.clone();
+            item.tags.clear();
+            records.insert(key, Record::Item(Box::new(item)));
+        }
+    }
+    records
+}
+
+/// Applies a remote change to the local database.
+fn apply(db: &mut Database, key: &str, record: Option<Record>, now: i64) {
+    match record {
+        Some(Record::Collection(collection)) => {
+            match db.collections.iter_mut().find(|c| c.id == collection.id) {
+                Some(existing) => existing.name = collection.name,
+                None => db.collections.push(collection),
+            }
+        }
+        Some(Record::Item(mut item)) => {
+            if let Some(collection) = &item.collection_id {
+                if !db.collections.iter().any(|c| &c.id == collection) {
+                    item.collection_id = None;
+                }
+            }
+            if item.r#type == "text" {
+                item.classify();
+            }
+            match db.history.iter_mut().find(|i| i.id == item.id) {
+                Some(existing) => {
+                    // Tags and usage statistics stay local.
+                    item.tags = std::mem::take(&mut existing.tags);
+                    item.copy_count = existing.copy_count;
+                    item.paste_count = existing.paste_count;
+                    item.last_pasted_at = existing.last_pasted_at;
+                    if item.ocr_text.is_none() {
+                        item.ocr_text = existing.ocr_text.take();
+                    }
+                    if item.decoded.is_none() {
+                        item.decoded = existing.decoded.take();
+                    }
+                    *existing = *item;
+                }
+                None => db::insert_by_timestamp(&mut db.history, *item),
+            }
+        }
+        None => {
+            if let Some(id) = key.strip_prefix("item/") {
+                if let Some(position) = db.history.iter().position(|i| i.id == id) {
+                    let item = db.history.remove(position);
+                    db.trash.push(TrashedItem { item, deleted_at: now, position });
+                }
+            } else if let Some(id) = key.strip_prefix("collection/") {
+                db.collections.retain(|c| c.id != id);
+                for item in db.history.iter_mut().filter(|i| i.collection_id.as_deref() == Some(id)) {
+                    item.collection_id = None;
+                }
+            }
+        }
+    }
+}
+
+/// Reconciles `db` with `winners`, read from the logs in `dir`, and appends
+/// the local changes to this device's log there. Changes without a recorded
+/// edit time are stamped with `now`.
+fn sync_database(
+    db: &mut Database,
+    dir: &Path,
+    winners: BTreeMap<String, Change>,
+    scope: SyncScope,
+    now: i64,
+) -> Result<SyncReport, String> {
+    if db.sync.device_id.is_empty() {
+        db.sync.device_id = uuid::Uuid::new_v4().to_string();
+    }
+    let device = db.sync.device_id.clone();
+    let local = local_records(db, scope);
+    let private = private_keys(db);
+    let mut report = SyncReport::default();
+    let mut pushed = Vec::new();
+    let mut incoming = Vec::new();
+
+    for (key, record) in &local {
+        let base = db.sync.seen.get(key).copied().unwrap_or(i64::MIN);
+        let edited = db.sync.modified.get(key).copied();
+        match winners.get(key) {
+            // Changed elsewhere since our last sync, and any pending edit here is older.
+            Some(winner)
+                if winner.at > base && winner.device != device && !edited.is_some_and(|at| at >= winner.at) =>
+            {
+                incoming.push(winner.clone())
+            }
+            winner => {
+                let unchanged = winner
+                    .and_then(|w| w.record.as_ref())
+                    .is_some_and(|synced| same(synced, record));
+                if !unchanged {
+                    let at = edited.unwrap_or(now);
+                    pushed.push(Change { key: key.clone(), at, device: device.clone(), record: Some(record.clone()) });
+                }
+            }
+        }
+    }
+
+    for (key, winner) in &winners {
+        if local.contains_key(key) || private.contains(key) {
+            continue;
+        }
+        let base = db.sync.seen.get(key).copied();
+        match &winner.record {
+            None => {
+                db.sync.seen.insert(key.clone(), winner.at);
+            }
+            Some(_) if base.is_some_and(|b| b >= winner.at) => {
+                // We had it and it's gone: deleted here, unless the item was
+                // only dropped by the history limit.
+                let deleted = match key.strip_prefix("item/") {
+                    Some(id) => db.trash.iter().any(|t| t.item.id == id),
+                    None => true,
+                };
+                if deleted {
+                    pushed.push(Change { key: key.clone(), at: now, device: device.clone(), record: None });
+                }
+            }
+            Some(Record::Item(item)) if !in_scope(item, scope) => {}
+            Some(_) => incoming.push(winner.clone()),
+        }
+    }
+
+    // Collections first, so incoming items can be filed in them.
+    incoming.sort_by_key(|change| !change.key.starts_with("collection/"));
+    for change in incoming {
+        if change.record.is_some() {
+            report.pulled += 1;
+        } else {
+            report.removed += 1;
+        }
+        db.sync.seen.insert(change.key.clone(), change.at);
+        apply(db, &change.key, change.record, now);
+    }
+    db::prune(&mut db.history);
+
+    if !pushed.is_empty() {
+        let mut lines = String::new();
+        for change in &pushed {
+            lines.push_str(&serde_json::to_string(change).map_err(|e| e.to_string())?);
+            lines.push('\n');
+        }
+        let path = dir.join(format!("{}.{}", device, LOG_EXTENSION));
+        let mut log = OpenOptions::new().create(true).append(true).open(&path).map_err(|e| e.to_string())?;
+        log.write_all(lines.as_bytes()).map_err(|e| e.to_string())?;
+        for change in &pushed {
+            db.sync.seen.insert(change.key.clone(), change.at);
+        }
+        report.pushed = pushed.len();
+    }
+    db.sync.modified.clear();
+    Ok(report)
+}
+
+/// Syncs with the configured folder, saving and notifying the UI when
+/// anything came in. Fails when sync is off.
+pub fn run(app: &AppHandle) -> Result<SyncReport, String> {
+    let state = app.state::<DbState>();
+    let settings = state.get_settings();
+    let dir = settings.sync_dir.ok_or("Sync is turned off")?;
+    let dir = Path::new(&dir);
+    // Reading other devices' logs can be slow (network folders); don't block the UI on it.
+    let winners = read_logs(dir)?;
+    let report = {
+        let mut db = state.db.lock().unwrap();
+        sync_database(&mut db, dir, winners, settings.sync_scope, chrono::Utc::now().timestamp_millis())?
+    };
+    if report != SyncReport::default() {
+        let _ = state.save();
+    }
+    if report.pulled + report.removed > 0 {
+        let _ = app.emit("clipboard-changed", state.get_history());
+    }
+    Ok(report)
+}
+
+/// Syncs every `sync_interval_secs` while a sync folder is configured.
+pub fn start(app: AppHandle) {
+    thread::spawn(move || loop {
+        let settings = app.state::<DbState>().get_settings();
+        if settings.sync_dir.is_some() {
+            if let Err(e) = run(&app) {
+                log::warn!("Sync failed: {}", e);
+            }
+        }
+        thread::sleep(Duration::from_secs(settings.sync_interval_secs as u64));
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    /// A device with its own copy of the shared folder.
+    struct Device {
+        db: Database,
+        dir: PathBuf,
+    }
+
+    impl Device {
+        fn new() -> Self {
+            let dir = std::env::temp_dir().join(format!("clipboard-sync-{}", uuid::Uuid::new_v4()));
+            fs::create_dir_all(&dir).unwrap();
+            Self { db: Database::default(), dir }
+        }
+
+        fn sync(&mut self, now: i64) -> SyncReport {
+            let winners = read_logs(&self.dir).unwrap();
+            sync_database(&mut self.db, &self.dir, winners, SyncScope::Pinned, now).unwrap()
+        }
+
+        fn item(&self, id: &str) -> Option<&ClipboardItem> {
+            self.db.history.iter().find(|i| i.id == id)
+        }
+    }
+
+    impl Drop for Device {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.dir);
+        }
+    }
+
+    /// Copies each device's logs into the other's folder, like Syncthing would.
+    fn exchange(a: &Device, b: &Device) {
+        for (from, to) in [(&a.dir, &b.dir), (&b.dir, &a.dir)] {
+            for entry in fs::read_dir(from).unwrap() {
+                let path = entry.unwrap().path();
+                fs::copy(&path, to.join(path.file_name().unwrap())).unwrap();
+            }
+        }
+    }
+
+    fn item(id: &str, content: &str, timestamp: i64, is_pinned: bool) -> ClipboardItem {
+        ClipboardItem {
+            id: id.to_string(),
+            r#type: "text".to_string(),
+            content: content.to_string(),
+            timestamp,
+            is_pinned,
+            ..Default::default()
+        }
+    }
+
+    /// Device A with a pinned item, an unpinned one and an item in a collection,
+    /// synced to an empty device B.
+    fn synced_pair() -> (Device, Device) {
+        let mut a = Device::new();
+        let mut b = Device::new();
+        a.db.collections.push(Collection { id: "c".to_string(), name: "Work".to_string() });
+        a.db.history = vec![
+            item("pinned", "keep me", 30, true),
+            item("plain", "just history", 20, false),
+            ClipboardItem { collection_id: Some("c".to_string()), ..item("filed", "in a folder", 10, false) },
+        ];
+        assert_eq!(a.sync(100).pushed, 3);
+        exchange(&a, &b);
+        assert_eq!(b.sync(110).pulled, 3);
+        (a, b)
+    }
+
+    #[test]
+    fn pinned_items_and_collections_reach_other_devices() {
+        let (_a, b) = synced_pair();
+        assert!(b.item("pinned").unwrap().is_pinned);
+        assert_eq!(b.item("filed").unwrap().collection_id.as_deref(), Some("c"));
+        assert_eq!(b.db.collections[0].name, "Work");
+        assert!(b.item("plain").is_none(), "unpinned items stay local in the pinned scope");
+    }
+
+    #[test]
+    fn deletions_propagate_as_tombstones() {
+        let (mut a, mut b) = synced_pair();
+        let position = a.db.history.iter().position(|i| i.id == "pinned").unwrap();
+        let deleted = a.db.history.remove(position);
+        a.db.trash.push(TrashedItem { item: deleted, deleted_at: 200, position });
+        assert_eq!(a.sync(200).pushed, 1);
+
+        exchange(&a, &b);
+        assert_eq!(b.sync(210), SyncReport { pushed: 0, pulled: 0, removed: 1 });
+        assert!(b.item("pinned").is_none());
+        assert!(b.db.trash.iter().any(|t| t.item.id == "pinned"));
+    }
+
+    #[test]
+    fn concurrent_edits_converge_on_the_last_writer() {
+        let (mut a, mut b) = synced_pair();
+        a.db.collections[0].name = "From A".to_string();
+        b.db.collections[0].name = "From B".to_string();
+        a.sync(200);
+        b.sync(300);
+
+        exchange(&a, &b);
+        a.sync(400);
+        b.sync(410);
+        assert_eq!(a.db.collections[0].name, "From B");
+        assert_eq!(b.db.collections[0].name, "From B");
+
+        // Nothing left to exchange once both agree.
+        exchange(&a, &b);
+        assert_eq!(a.sync(500), SyncReport::default());
+        assert_eq!(b.sync(510), SyncReport::default());
+    }
+
+    #[test]
+    fn a_newer_local_edit_wins_over_an_older_remote_one() {
+        let (mut a, mut b) = synced_pair();
+        b.db.collections[0].name = "From B".to_string();
+        b.db.sync.touch_collection("c", 200);
+        b.sync(210);
+        a.db.collections[0].name = "From A".to_string();
+        a.db.sync.touch_collection("c", 250);
+
+        exchange(&a, &b);
+        assert_eq!(a.sync(300).pushed, 1);
+        exchange(&a, &b);
+        b.sync(310);
+        assert_eq!(a.db.collections[0].name, "From A");
+        assert_eq!(b.db.collections[0].name, "From A");
+        assert!(a.db.sync.modified.is_empty());
+    }
+
+    #[test]
+    fn an_older_local_edit_loses_to_a_newer_remote_one() {
+        let (mut a, mut b) = synced_pair();
+        a.db.collections[0].name = "From A".to_string();
+        a.db.sync.touch_collection("c", 200);
+        b.db.collections[0].name = "From B".to_string();
+        b.db.sync.touch_collection("c", 250);
+        b.sync(260);
+
+        exchange(&a, &b);
+        assert_eq!(a.sync(300).pulled, 1);
+        assert_eq!(a.db.collections[0].name, "From B");
+    }
+
+    #[test]
+    fn sensitive_items_are_not_synced() {
+        let (mut a, mut b) = synced_pair();
+        a.db.history.push(ClipboardItem { is_sensitive: true, ..item("secret", "hunter2", 5, true) });
+        a.db.history[0].is_sensitive = true;
+        assert_eq!(a.sync(200), SyncReport::default());
+
+        // B's edit to the item A has since marked sensitive doesn't come back.
+        b.db.history.iter_mut().find(|i| i.id == "pinned").unwrap().content = "edited".to_string();
+        b.sync(210);
+        exchange(&a, &b);
+        assert_eq!(a.sync(300), SyncReport::default());
+        assert_eq!(a.item("pinned").unwrap().content, "keep me");
+        b.sync(310);
+        assert!(b.item("secret").is_none());
+    }
+
+    #[test]
+    fn unpinning_a_synced_item_is_synced() {
+        let (mut a, mut b) = synced_pair();
+        a.db.history[0].is_pinned = false;
+        assert_eq!(a.sync(200).pushed, 1);
+
+        exchange(&a, &b);
+        b.sync(210);
+        let unpinned = b.item("pinned").unwrap();
+        assert!(!unpinned.is_pinned, "the item stays, only its pin changes");
+    }
+
+    #[test]
+    fn items_dropped_by_the_history_limit_are_not_deleted_elsewhere() {
+        let (mut a, mut b) = synced_pair();
+        a.db.history.retain(|i| i.id != "filed");
+        assert_eq!(a.sync(200), SyncReport::default());
+
+        exchange(&a, &b);
+        b.sync(210);
+        assert!(b.item("filed").is_some());
+    }
+
+    #[test]
+    fn torn_log_lines_are_ignored() {
+        let (a, mut b) = synced_pair();
+        let log = fs::read_dir(&a.dir).unwrap().next().unwrap().unwrap().path();
+        let mut file = OpenOptions::new().append(true).open(&log).unwrap();
+        file.write_all(b"{\"key\":\"item/x\",\"at\":9").unwrap();
+
+        exchange(&a, &b);
+        assert_eq!(b.sync(300), SyncReport::default());
+    }
+}
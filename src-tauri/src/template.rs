@@ -0,0 +1,338 @@
+use std::collections::HashMap;
+use std::fmt;
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Local};
+use serde::{Serialize, Serializer};
+
+const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Values available while expanding a snippet.
+pub struct TemplateContext<'a> {
+    /// Current text contents of the system clipboard, for `{clipboard}`.
+    pub clipboard: &'a str,
+    /// Answers to `{input:Label}` prompts, keyed by label.
+    pub inputs: &'a HashMap<String, String>,
+    pub now: DateTime<Local>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Expansion {
+    pub text: String,
+    /// Number of characters after `{cursor}`, i.e. how far to move the caret
+    /// back after pasting. `None` when the template has no cursor marker.
+    pub cursor_back: Option<usize>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum TemplateError {
+    /// A `{` without its closing `}`, at this char offset.
+    Unterminated(usize),
+    UnknownPlaceholder(String),
+    InvalidDateFormat(String),
+    MissingInput(String),
+    MultipleCursors,
+    /// The snippet to expand no longer exists.
+    NotFound,
+}
+
+impl TemplateError {
+    fn kind(&self) -> &'static str {
+        match self {
+            TemplateError::Unterminated(_) => "unterminated",
+            TemplateError::UnknownPlaceholder(_) => "unknownPlaceholder",
+            TemplateError::InvalidDateFormat(_) => "invalidDateFormat",
+            TemplateError::MissingInput(_) => "missingInput",
+            TemplateError::MultipleCursors => "multipleCursors",
+            TemplateError::NotFound => "notFound",
+        }
+    }
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TemplateError::Unterminated(at) => write!(f, "Unclosed placeholder at position {}", at),
+            TemplateError::UnknownPlaceholder(name) => write!(f, "Unknown placeholder {{{}}}", name),
+            TemplateError::InvalidDateFormat(format) => write!(f, "Invalid date format \"{}\"", format),
+            TemplateError::MissingInput(label) => write!(f, "No value given for \"{}\"", label),
+            TemplateError::MultipleCursors => write!(f, "Only one {{cursor}} is allowed"),
+            TemplateError::NotFound => write!(f, "Snippet not found"),
+        }
+    }
+}
+
+/// Sent to the UI as `{ kind, label, message }`; `label` names the input to
+/// prompt for when `kind` is `missingInput`.
+impl Serialize for TemplateError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct Repr<'a> {
+            kind: &'a str,
+            label: Option<&'a str>,
+            message: String,
+        }
+        let label = match self {
+            TemplateError::MissingInput(label) => Some(label.as_str()),
+            _ => None,
+        };
+        Repr { kind: self.kind(), label, message: self.to_string() }.serialize(serializer)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Token<'a> {
+    Literal(String),
+    Date(&'a str),
+    Clipboard,
+    Uuid,
+    Input(&'a str),
+    Cursor,
+}
+
+/// Splits a template into literals and placeholders. `{{` and `}}` are literal braces.
+fn parse(template: &str) -> Result<Vec<Token<'_>>, TemplateError> {
+    let mut tokens = Vec::new();
+    let mut literal = String::new();
+    let mut rest = template;
+    let mut offset = 0;
+
+    while let Some(c) = rest.chars().next() {
+        if rest.starts_with("{{") || rest.starts_with("}}") {
+            literal.push(c);
+            rest = &rest[2..];
+            offset += 2;
+            continue;
+        }
+        if c != '{' {
+            literal.push(c);
+            rest = &rest[c.len_utf8()..];
+            offset += 1;
+            continue;
+        }
+
+        let end = rest.find('}').ok_or(TemplateError::Unterminated(offset))?;
+        let body = &rest[1..end];
+        let (name, arg) = match body.split_once(':') {
+            Some((name, arg)) => (name, Some(arg)),
+            None => (body, None),
+        };
+        let token = match (name, arg) {
+            ("date", format) => Token::Date(format.unwrap_or(DEFAULT_DATE_FORMAT)),
+            ("clipboard", None) => Token::Clipboard,
+            ("uuid", None) => Token::Uuid,
+            ("input", Some(label)) if !label.trim().is_empty() => Token::Input(label.trim()),
+            ("cursor", None) => Token::Cursor,
+            _ => return Err(TemplateError::UnknownPlaceholder(body.to_string())),
+        };
+
+        if !literal.is_empty() {
+            tokens.push(Token::Literal(std::mem::take(&mut literal)));
+        }
+        tokens.push(token);
+        offset += body.chars().count() + 2;
+        rest = &rest[end + 1..];
+    }
+
+    if !literal.is_empty() {
+        tokens.push(Token::Literal(literal));
+    }
+    Ok(tokens)
+}
+
+/// Validates the template and lists its `{input:...}` labels in order, without
+/// duplicates, so the UI can prompt for them before pasting.
+pub fn input_prompts(template: &str) -> Result<Vec<String>, TemplateError> {
+    let mut labels: Vec<String> = Vec::new();
+    let mut cursors = 0;
+    for token in parse(template)? {
+        match token {
+            Token::Input(label) if !labels.iter().any(|l| l == label) => labels.push(label.to_string()),
+            Token::Date(format) => check_date_format(format)?,
+            Token::Cursor => cursors += 1,
+            _ => {}
+        }
+    }
+    if cursors > 1 {
+        return Err(TemplateError::MultipleCursors);
+    }
+    Ok(labels)
+}
+
+fn check_date_format(format: &str) -> Result<(), TemplateError> {
+    if StrftimeItems::new(format).any(|item| item == Item::Error) {
+        return Err(TemplateError::InvalidDateFormat(format.to_string()));
+    }
+    Ok(())
+}
+
+/// Expands every placeholder in `template`.
+pub fn expand(template: &str, ctx: &TemplateContext) -> Result<Expansion, TemplateError> {
+    let mut text = String::new();
+    let mut cursor_at: Option<usize> = None;
+
+    for token in parse(template)? {
+        match token {
+            Token::Literal(s) => text.push_str(&s),
+            Token::Date(format) => {
+                check_date_format(format)?;
+                text.push_str(&ctx.now.format(format).to_string());
+            }
+            Token::Clipboard => text.push_str(ctx.clipboard),
+            Token::Uuid => text.push_str(&uuid::Uuid::new_v4().to_string()),
+            Token::Input(label) => {
+                let value = ctx
+                    .inputs
+                    .get(label)
+                    .ok_or_else(|| TemplateError::MissingInput(label.to_string()))?;
+                text.push_str(value);
+            }
+            Token::Cursor => {
+                if cursor_at.is_some() {
+                    return Err(TemplateError::MultipleCursors);
+                }
+                cursor_at = Some(text.chars().count());
+            }
+        }
+    }
+
+    let cursor_back = cursor_at.map(|at| text.chars().count() - at);
+    Ok(Expansion { text, cursor_back })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn expand_with(template: &str, clipboard: &str, inputs: &[(&str, &str)]) -> Result<Expansion, TemplateError> {
+        let inputs: HashMap<String, String> = inputs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let ctx = TemplateContext {
+            clipboard,
+            inputs: &inputs,
+            now: Local.with_ymd_and_hms(2024, 3, 9, 14, 5, 0).unwrap(),
+        };
+        expand(template, &ctx)
+    }
+
+    #[test]
+    fn plain_text_is_unchanged() {
+        let out = expand_with("hello world", "", &[]).unwrap();
+        assert_eq!(out.text, "hello world");
+        assert_eq!(out.cursor_back, None);
+    }
+
+    #[test]
+    fn date_with_format() {
+        let out = expand_with("Due {date:%d/%m/%Y %H:%M}", "", &[]).unwrap();
+        assert_eq!(out.text, "Due 09/03/2024 14:05");
+    }
+
+    #[test]
+    fn date_without_format_uses_iso_date() {
+        assert_eq!(expand_with("{date}", "", &[]).unwrap().text, "2024-03-09");
+    }
+
+    #[test]
+    fn date_with_invalid_format_is_rejected() {
+        assert_eq!(
+            expand_with("{date:%Q}", "", &[]),
+            Err(TemplateError::InvalidDateFormat("%Q".to_string()))
+        );
+        assert_eq!(
+            input_prompts("{date:%Q}"),
+            Err(TemplateError::InvalidDateFormat("%Q".to_string()))
+        );
+    }
+
+    #[test]
+    fn clipboard_is_inserted() {
+        let out = expand_with("Re: {clipboard}!", "ünïcode text", &[]).unwrap();
+        assert_eq!(out.text, "Re: ünïcode text!");
+    }
+
+    #[test]
+    fn uuid_is_fresh_v4() {
+        let a = expand_with("{uuid}", "", &[]).unwrap().text;
+        let b = expand_with("{uuid}", "", &[]).unwrap().text;
+        let parsed = uuid::Uuid::parse_str(&a).unwrap();
+        assert_eq!(parsed.get_version_num(), 4);
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn input_uses_provided_value() {
+        let out = expand_with("PROJ-{input:Ticket number}: {input:Ticket number}", "", &[("Ticket number", "42")]).unwrap();
+        assert_eq!(out.text, "PROJ-42: 42");
+    }
+
+    #[test]
+    fn input_without_value_is_an_error() {
+        assert_eq!(
+            expand_with("{input:Ticket number}", "", &[]),
+            Err(TemplateError::MissingInput("Ticket number".to_string()))
+        );
+    }
+
+    #[test]
+    fn input_prompts_are_unique_and_ordered() {
+        let labels = input_prompts("{input:B} {input:A} {input:B} {clipboard}").unwrap();
+        assert_eq!(labels, vec!["B".to_string(), "A".to_string()]);
+    }
+
+    #[test]
+    fn input_needs_a_label() {
+        assert_eq!(
+            expand_with("{input:}", "", &[]),
+            Err(TemplateError::UnknownPlaceholder("input:".to_string()))
+        );
+    }
+
+    #[test]
+    fn cursor_reports_chars_after_marker() {
+        let out = expand_with("fn {cursor}() {{}}", "", &[]).unwrap();
+        assert_eq!(out.text, "fn () {}");
+        assert_eq!(out.cursor_back, Some(5));
+    }
+
+    #[test]
+    fn cursor_counts_chars_not_bytes() {
+        let out = expand_with("«{cursor}»é", "", &[]).unwrap();
+        assert_eq!(out.cursor_back, Some(2));
+    }
+
+    #[test]
+    fn only_one_cursor_allowed() {
+        assert_eq!(expand_with("{cursor}{cursor}", "", &[]), Err(TemplateError::MultipleCursors));
+        assert_eq!(input_prompts("{cursor}{cursor}"), Err(TemplateError::MultipleCursors));
+    }
+
+    #[test]
+    fn escaped_braces_are_literal() {
+        let out = expand_with("{{clipboard}} = {clipboard}", "x", &[]).unwrap();
+        assert_eq!(out.text, "{clipboard} = x");
+    }
+
+    #[test]
+    fn unknown_placeholder_is_rejected() {
+        assert_eq!(
+            expand_with("{nope}", "", &[]),
+            Err(TemplateError::UnknownPlaceholder("nope".to_string()))
+        );
+    }
+
+    #[test]
+    fn missing_input_serializes_its_label() {
+        let json = serde_json::to_value(TemplateError::MissingInput("Ticket".to_string())).unwrap();
+        assert_eq!(json["kind"], "missingInput");
+        assert_eq!(json["label"], "Ticket");
+        assert_eq!(json["message"], "No value given for \"Ticket\"");
+    }
+
+    #[test]
+    fn unterminated_placeholder_reports_position() {
+        assert_eq!(expand_with("ab{date", "", &[]), Err(TemplateError::Unterminated(2)));
+    }
+}
@@ -0,0 +1,294 @@
+use base64::Engine;
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use serde::{Deserialize, Serialize};
+
+/// Characters left as-is by `urlEncode` (the RFC 3986 unreserved set).
+const URL_COMPONENT: &AsciiSet = &NON_ALPHANUMERIC.remove(b'-').remove(b'_').remove(b'.').remove(b'~');
+
+/// A text transformation that can be applied before pasting.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum Transform {
+    Trim,
+    Uppercase,
+    Lowercase,
+    TitleCase,
+    CamelCase,
+    SnakeCase,
+    KebabCase,
+    /// Normalizes typographic characters (smart quotes, non-breaking and
+    /// zero-width spaces) and trailing whitespace picked up from rich text.
+    StripFormatting,
+    UrlEncode,
+    UrlDecode,
+    Base64Encode,
+    Base64Decode,
+    JsonPretty,
+    JsonMinify,
+    SortLines,
+}
+
+impl Transform {
+    /// Every available transform, in the order the UI lists them.
+    pub const ALL: &'static [Transform] = &[
+        Transform::Trim,
+        Transform::Uppercase,
+        Transform::Lowercase,
+        Transform::TitleCase,
+        Transform::CamelCase,
+        Transform::SnakeCase,
+        Transform::KebabCase,
+        Transform::StripFormatting,
+        Transform::UrlEncode,
+        Transform::UrlDecode,
+        Transform::Base64Encode,
+        Transform::Base64Decode,
+        Transform::JsonPretty,
+        Transform::JsonMinify,
+        Transform::SortLines,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Transform::Trim => "Trim whitespace",
+            Transform::Uppercase => "UPPERCASE",
+            Transform::Lowercase => "lowercase",
+            Transform::TitleCase => "Title Case",
+            Transform::CamelCase => "camelCase",
+            Transform::SnakeCase => "snake_case",
+            Transform::KebabCase => "kebab-case",
+            Transform::StripFormatting => "Strip formatting",
+            Transform::UrlEncode => "URL encode",
+            Transform::UrlDecode => "URL decode",
+            Transform::Base64Encode => "Base64 encode",
+            Transform::Base64Decode => "Base64 decode",
+            Transform::JsonPretty => "Pretty-print JSON",
+            Transform::JsonMinify => "Minify JSON",
+            Transform::SortLines => "Sort lines",
+        }
+    }
+
+    pub fn apply(&self, text: &str) -> Result<String, String> {
+        let out = match self {
+            Transform::Trim => text.trim().to_string(),
+            Transform::Uppercase => text.to_uppercase(),
+            Transform::Lowercase => text.to_lowercase(),
+            Transform::TitleCase => title_case(text),
+            Transform::CamelCase => {
+                let mut out = String::new();
+                for (i, word) in words(text).iter().enumerate() {
+                    if i == 0 {
+                        out.push_str(&word.to_lowercase());
+                    } else {
+                        out.push_str(&capitalize(word));
+                    }
+                }
+                out
+            }
+            Transform::SnakeCase => join_lower(text, "_"),
+            Transform::KebabCase => join_lower(text, "-"),
+            Transform::StripFormatting => strip_formatting(text),
+            Transform::UrlEncode => utf8_percent_encode(text, URL_COMPONENT).to_string(),
+            // Percent-decoding only: '+' is a literal plus outside HTML form bodies.
+            Transform::UrlDecode => percent_decode_str(text)
+                .decode_utf8()
+                .map_err(|_| "URL-decoded text is not valid UTF-8".to_string())?
+                .into_owned(),
+            Transform::Base64Encode => base64::engine::general_purpose::STANDARD.encode(text),
+            Transform::Base64Decode => {
+                let compact: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+                let bytes = base64::engine::general_purpose::STANDARD
+                    .decode(&compact)
+                    .or_else(|_| base64::engine::general_purpose::URL_SAFE.decode(&compact))
+                    .map_err(|_| "Text is not valid base64".to_string())?;
+                String::from_utf8(bytes).map_err(|_| "Decoded base64 is not text".to_string())?
+            }
+            Transform::JsonPretty => serde_json::to_string_pretty(&parse_json(text)?).map_err(|e| e.to_string())?,
+            Transform::JsonMinify => serde_json::to_string(&parse_json(text)?).map_err(|e| e.to_string())?,
+            Transform::SortLines => {
+                let trailing_newline = text.ends_with('\n');
+                let mut lines: Vec<&str> = text.lines().collect();
+                lines.sort_by_key(|l| l.to_lowercase());
+                let mut out = lines.join("\n");
+                if trailing_newline {
+                    out.push('\n');
+                }
+                out
+            }
+        };
+        Ok(out)
+    }
+}
+
+/// What goes between items joined by `merge_items`.
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum Separator {
+    Newline,
+    Comma,
+    Tab,
+    Custom(String),
+}
+
+impl Separator {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Separator::Newline => "\n",
+            Separator::Comma => ", ",
+            Separator::Tab => "\t",
+            Separator::Custom(s) => s,
+        }
+    }
+}
+
+/// Applies `transforms` left to right.
+pub fn apply_all(text: &str, transforms: &[Transform]) -> Result<String, String> {
+    transforms.iter().try_fold(text.to_string(), |acc, t| {
+        t.apply(&acc).map_err(|e| format!("{}: {}", t.label(), e))
+    })
+}
+
+fn parse_json(text: &str) -> Result<serde_json::Value, String> {
+    serde_json::from_str(text.trim()).map_err(|e| format!("Invalid JSON: {}", e))
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars.flat_map(char::to_lowercase)).collect(),
+        None => String::new(),
+    }
+}
+
+/// Capitalizes each whitespace-separated word, keeping the original spacing.
+fn title_case(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut at_word_start = true;
+    for c in text.chars() {
+        if c.is_whitespace() {
+            at_word_start = true;
+            out.push(c);
+        } else if at_word_start {
+            at_word_start = false;
+            out.extend(c.to_uppercase());
+        } else {
+            out.extend(c.to_lowercase());
+        }
+    }
+    out
+}
+
+/// Splits identifiers and phrases into words: on any non-alphanumeric
+/// character and on lower-to-upper case boundaries (`fooBar` -> `foo`, `Bar`).
+fn words(text: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut prev_lower = false;
+    for c in text.chars() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            prev_lower = false;
+            continue;
+        }
+        if c.is_uppercase() && prev_lower && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+        prev_lower = c.is_lowercase() || c.is_numeric();
+        current.push(c);
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+fn join_lower(text: &str, separator: &str) -> String {
+    words(text).iter().map(|w| w.to_lowercase()).collect::<Vec<_>>().join(separator)
+}
+
+fn strip_formatting(text: &str) -> String {
+    let normalized: String = text
+        .chars()
+        .filter_map(|c| match c {
+            '\u{200B}' | '\u{200C}' | '\u{200D}' | '\u{2060}' | '\u{FEFF}' | '\u{00AD}' => None,
+            '\u{00A0}' | '\u{2007}' | '\u{202F}' | '\u{2009}' => Some(' '),
+            '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{2032}' => Some('\''),
+            '\u{201C}' | '\u{201D}' | '\u{201E}' | '\u{2033}' => Some('"'),
+            '\u{2013}' | '\u{2014}' => Some('-'),
+            '\r' => None,
+            c => Some(c),
+        })
+        .collect();
+    normalized.lines().map(str::trim_end).collect::<Vec<_>>().join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(transform: Transform, text: &str) -> String {
+        transform.apply(text).unwrap()
+    }
+
+    #[test]
+    fn trim_and_case() {
+        assert_eq!(apply(Transform::Trim, "  a b \n"), "a b");
+        assert_eq!(apply(Transform::Uppercase, "straße"), "STRASSE");
+        assert_eq!(apply(Transform::Lowercase, "ÀB"), "àb");
+        assert_eq!(apply(Transform::TitleCase, "hello  wORLD"), "Hello  World");
+    }
+
+    #[test]
+    fn identifier_cases_split_words() {
+        assert_eq!(apply(Transform::CamelCase, "user_id value"), "userIdValue");
+        assert_eq!(apply(Transform::SnakeCase, "parseHTTPResponse v2"), "parse_httpresponse_v2");
+        assert_eq!(apply(Transform::KebabCase, "Hello World_again"), "hello-world-again");
+    }
+
+    #[test]
+    fn strip_formatting_normalizes_typography() {
+        let text = "\u{201C}Hi\u{201D}\u{00A0}it\u{2019}s \u{2014} ok\u{200B}  \r\nnext";
+        assert_eq!(apply(Transform::StripFormatting, text), "\"Hi\" it's - ok\nnext");
+    }
+
+    #[test]
+    fn url_encode_keeps_unreserved() {
+        assert_eq!(apply(Transform::UrlEncode, "a b/c?d=é~_.-"), "a%20b%2Fc%3Fd%3D%C3%A9~_.-");
+    }
+
+    #[test]
+    fn url_decode_keeps_plus_signs() {
+        assert_eq!(apply(Transform::UrlDecode, "a+b%20c%2B%C3%A9"), "a+b c+é");
+        assert!(Transform::UrlDecode.apply("%FF").is_err());
+    }
+
+    #[test]
+    fn base64_round_trip() {
+        assert_eq!(apply(Transform::Base64Encode, "héllo"), "aMOpbGxv");
+        assert_eq!(apply(Transform::Base64Decode, "aMOp\nbGxv"), "héllo");
+        // URL-safe alphabet is accepted too.
+        assert_eq!(apply(Transform::Base64Decode, "Pz8_"), "???");
+        assert!(Transform::Base64Decode.apply("not base64!").is_err());
+    }
+
+    #[test]
+    fn json_pretty_and_minify() {
+        assert_eq!(apply(Transform::JsonMinify, "{ \"a\": [1, 2] }"), r#"{"a":[1,2]}"#);
+        assert_eq!(apply(Transform::JsonPretty, r#"{"a":1}"#), "{\n  \"a\": 1\n}");
+        assert!(Transform::JsonPretty.apply("{").is_err());
+    }
+
+    #[test]
+    fn sort_lines_is_case_insensitive_and_keeps_trailing_newline() {
+        assert_eq!(apply(Transform::SortLines, "b\nA\nc\n"), "A\nb\nc\n");
+    }
+
+    #[test]
+    fn apply_all_names_the_failing_transform() {
+        assert_eq!(apply_all(" x ", &[Transform::Trim, Transform::Uppercase]).unwrap(), "X");
+        let err = apply_all("x", &[Transform::Trim, Transform::JsonMinify]).unwrap_err();
+        assert!(err.starts_with("Minify JSON: Invalid JSON"), "{}", err);
+    }
+}
@@ -0,0 +1,96 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_global_shortcut::{Code, GlobalShortcutExt, Shortcut};
+use tauri_plugin_shell::process::{CommandChild, CommandEvent};
+use tauri_plugin_shell::ShellExt;
+use crate::db::DbState;
+
+/// Characters sent per `xdotool type` call; Escape is checked between chunks.
+const CHUNK_CHARS: usize = 64;
+
+/// Tracks the running type-out so Escape can stop it.
+#[derive(Default)]
+pub struct TypeOutState {
+    active: AtomicBool,
+    cancelled: AtomicBool,
+    child: Mutex<Option<CommandChild>>,
+}
+
+fn cancel_shortcut() -> Shortcut {
+    Shortcut::new(None, Code::Escape)
+}
+
+/// True for the temporary Escape binding registered while typing.
+pub fn is_cancel_shortcut(shortcut: &Shortcut) -> bool {
+    *shortcut == cancel_shortcut()
+}
+
+/// Stops the running type-out, if any.
+pub fn cancel(app: &AppHandle) {
+    let state = app.state::<TypeOutState>();
+    state.cancelled.store(true, Ordering::SeqCst);
+    if let Some(child) = state.child.lock().unwrap().take() {
+        let _ = child.kill();
+    }
+}
+
+/// Sends `text` to the focused window as synthesized keystrokes, for targets
+/// that ignore clipboard paste. xdotool maps characters missing from the
+/// keyboard layout to spare keycodes, so any Unicode text can be typed.
+/// Returns `Ok(false)` when cancelled with Escape.
+pub async fn type_text(app: &AppHandle, text: &str) -> Result<bool, String> {
+    let state = app.state::<TypeOutState>();
+    if state.active.swap(true, Ordering::SeqCst) {
+        return Err("Already typing".to_string());
+    }
+    state.cancelled.store(false, Ordering::SeqCst);
+
+    let gs = app.global_shortcut();
+    let escape_registered = match gs.register(cancel_shortcut()) {
+        Ok(()) => true,
+        Err(e) => {
+            log::warn!("Escape will not cancel typing: {}", e);
+            false
+        }
+    };
+
+    let result = type_chunks(app, &state, text).await;
+
+    if escape_registered {
+        let _ = gs.unregister(cancel_shortcut());
+    }
+    state.child.lock().unwrap().take();
+    state.active.store(false, Ordering::SeqCst);
+    result.map(|_| !state.cancelled.load(Ordering::SeqCst))
+}
+
+async fn type_chunks(app: &AppHandle, state: &TypeOutState, text: &str) -> Result<(), String> {
+    let delay = app.state::<DbState>().get_settings().type_delay_ms.to_string();
+    // Control characters other than newline and tab would be typed as odd key combos.
+    let chars: Vec<char> = text
+        .chars()
+        .filter(|c| !c.is_control() || *c == '\n' || *c == '\t')
+        .collect();
+
+    for chunk in chars.chunks(CHUNK_CHARS) {
+        if state.cancelled.load(Ordering::SeqCst) {
+            break;
+        }
+        let chunk: String = chunk.iter().collect();
+        let (mut rx, child) = app
+            .shell()
+            .command("xdotool")
+            .args(["type", "--clearmodifiers", "--delay", delay.as_str(), "--", chunk.as_str()])
+            .spawn()
+            .map_err(|e| format!("Failed to run xdotool: {}", e))?;
+        *state.child.lock().unwrap() = Some(child);
+
+        while let Some(event) = rx.recv().await {
+            if let CommandEvent::Terminated(_) = event {
+                break;
+            }
+        }
+    }
+    Ok(())
+}
@@ -1,8 +1,10 @@
 import { useState, useEffect, useMemo, useRef } from 'react';
-import type { ClipboardItem, Settings as SettingsType } from './src/types';
+import type { ClipboardItem, ImageEdit, ImageOutput, ItemUpdate, PasteQueue, Separator, Settings as SettingsType, SettingsError } from './src/types';
 import { ClipboardCard } from './components/ClipboardCard';
 import { Settings } from './components/Settings';
-import { Search, Trash2, Layout, Settings as SettingsIcon, Image as ImageIcon, Type, Grid, Smile, Sigma, Clipboard } from 'lucide-react';
+import { ItemEditor } from './components/ItemEditor';
+import { ImageEditor } from './components/ImageEditor';
+import { Search, Trash2, Layout, Settings as SettingsIcon, Image as ImageIcon, Type, Grid, Smile, Sigma, Clipboard, ListOrdered, FileText } from 'lucide-react';
 import {
   DndContext,
   closestCenter,
@@ -22,22 +24,51 @@ import { SortableItem } from './components/SortableItem';
 import { translations } from './locales';
 import { EmojiPicker } from './components/EmojiPicker';
 import { SymbolPicker } from './components/SymbolPicker';
+import { SnippetPicker } from './components/SnippetPicker';
 import { api } from './src/lib/api';
 
 function App() {
   const [history, setHistory] = useState<ClipboardItem[]>([]);
   const [searchQuery, setSearchQuery] = useState('');
+  // Items picked with Ctrl+click for merging, in click order.
+  const [selectedIds, setSelectedIds] = useState<string[]>([]);
+  const [separator, setSeparator] = useState<'newline' | 'comma' | 'tab' | 'custom'>('newline');
+  const [customSeparator, setCustomSeparator] = useState(' ');
   const [settings, setSettings] = useState<SettingsType>({
     position: 'cursor',
     grouping: 'categorized',
     zoom: 100,
     theme: 'dark',
     language: null,
-    useInternalShortcut: false
+    useInternalShortcut: false,
+    toggleShortcut: 'Control+Alt+V',
+    hotkeys: { pastePrevious: null, pastePinned: [], pauseCapture: null, clearHistory: null, pasteNextQueued: null },
+    trashRetentionDays: 7,
+    pasteRules: [],
+    typeDelayMs: 12,
+    transientPaste: false,
+    restoreDelayMs: 500,
+    syncDir: null,
+    syncScope: 'pinned',
+    syncIntervalSecs: 30,
+    dedup: 'newestOnly',
+    similarImages: false,
+    historyOrder: 'recent',
+    autoClear: 'off',
+    autoClearSecs: 30,
+    takeOwnership: false,
+    ocr: false,
+    ocrLanguages: 'eng'
   });
+  const [settingsError, setSettingsError] = useState<string | null>(null);
+  const [queue, setQueue] = useState<PasteQueue>({ itemIds: [], position: 0, collecting: false });
   const [isSettingsOpen, setIsSettingsOpen] = useState(false);
+  const [editingId, setEditingId] = useState<string | null>(null);
+  const [editorError, setEditorError] = useState<string | null>(null);
+  const [imageEditingId, setImageEditingId] = useState<string | null>(null);
+  const [imageEditorError, setImageEditorError] = useState<string | null>(null);
   const [activeTab, setActiveTab] = useState<'all' | 'text' | 'image'>('all');
-  const [viewMode, setViewMode] = useState<'clipboard' | 'emojis' | 'symbols'>('clipboard');
+  const [viewMode, setViewMode] = useState<'clipboard' | 'emojis' | 'symbols' | 'snippets'>('clipboard');
 
   const inputRef = useRef<HTMLInputElement>(null);
 
@@ -67,9 +98,21 @@ function App() {
       }
     });
 
+    // Expired items are already gone from the history sent with clipboard-changed.
+    const unsubscribeExpired = api.onItemExpired((id) => {
+      setSelectedIds(prev => prev.filter(i => i !== id));
+      setEditingId(prev => prev === id ? null : prev);
+      setImageEditingId(prev => prev === id ? null : prev);
+    });
+
+    api.getPasteQueue().then(setQueue);
+    const unsubscribeQueue = api.onPasteQueueChanged(setQueue);
+
     return () => {
       unsubscribe();
       unsubscribeFocus();
+      unsubscribeQueue();
+      unsubscribeExpired();
     };
   }, []);
 
@@ -100,6 +143,10 @@ function App() {
         if (!settings.language) return;
         api.hideWindow();
       }
+      if (e.key === 'z' && (e.ctrlKey || e.metaKey) && !(e.target instanceof HTMLInputElement || e.target instanceof HTMLTextAreaElement)) {
+        e.preventDefault();
+        api.undoLastAction().then(setHistory);
+      }
     };
 
     const handleContextMenu = (e: MouseEvent) => {
@@ -152,14 +199,95 @@ function App() {
     setHistory(newHistory);
   };
 
+  const handleEdit = (id: string, e: React.MouseEvent) => {
+    e.stopPropagation();
+    setEditorError(null);
+    setEditingId(id);
+  };
+
+  const handleSaveEdit = async (id: string, update: ItemUpdate) => {
+    try {
+      setHistory(await api.updateItem(id, update));
+      setEditingId(null);
+    } catch (e) {
+      setEditorError(String(e));
+    }
+  };
+
+  const handleRestoreRevision = async (id: string, index: number) => {
+    try {
+      setHistory(await api.restoreRevision(id, index));
+    } catch (e) {
+      setEditorError(String(e));
+    }
+  };
+
+  const handleSetExpiry = async (id: string, at: number | null) => {
+    try {
+      setHistory(await api.setItemExpiry(id, at));
+    } catch (e) {
+      setEditorError(String(e));
+    }
+  };
+
+  const handleEditImage = (id: string, e: React.MouseEvent) => {
+    e.stopPropagation();
+    setImageEditorError(null);
+    setImageEditingId(id);
+  };
+
+  const handleApplyImageEdits = async (id: string, edits: ImageEdit[], output: ImageOutput) => {
+    try {
+      setHistory(await api.editImage(id, edits, output));
+      setImageEditingId(null);
+    } catch (e) {
+      setImageEditorError(String(e));
+    }
+  };
+
+  const handleCopyText = async (id: string, e: React.MouseEvent) => {
+    e.stopPropagation();
+    await api.copyOcrText(id);
+  };
+
+  const handleCopyDecoded = async (id: string, index: number, e: React.MouseEvent) => {
+    e.stopPropagation();
+    await api.copyDecoded(id, index);
+  };
+
+  // Marks or unmarks an item for the paste queue; marked items paste in click order.
+  const handleQueue = async (id: string, e: React.MouseEvent) => {
+    e.stopPropagation();
+    const ids = queue.itemIds.includes(id)
+      ? queue.itemIds.filter(i => i !== id)
+      : [...queue.itemIds, id];
+    setQueue(await api.setPasteQueue(ids));
+  };
+
+  const handleMerge = async (save: boolean) => {
+    const sep: Separator = separator === 'custom' ? { custom: customSeparator } : separator;
+    const newHistory = await api.mergeItems(selectedIds, sep, save);
+    setHistory(newHistory);
+    setSelectedIds([]);
+  };
+
   const handleClearAll = async () => {
     await api.clearAll();
     const newHistory = await api.getHistory();
     setHistory(newHistory);
   };
 
-  const handlePaste = async (id: string) => {
-    await api.pasteItem(id);
+  // Shift pastes plain text, Alt types the text out key by key, Ctrl selects for merging.
+  const handlePaste = async (id: string, e: React.MouseEvent) => {
+    if (e.ctrlKey || e.metaKey) {
+      setSelectedIds(prev => prev.includes(id) ? prev.filter(i => i !== id) : [...prev, id]);
+    } else if (e.altKey) {
+      await api.typeItem(id);
+    } else if (e.shiftKey) {
+      await api.pastePlain(id);
+    } else {
+      await api.pasteItem(id);
+    }
   };
 
   const handleCopyAndPaste = async (content: string) => {
@@ -167,7 +295,14 @@ function App() {
   }
 
   const updateSetting = async (key: keyof SettingsType, value: any) => {
-    const newSettings = await api.updateSetting(key, value);
+    let newSettings: SettingsType;
+    try {
+      newSettings = await api.updateSetting(key, value);
+    } catch (e) {
+      setSettingsError((e as SettingsError).message ?? String(e));
+      return;
+    }
+    setSettingsError(null);
     setSettings(newSettings);
     if (key === 'zoom') {
       const zoomValue = typeof value === 'number' ? value / 100 : 1.0;
@@ -194,6 +329,10 @@ function App() {
       const lowerQuery = searchQuery.toLowerCase();
       data = data.filter(item =>
         item.content.toLowerCase().includes(lowerQuery) ||
+        item.title?.toLowerCase().includes(lowerQuery) ||
+        item.note?.toLowerCase().includes(lowerQuery) ||
+        item.ocrText?.toLowerCase().includes(lowerQuery) ||
+        item.decoded?.some(code => code.text.toLowerCase().includes(lowerQuery)) ||
         (item.type === 'image' && 'image'.includes(lowerQuery))
       );
     }
@@ -221,6 +360,27 @@ function App() {
         }}
         settings={settings}
         onUpdate={(k, v) => { updateSetting(k, v); }}
+        error={settingsError}
+        t={t}
+      />
+
+      <ItemEditor
+        item={history.find(item => item.id === editingId) ?? null}
+        onClose={() => setEditingId(null)}
+        onSave={handleSaveEdit}
+        onRestore={handleRestoreRevision}
+        onSetExpiry={handleSetExpiry}
+        error={editorError}
+        theme={settings.theme}
+        t={t}
+      />
+
+      <ImageEditor
+        item={history.find(item => item.id === imageEditingId) ?? null}
+        onClose={() => setImageEditingId(null)}
+        onApply={handleApplyImageEdits}
+        error={imageEditorError}
+        theme={settings.theme}
         t={t}
       />
 
@@ -234,6 +394,16 @@ function App() {
           <span className="font-semibold text-sm">{t.appTitle}</span>
         </div>
         <div className="flex gap-2">
+          <button
+            onMouseDown={(e) => e.stopPropagation()}
+            onClick={async () => setQueue(await api.setQueueCollecting(!queue.collecting))}
+            className={`rounded-full p-1.5 transition-colors ${queue.collecting
+              ? 'bg-blue-600 text-white'
+              : settings.theme === 'light' ? 'hover:bg-gray-200 text-gray-600' : 'hover:bg-white/10 text-gray-400'}`}
+            title={t.actions.collectQueue}
+          >
+            <ListOrdered size={14} />
+          </button>
           <button
             onMouseDown={(e) => e.stopPropagation()}
             onClick={() => setIsSettingsOpen(true)}
@@ -285,6 +455,16 @@ function App() {
           <Sigma size={18} className="mb-1" />
           {t.typeNav.symbols}
         </button>
+        <button
+          onClick={() => setViewMode('snippets')}
+          className={`flex flex-1 flex-col items-center py-2 text-[10px] font-medium transition-colors border-b-2 
+            ${viewMode === 'snippets'
+              ? (settings.theme === 'light' ? 'border-blue-600 text-blue-600' : 'border-blue-500 text-blue-400')
+              : 'border-transparent text-gray-500 hover:text-gray-900 dark:hover:text-gray-300'}`}
+        >
+          <FileText size={18} className="mb-1" />
+          {t.typeNav.snippets}
+        </button>
       </div>
 
       {/* Search & Tabs logic */}
@@ -342,13 +522,21 @@ function App() {
             >
               <div className="space-y-2">
                 {filteredHistory.map((item) => (
-                  <SortableItem key={item.id} id={item.id}>
+                  // Manual order has no effect while the history is sorted by use.
+                  <SortableItem key={item.id} id={item.id} disabled={settings.historyOrder === 'frecency'}>
                     <ClipboardCard
                       key={item.id}
                       item={item}
                       onDelete={handleDelete}
                       onPin={handlePin}
-                      onClick={() => handlePaste(item.id)}
+                      onQueue={handleQueue}
+                      onEdit={handleEdit}
+                      onEditImage={handleEditImage}
+                      onCopyText={handleCopyText}
+                      onCopyDecoded={handleCopyDecoded}
+                      selected={selectedIds.includes(item.id)}
+                      queuePosition={queue.itemIds.includes(item.id) ? queue.itemIds.indexOf(item.id) + 1 : undefined}
+                      onClick={(_, e) => handlePaste(item.id, e)}
                       theme={settings.theme}
                       t={t}
                     />
@@ -365,6 +553,40 @@ function App() {
         </div>
       )}
 
+      {viewMode === 'clipboard' && selectedIds.length > 1 && (
+        <div className={`flex items-center gap-2 border-t p-2 text-xs ${settings.theme === 'light' ? 'border-gray-200 bg-gray-50' : 'border-white/5 bg-[#0f0f0f]'}`}>
+          <span className="font-medium">{t.merge.selected.replace('{count}', String(selectedIds.length))}</span>
+          <select
+            value={separator}
+            onChange={(e) => setSeparator(e.target.value as typeof separator)}
+            className={`rounded px-1 py-1 outline-none ${settings.theme === 'light' ? 'bg-white' : 'bg-[#1e1e1e]'}`}
+          >
+            <option value="newline">{t.merge.newline}</option>
+            <option value="comma">{t.merge.comma}</option>
+            <option value="tab">{t.merge.tab}</option>
+            <option value="custom">{t.merge.custom}</option>
+          </select>
+          {separator === 'custom' && (
+            <input
+              type="text"
+              value={customSeparator}
+              onChange={(e) => setCustomSeparator(e.target.value)}
+              className={`w-12 rounded px-1 py-1 font-mono outline-none ${settings.theme === 'light' ? 'bg-white' : 'bg-[#1e1e1e]'}`}
+            />
+          )}
+          <div className="flex-1" />
+          <button onClick={() => handleMerge(false)} className="rounded bg-blue-600 px-2 py-1 font-medium text-white">
+            {t.merge.paste}
+          </button>
+          <button onClick={() => handleMerge(true)} className="rounded px-2 py-1 font-medium text-blue-500">
+            {t.merge.save}
+          </button>
+          <button onClick={() => setSelectedIds([])} className="text-gray-500" title={t.merge.cancel}>
+            ✕
+          </button>
+        </div>
+      )}
+
       {viewMode === 'emojis' && (
         <EmojiPicker onSelect={handleCopyAndPaste} theme={settings.theme} searchQuery={searchQuery} t={t} />
       )}
@@ -373,6 +595,10 @@ function App() {
         <SymbolPicker onSelect={handleCopyAndPaste} theme={settings.theme} t={t} />
       )}
 
+      {viewMode === 'snippets' && (
+        <SnippetPicker theme={settings.theme} searchQuery={searchQuery} t={t} />
+      )}
+
       {/* Footer */}
       <div className={`border-t py-2 text-center text-[10px] ${settings.theme === 'light' ? 'border-gray-200 text-gray-400' : 'border-white/5 text-white/20'}`}>
         {t.footer}
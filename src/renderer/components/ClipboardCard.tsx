@@ -1,18 +1,31 @@
 import React from 'react';
 import type { ClipboardItem } from '../src/types';
-import { GripVertical, Pin, Trash2 } from 'lucide-react';
+import { Crop, GripVertical, ListOrdered, Lock, Pencil, Pin, QrCode, ScanText, Timer, Trash2 } from 'lucide-react';
 import { translations } from '../locales';
 
 interface ClipboardCardProps {
     item: ClipboardItem;
     onDelete: (id: string, e: React.MouseEvent) => void;
     onPin: (id: string, e: React.MouseEvent) => void;
-    onClick: (item: ClipboardItem) => void;
+    onQueue: (id: string, e: React.MouseEvent) => void;
+    onEdit: (id: string, e: React.MouseEvent) => void;
+    /** Opens the crop/resize/annotate editor for an image. */
+    onEditImage: (id: string, e: React.MouseEvent) => void;
+    /** Copies the text recognized in an image. */
+    onCopyText: (id: string, e: React.MouseEvent) => void;
+    /** Copies a QR code or barcode payload found in an image. */
+    onCopyDecoded: (id: string, index: number, e: React.MouseEvent) => void;
+    /** 1-based place in the paste queue, if queued. */
+    queuePosition?: number;
+    /** Selected for merging (Ctrl+click). */
+    selected?: boolean;
+    /** Shift+click requests a plain-text paste, Alt+click typing it out. */
+    onClick: (item: ClipboardItem, e: React.MouseEvent) => void;
     theme: 'light' | 'dark';
     t: typeof translations['en'];
 }
 
-export const ClipboardCard: React.FC<ClipboardCardProps> = ({ item, onDelete, onPin, onClick, theme, t }) => {
+export const ClipboardCard: React.FC<ClipboardCardProps> = ({ item, onDelete, onPin, onQueue, onEdit, onEditImage, onCopyText, onCopyDecoded, queuePosition, selected, onClick, theme, t }) => {
     const isLight = theme === 'light';
 
     return (
@@ -21,8 +34,10 @@ export const ClipboardCard: React.FC<ClipboardCardProps> = ({ item, onDelete, on
                 ${isLight
                     ? 'bg-white border-gray-200 shadow-sm hover:shadow-md hover:border-blue-400'
                     : 'bg-gray-800 border-transparent hover:bg-gray-700 hover:border-gray-600'
-                }`}
-            onClick={() => onClick(item)}
+                }
+                ${selected ? 'ring-2 ring-blue-500' : ''}`}
+            onClick={(e) => onClick(item, e)}
+            title={item.type === 'text' ? t.actions.pastePlainHint : undefined}
         >
             {/* Type Indicator / Icon */}
             <div className={`mr-3 ${isLight ? 'text-gray-400' : 'text-gray-500'}`}>
@@ -31,6 +46,11 @@ export const ClipboardCard: React.FC<ClipboardCardProps> = ({ item, onDelete, on
 
             {/* Content */}
             <div className="flex-1 overflow-hidden">
+                {item.title && (
+                    <p className={`mb-1 truncate text-sm font-semibold ${isLight ? 'text-gray-900' : 'text-white'}`}>
+                        {item.title}
+                    </p>
+                )}
                 {item.type === 'text' ? (
                     <p className={`font-mono whitespace-pre-wrap break-words text-sm line-clamp-3 ${isLight ? 'text-gray-800' : 'text-gray-200'}`}>
                         {item.content}
@@ -42,13 +62,94 @@ export const ClipboardCard: React.FC<ClipboardCardProps> = ({ item, onDelete, on
                         className="max-h-24 w-full rounded object-contain bg-black/50"
                     />
                 )}
+                {item.note && (
+                    <p className={`mt-1 truncate text-xs italic ${isLight ? 'text-gray-500' : 'text-gray-400'}`}>
+                        {item.note}
+                    </p>
+                )}
+                {item.decoded?.map((code, index) => (
+                    <button
+                        key={index}
+                        onClick={(e) => onCopyDecoded(item.id, index, e)}
+                        className={`mt-1 flex max-w-full items-center gap-1 rounded px-1 text-xs transition-colors ${isLight ? 'text-blue-600 hover:bg-gray-100' : 'text-blue-400 hover:bg-white/10'}`}
+                        title={t.actions.copyDecoded.replace('{format}', code.format)}
+                    >
+                        <QrCode size={12} className="shrink-0" />
+                        <span className="truncate">{code.text}</span>
+                    </button>
+                ))}
                 <div className={`text-xs mt-1 ${isLight ? 'text-gray-400' : 'text-gray-500'}`}>
                     {new Date(item.timestamp).toLocaleTimeString()}
                 </div>
             </div>
 
+            {item.isSensitive && (
+                <span className={`mr-2 ${isLight ? 'text-gray-400' : 'text-gray-500'}`} title={t.editor.sensitive}>
+                    <Lock size={14} />
+                </span>
+            )}
+
+            {item.expiresAt && (
+                <span
+                    className={`mr-2 ${isLight ? 'text-orange-500' : 'text-orange-400'}`}
+                    title={t.editor.expiresAt.replace('{time}', new Date(item.expiresAt).toLocaleString())}
+                >
+                    <Timer size={14} />
+                </span>
+            )}
+
+            {item.copyCount > 1 && (
+                <span
+                    className={`mr-2 text-[10px] font-medium ${isLight ? 'text-gray-400' : 'text-gray-500'}`}
+                    title={t.actions.copyCount.replace('{count}', String(item.copyCount))}
+                >
+                    ×{item.copyCount}
+                </span>
+            )}
+
+            {queuePosition !== undefined && (
+                <span className="mr-2 flex h-5 min-w-5 items-center justify-center rounded-full bg-blue-600 px-1 text-[10px] font-bold text-white">
+                    {queuePosition}
+                </span>
+            )}
+
             {/* Actions (Visible on Hover or if Pinned) */}
             <div className="flex flex-col gap-1 opacity-0 transition-opacity group-hover:opacity-100">
+                <button
+                    onClick={(e) => onQueue(item.id, e)}
+                    className={`rounded p-1 transition-colors ${queuePosition !== undefined
+                        ? 'text-blue-400 hover:bg-blue-400/10'
+                        : isLight ? 'text-gray-400 hover:bg-gray-100 hover:text-gray-600' : 'text-gray-500 hover:bg-white/10 hover:text-gray-300'
+                        }`}
+                    title={queuePosition !== undefined ? t.actions.unqueue : t.actions.queue}
+                >
+                    <ListOrdered size={14} />
+                </button>
+                {item.ocrText && (
+                    <button
+                        onClick={(e) => onCopyText(item.id, e)}
+                        className={`rounded p-1 transition-colors ${isLight ? 'text-gray-400 hover:bg-gray-100 hover:text-gray-600' : 'text-gray-500 hover:bg-white/10 hover:text-gray-300'}`}
+                        title={t.actions.copyText}
+                    >
+                        <ScanText size={14} />
+                    </button>
+                )}
+                {item.type === 'image' && (
+                    <button
+                        onClick={(e) => onEditImage(item.id, e)}
+                        className={`rounded p-1 transition-colors ${isLight ? 'text-gray-400 hover:bg-gray-100 hover:text-gray-600' : 'text-gray-500 hover:bg-white/10 hover:text-gray-300'}`}
+                        title={t.actions.editImage}
+                    >
+                        <Crop size={14} />
+                    </button>
+                )}
+                <button
+                    onClick={(e) => onEdit(item.id, e)}
+                    className={`rounded p-1 transition-colors ${isLight ? 'text-gray-400 hover:bg-gray-100 hover:text-gray-600' : 'text-gray-500 hover:bg-white/10 hover:text-gray-300'}`}
+                    title={t.actions.edit}
+                >
+                    <Pencil size={14} />
+                </button>
                 <button
                     onClick={(e) => onPin(item.id, e)}
                     className={`rounded p-1 transition-colors ${item.isPinned
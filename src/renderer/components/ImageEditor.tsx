@@ -0,0 +1,298 @@
+import type { ClipboardItem, ImageEdit, ImageFormat, ImageOutput } from '../src/types';
+import { X, Undo2 } from 'lucide-react';
+import { useState, useEffect, useRef } from 'react';
+import { translations } from '../locales';
+
+type Tool = 'rectangle' | 'arrow';
+type Shape = Extract<ImageEdit, { op: Tool }>;
+type ResizeMode = 'none' | 'percent' | 'maxDimension';
+
+interface ImageEditorProps {
+    item: ClipboardItem | null;
+    onClose: () => void;
+    /** Saves the result as a new item; the edited item is left alone. */
+    onApply: (id: string, edits: ImageEdit[], output: ImageOutput) => Promise<void>;
+    error: string | null;
+    theme: 'light' | 'dark';
+    t: typeof translations['en'];
+}
+
+export const ImageEditor = ({ item, onClose, onApply, error, theme, t }: ImageEditorProps) => {
+    const [size, setSize] = useState({ width: 0, height: 0 });
+    const [crop, setCrop] = useState({ x: 0, y: 0, width: 0, height: 0 });
+    const [resizeMode, setResizeMode] = useState<ResizeMode>('none');
+    const [resizeValue, setResizeValue] = useState(50);
+    const [rotation, setRotation] = useState(0);
+    const [tool, setTool] = useState<Tool>('rectangle');
+    const [color, setColor] = useState('#ff0000');
+    const [thickness, setThickness] = useState(4);
+    const [shapes, setShapes] = useState<Shape[]>([]);
+    const [dragStart, setDragStart] = useState<{ x: number; y: number } | null>(null);
+    const [format, setFormat] = useState<ImageFormat>('png');
+    const [quality, setQuality] = useState(85);
+    const imgRef = useRef<HTMLImageElement>(null);
+
+    useEffect(() => {
+        setSize({ width: 0, height: 0 });
+        setShapes([]);
+        setResizeMode('none');
+        setRotation(0);
+        setFormat('png');
+    }, [item?.id]);
+
+    if (!item || item.type !== 'image') return null;
+
+    const isLight = theme === 'light';
+    const bgColor = isLight ? 'bg-white' : 'bg-[#1e1e1e]';
+    const textColor = isLight ? 'text-gray-900' : 'text-white';
+    const secondaryText = isLight ? 'text-gray-600' : 'text-white/70';
+    const borderColor = isLight ? 'border-gray-200' : 'border-white/5';
+    const inputBg = isLight ? 'bg-gray-100' : 'bg-black/20';
+    const closeBtnHover = isLight ? 'hover:bg-gray-100' : 'hover:bg-white/10';
+    const closeBtnIcon = isLight ? 'text-gray-500' : 'text-white/70';
+    const inactiveText = isLight ? 'text-gray-500 hover:text-gray-900' : 'text-white/50 hover:text-white';
+    const inputClass = `w-full rounded-lg px-2 py-1.5 text-sm outline-none focus:ring-1 focus:ring-blue-500 ${inputBg} ${textColor}`;
+
+    const onLoad = () => {
+        const img = imgRef.current;
+        if (!img) return;
+        setSize({ width: img.naturalWidth, height: img.naturalHeight });
+        setCrop({ x: 0, y: 0, width: img.naturalWidth, height: img.naturalHeight });
+    };
+
+    // Converts a mouse position to image pixels.
+    const toImage = (e: React.MouseEvent) => {
+        const rect = imgRef.current!.getBoundingClientRect();
+        return {
+            x: Math.round((e.clientX - rect.left) * size.width / rect.width),
+            y: Math.round((e.clientY - rect.top) * size.height / rect.height),
+        };
+    };
+
+    const finishShape = (e: React.MouseEvent) => {
+        if (!dragStart) return;
+        const end = toImage(e);
+        setDragStart(null);
+        if (end.x === dragStart.x && end.y === dragStart.y) return;
+        const style = { color, thickness };
+        const shape: Shape = tool === 'rectangle'
+            ? {
+                op: 'rectangle',
+                x: Math.min(dragStart.x, end.x),
+                y: Math.min(dragStart.y, end.y),
+                width: Math.abs(end.x - dragStart.x),
+                height: Math.abs(end.y - dragStart.y),
+                style,
+            }
+            : { op: 'arrow', x1: dragStart.x, y1: dragStart.y, x2: end.x, y2: end.y, style };
+        setShapes([...shapes, shape]);
+    };
+
+    // Annotations use the coordinates of the original image, so they go first.
+    const apply = () => {
+        const edits: ImageEdit[] = [...shapes];
+        if (crop.x > 0 || crop.y > 0 || crop.width < size.width || crop.height < size.height) {
+            edits.push({ op: 'crop', ...crop });
+        }
+        if (rotation) {
+            edits.push({ op: 'rotate', degrees: rotation });
+        }
+        if (resizeMode === 'percent') {
+            edits.push({ op: 'resize', by: { percent: resizeValue } });
+        } else if (resizeMode === 'maxDimension') {
+            edits.push({ op: 'resize', by: { maxDimension: resizeValue } });
+        }
+        onApply(item.id, edits, { format, quality: format === 'jpeg' ? quality : undefined });
+    };
+
+    const numberInput = (value: number, onChange: (value: number) => void, min = 0) => (
+        <input
+            type="number"
+            min={min}
+            value={value}
+            onChange={(e) => onChange(Math.max(min, Number(e.target.value) || 0))}
+            className={inputClass}
+        />
+    );
+
+    return (
+        <div className="fixed inset-0 z-50 flex items-center justify-center bg-black/50 backdrop-blur-sm p-4">
+            <div className={`flex w-full max-w-sm flex-col rounded-xl border shadow-2xl max-h-[85vh] overflow-hidden ${bgColor} ${isLight ? 'border-gray-200' : 'border-white/10'}`}>
+                <div className={`flex items-center justify-between border-b p-4 ${borderColor}`}>
+                    <h2 className={`text-lg font-bold ${textColor}`}>{t.imageEditor.title}</h2>
+                    <button onClick={onClose} className={`rounded-full p-1 ${closeBtnHover}`}>
+                        <X size={20} className={closeBtnIcon} />
+                    </button>
+                </div>
+
+                <div className="space-y-4 overflow-y-auto p-4">
+                    <div className="relative select-none">
+                        <img
+                            ref={imgRef}
+                            src={`data:image/png;base64,${item.content}`}
+                            alt=""
+                            draggable={false}
+                            onLoad={onLoad}
+                            onMouseDown={(e) => setDragStart(toImage(e))}
+                            onMouseUp={finishShape}
+                            className="w-full cursor-crosshair rounded bg-black/50"
+                        />
+                        {size.width > 0 && (
+                            <svg
+                                viewBox={`0 0 ${size.width} ${size.height}`}
+                                className="pointer-events-none absolute inset-0 h-full w-full"
+                            >
+                                <rect
+                                    x={crop.x} y={crop.y} width={crop.width} height={crop.height}
+                                    fill="none" stroke="#3b82f6" strokeDasharray="8 4" strokeWidth={Math.max(1, size.width / 300)}
+                                />
+                                {shapes.map((shape, index) => shape.op === 'rectangle' ? (
+                                    <rect
+                                        key={index}
+                                        x={shape.x} y={shape.y} width={shape.width} height={shape.height}
+                                        fill="none" stroke={shape.style?.color} strokeWidth={shape.style?.thickness}
+                                    />
+                                ) : (
+                                    <line
+                                        key={index}
+                                        x1={shape.x1} y1={shape.y1} x2={shape.x2} y2={shape.y2}
+                                        stroke={shape.style?.color} strokeWidth={shape.style?.thickness}
+                                    />
+                                ))}
+                            </svg>
+                        )}
+                    </div>
+                    <p className={`text-xs ${isLight ? 'text-gray-500' : 'text-white/50'}`}>
+                        {t.imageEditor.size.replace('{width}', String(size.width)).replace('{height}', String(size.height))}
+                    </p>
+
+                    <div className="space-y-1">
+                        <label className={`text-sm font-medium ${secondaryText}`}>{t.imageEditor.annotate}</label>
+                        <div className="flex items-center gap-2">
+                            <div className={`flex flex-1 rounded-lg p-1 ${inputBg}`}>
+                                {(['rectangle', 'arrow'] as Tool[]).map(option => (
+                                    <button
+                                        key={option}
+                                        onClick={() => setTool(option)}
+                                        className={`flex-1 rounded-md py-1.5 text-sm font-medium transition-all ${tool === option
+                                            ? 'bg-blue-600 text-white shadow-md'
+                                            : inactiveText
+                                            }`}
+                                    >
+                                        {t.imageEditor.tools[option]}
+                                    </button>
+                                ))}
+                            </div>
+                            <input
+                                type="color"
+                                value={color}
+                                onChange={(e) => setColor(e.target.value)}
+                                className="h-8 w-8 shrink-0 cursor-pointer rounded bg-transparent"
+                                title={t.imageEditor.color}
+                            />
+                            <div className="w-16 shrink-0" title={t.imageEditor.thickness}>
+                                {numberInput(thickness, setThickness, 1)}
+                            </div>
+                            <button
+                                onClick={() => setShapes(shapes.slice(0, -1))}
+                                disabled={shapes.length === 0}
+                                className={`rounded p-1 disabled:opacity-30 ${closeBtnHover} ${closeBtnIcon}`}
+                                title={t.imageEditor.undoShape}
+                            >
+                                <Undo2 size={14} />
+                            </button>
+                        </div>
+                        <p className={`text-xs ${isLight ? 'text-gray-500' : 'text-white/50'}`}>{t.imageEditor.annotateDesc}</p>
+                    </div>
+
+                    <div className="space-y-1">
+                        <label className={`text-sm font-medium ${secondaryText}`}>{t.imageEditor.crop}</label>
+                        <div className="grid grid-cols-4 gap-2">
+                            {(['x', 'y', 'width', 'height'] as const).map(key => (
+                                <div key={key} title={t.imageEditor.cropFields[key]}>
+                                    {numberInput(crop[key], (value) => setCrop({ ...crop, [key]: value }))}
+                                </div>
+                            ))}
+                        </div>
+                    </div>
+
+                    <div className="space-y-1">
+                        <label className={`text-sm font-medium ${secondaryText}`}>{t.imageEditor.rotate}</label>
+                        <select
+                            value={rotation}
+                            onChange={(e) => setRotation(Number(e.target.value))}
+                            className={inputClass}
+                        >
+                            {[0, 90, 180, 270].map(degrees => (
+                                <option key={degrees} value={degrees}>{degrees}°</option>
+                            ))}
+                        </select>
+                    </div>
+
+                    <div className="space-y-1">
+                        <label className={`text-sm font-medium ${secondaryText}`}>{t.imageEditor.resize}</label>
+                        <div className="flex gap-2">
+                            <select
+                                value={resizeMode}
+                                onChange={(e) => {
+                                    const mode = e.target.value as ResizeMode;
+                                    setResizeMode(mode);
+                                    setResizeValue(mode === 'maxDimension' ? 1280 : 50);
+                                }}
+                                className={inputClass}
+                            >
+                                {Object.entries(t.imageEditor.resizeModes).map(([value, label]) => (
+                                    <option key={value} value={value}>{label}</option>
+                                ))}
+                            </select>
+                            {resizeMode !== 'none' && (
+                                <div className="w-24 shrink-0">{numberInput(resizeValue, setResizeValue, 1)}</div>
+                            )}
+                        </div>
+                    </div>
+
+                    <div className="space-y-1">
+                        <label className={`text-sm font-medium ${secondaryText}`}>{t.imageEditor.format}</label>
+                        <div className="flex gap-2">
+                            <select
+                                value={format}
+                                onChange={(e) => setFormat(e.target.value as ImageFormat)}
+                                className={inputClass}
+                            >
+                                {Object.entries(t.imageEditor.formats).map(([value, label]) => (
+                                    <option key={value} value={value}>{label}</option>
+                                ))}
+                            </select>
+                            {format === 'jpeg' && (
+                                <div className="w-24 shrink-0" title={t.imageEditor.quality}>
+                                    {numberInput(quality, (value) => setQuality(Math.min(100, value)), 1)}
+                                </div>
+                            )}
+                        </div>
+                    </div>
+                </div>
+
+                {error && (
+                    <div className="border-t border-red-500/30 bg-red-500/10 px-4 py-2 text-xs text-red-500">
+                        {error}
+                    </div>
+                )}
+
+                <div className={`flex justify-end gap-2 border-t p-3 ${borderColor}`}>
+                    <button
+                        onClick={onClose}
+                        className={`rounded-lg px-3 py-1.5 text-sm ${isLight ? 'text-gray-600 hover:bg-gray-100' : 'text-white/70 hover:bg-white/10'}`}
+                    >
+                        {t.editor.cancel}
+                    </button>
+                    <button
+                        onClick={apply}
+                        className="rounded-lg bg-blue-600 px-3 py-1.5 text-sm font-medium text-white hover:bg-blue-500"
+                    >
+                        {t.imageEditor.saveCopy}
+                    </button>
+                </div>
+            </div>
+        </div>
+    );
+};
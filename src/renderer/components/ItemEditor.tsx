@@ -0,0 +1,177 @@
+import type { ClipboardItem, ItemUpdate } from '../src/types';
+import { X, RotateCcw } from 'lucide-react';
+import { useState, useEffect } from 'react';
+import { translations } from '../locales';
+
+const EXPIRY_SECONDS: Record<string, number> = { '1m': 60, '5m': 300, '1h': 3600, '1d': 86400 };
+
+interface ItemEditorProps {
+    item: ClipboardItem | null;
+    onClose: () => void;
+    onSave: (id: string, update: ItemUpdate) => Promise<void>;
+    onRestore: (id: string, index: number) => Promise<void>;
+    onSetExpiry: (id: string, at: number | null) => Promise<void>;
+    error: string | null;
+    theme: 'light' | 'dark';
+    t: typeof translations['en'];
+}
+
+export const ItemEditor = ({ item, onClose, onSave, onRestore, onSetExpiry, error, theme, t }: ItemEditorProps) => {
+    const [title, setTitle] = useState('');
+    const [note, setNote] = useState('');
+    const [content, setContent] = useState('');
+    const [sensitive, setSensitive] = useState(false);
+
+    useEffect(() => {
+        setTitle(item?.title ?? '');
+        setNote(item?.note ?? '');
+        setContent(item?.content ?? '');
+        setSensitive(item?.isSensitive ?? false);
+    }, [item]);
+
+    if (!item) return null;
+
+    const isLight = theme === 'light';
+    const bgColor = isLight ? 'bg-white' : 'bg-[#1e1e1e]';
+    const textColor = isLight ? 'text-gray-900' : 'text-white';
+    const secondaryText = isLight ? 'text-gray-600' : 'text-white/70';
+    const borderColor = isLight ? 'border-gray-200' : 'border-white/5';
+    const inputBg = isLight ? 'bg-gray-100' : 'bg-black/20';
+    const closeBtnHover = isLight ? 'hover:bg-gray-100' : 'hover:bg-white/10';
+    const closeBtnIcon = isLight ? 'text-gray-500' : 'text-white/70';
+    const inactiveText = isLight ? 'text-gray-500 hover:text-gray-900' : 'text-white/50 hover:text-white';
+    const revisions = item.revisions ?? [];
+
+    const save = () => onSave(item.id, {
+        title,
+        note,
+        sensitive,
+        content: item.type === 'text' ? content : undefined,
+    });
+
+    return (
+        <div className="fixed inset-0 z-50 flex items-center justify-center bg-black/50 backdrop-blur-sm p-4">
+            <div className={`flex w-full max-w-sm flex-col rounded-xl border shadow-2xl max-h-[85vh] overflow-hidden ${bgColor} ${isLight ? 'border-gray-200' : 'border-white/10'}`}>
+                <div className={`flex items-center justify-between border-b p-4 ${borderColor}`}>
+                    <h2 className={`text-lg font-bold ${textColor}`}>{t.editor.title}</h2>
+                    <button onClick={onClose} className={`rounded-full p-1 ${closeBtnHover}`}>
+                        <X size={20} className={closeBtnIcon} />
+                    </button>
+                </div>
+
+                <div className="space-y-4 overflow-y-auto p-4">
+                    <div className="space-y-1">
+                        <label className={`text-sm font-medium ${secondaryText}`}>{t.editor.itemTitle}</label>
+                        <input
+                            type="text"
+                            value={title}
+                            onChange={(e) => setTitle(e.target.value)}
+                            className={`w-full rounded-lg px-3 py-1.5 text-sm outline-none focus:ring-1 focus:ring-blue-500 ${inputBg} ${textColor}`}
+                        />
+                    </div>
+
+                    <div className="space-y-1">
+                        <label className={`text-sm font-medium ${secondaryText}`}>{t.editor.note}</label>
+                        <textarea
+                            value={note}
+                            rows={2}
+                            onChange={(e) => setNote(e.target.value)}
+                            className={`w-full resize-y rounded-lg px-3 py-1.5 text-sm outline-none focus:ring-1 focus:ring-blue-500 ${inputBg} ${textColor}`}
+                        />
+                    </div>
+
+                    {item.type === 'text' && (
+                        <div className="space-y-1">
+                            <label className={`text-sm font-medium ${secondaryText}`}>{t.editor.content}</label>
+                            <textarea
+                                value={content}
+                                rows={8}
+                                onChange={(e) => setContent(e.target.value)}
+                                className={`w-full resize-y rounded-lg px-3 py-1.5 font-mono text-sm outline-none focus:ring-1 focus:ring-blue-500 ${inputBg} ${textColor}`}
+                            />
+                        </div>
+                    )}
+
+                    <div className="space-y-1">
+                        <label className={`text-sm font-medium ${secondaryText}`}>{t.editor.sensitive}</label>
+                        <div className={`flex rounded-lg p-1 ${inputBg}`}>
+                            <button
+                                onClick={() => setSensitive(!sensitive)}
+                                className={`flex-1 rounded-md py-1.5 text-sm font-medium transition-all ${sensitive
+                                    ? 'bg-blue-600 text-white shadow-md'
+                                    : inactiveText
+                                    }`}
+                            >
+                                {sensitive ? 'ON' : 'OFF'}
+                            </button>
+                        </div>
+                    </div>
+
+                    <div className="space-y-1">
+                        <label className={`text-sm font-medium ${secondaryText}`}>{t.editor.expiry}</label>
+                        {item.expiresAt && (
+                            <p className={`text-xs ${isLight ? 'text-gray-500' : 'text-white/50'}`}>
+                                {t.editor.expiresAt.replace('{time}', new Date(item.expiresAt).toLocaleString())}
+                            </p>
+                        )}
+                        <select
+                            value=""
+                            onChange={(e) => {
+                                const seconds = EXPIRY_SECONDS[e.target.value];
+                                onSetExpiry(item.id, seconds ? Date.now() + seconds * 1000 : null);
+                            }}
+                            className={`w-full rounded-lg px-2 py-1.5 text-sm outline-none ${inputBg} ${textColor}`}
+                        >
+                            <option value="" disabled>{t.editor.expiry}</option>
+                            {Object.entries(t.editor.expiryOptions).map(([value, label]) => (
+                                <option key={value} value={value}>{label}</option>
+                            ))}
+                        </select>
+                    </div>
+
+                    {revisions.length > 0 && (
+                        <div className="space-y-1">
+                            <label className={`text-sm font-medium ${secondaryText}`}>{t.editor.revisions}</label>
+                            {revisions.map((revision, index) => (
+                                <div key={index} className={`flex items-center gap-2 rounded-lg px-2 py-1 text-xs ${inputBg}`}>
+                                    <span className={`w-24 shrink-0 ${isLight ? 'text-gray-500' : 'text-white/50'}`}>
+                                        {index === 0 ? t.editor.original : new Date(revision.replacedAt).toLocaleString()}
+                                    </span>
+                                    <span className={`flex-1 truncate font-mono ${textColor}`}>{revision.content}</span>
+                                    <button
+                                        onClick={() => onRestore(item.id, index)}
+                                        className={`rounded p-1 ${closeBtnHover} ${closeBtnIcon}`}
+                                        title={t.editor.restore}
+                                    >
+                                        <RotateCcw size={12} />
+                                    </button>
+                                </div>
+                            ))}
+                        </div>
+                    )}
+                </div>
+
+                {error && (
+                    <div className="border-t border-red-500/30 bg-red-500/10 px-4 py-2 text-xs text-red-500">
+                        {error}
+                    </div>
+                )}
+
+                <div className={`flex justify-end gap-2 border-t p-3 ${borderColor}`}>
+                    <button
+                        onClick={onClose}
+                        className={`rounded-lg px-3 py-1.5 text-sm ${isLight ? 'text-gray-600 hover:bg-gray-100' : 'text-white/70 hover:bg-white/10'}`}
+                    >
+                        {t.editor.cancel}
+                    </button>
+                    <button
+                        onClick={save}
+                        className="rounded-lg bg-blue-600 px-3 py-1.5 text-sm font-medium text-white hover:bg-blue-500"
+                    >
+                        {t.editor.save}
+                    </button>
+                </div>
+            </div>
+        </div>
+    );
+};
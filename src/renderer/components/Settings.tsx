@@ -1,5 +1,5 @@
-import type { Settings as SettingsType } from '../src/types';
-import { X, Sun, Monitor, ZoomIn, Keyboard, Globe } from 'lucide-react';
+import type { AutoClear, DedupStrategy, ExportFormat, HistoryOrder, ImportMode, ImportSource, PasteMode, PasteRule, Settings as SettingsType, Stats, SyncScope } from '../src/types';
+import { X, Sun, Monitor, ZoomIn, Keyboard, Globe, ClipboardType, Trash2, Archive, RefreshCw, CopyCheck, BarChart3, Eraser, Anchor, ScanText } from 'lucide-react';
 import { useState, useEffect } from 'react';
 import { translations } from '../locales';
 import { api } from '../src/lib/api';
@@ -11,12 +11,45 @@ interface SettingsProps {
     onClose: () => void;
     settings: SettingsType;
     onUpdate: (key: keyof SettingsType, value: any) => void;
+    error: string | null;
     t: typeof translations['en'];
 }
 
-export const Settings = ({ isOpen, onClose, settings, onUpdate, t }: SettingsProps) => {
+export const Settings = ({ isOpen, onClose, settings, onUpdate, error, t }: SettingsProps) => {
     const [appPath, setAppPath] = useState<string>('...');
     const [autoStartEnabled, setAutoStartEnabled] = useState(false);
+    const [shortcutDraft, setShortcutDraft] = useState(settings.toggleShortcut);
+    const [ruleAppDraft, setRuleAppDraft] = useState('');
+    const [backupPath, setBackupPath] = useState('');
+    const [exportFormat, setExportFormat] = useState<ExportFormat>('zip');
+    const [importMode, setImportMode] = useState<ImportMode>('merge');
+    const [importSource, setImportSource] = useState<ImportSource | ''>('');
+    const [backupStatus, setBackupStatus] = useState<string | null>(null);
+    const [syncDirDraft, setSyncDirDraft] = useState(settings.syncDir ?? '');
+    const [syncStatus, setSyncStatus] = useState<string | null>(null);
+    const [stats, setStats] = useState<Stats | null>(null);
+    const [ocrAvailable, setOcrAvailable] = useState(true);
+    const [ocrLanguagesDraft, setOcrLanguagesDraft] = useState(settings.ocrLanguages);
+
+    useEffect(() => {
+        setShortcutDraft(settings.toggleShortcut);
+    }, [settings.toggleShortcut]);
+
+    useEffect(() => {
+        setSyncDirDraft(settings.syncDir ?? '');
+    }, [settings.syncDir]);
+
+    useEffect(() => {
+        if (isOpen) api.getStats().then(setStats);
+    }, [isOpen]);
+
+    useEffect(() => {
+        setOcrLanguagesDraft(settings.ocrLanguages);
+    }, [settings.ocrLanguages]);
+
+    useEffect(() => {
+        if (settings.ocr) api.ocrAvailable().then(setOcrAvailable);
+    }, [settings.ocr]);
 
     useEffect(() => {
         api.getAppPath().then(setAppPath);
@@ -46,6 +79,51 @@ export const Settings = ({ isOpen, onClose, settings, onUpdate, t }: SettingsPro
         }
     };
 
+    const updateRules = (rules: PasteRule[]) => onUpdate('pasteRules', rules);
+
+    const addRule = () => {
+        const app = ruleAppDraft.trim();
+        if (!app) return;
+        updateRules([...settings.pasteRules, { app, mode: 'plainText' }]);
+        setRuleAppDraft('');
+    };
+
+    const exportHistory = async () => {
+        try {
+            const count = await api.exportHistory(backupPath.trim(), exportFormat);
+            setBackupStatus(t.settings.exported.replace('{count}', String(count)));
+        } catch (e) {
+            setBackupStatus(String(e));
+        }
+    };
+
+    const importHistory = async () => {
+        try {
+            const summary = await api.importHistory(backupPath.trim(), importMode, importSource || undefined);
+            setBackupStatus(t.settings.imported
+                .replace('{added}', String(summary.added))
+                .replace('{skipped}', String(summary.skippedDuplicates))
+                .replace('{invalid}', String(summary.skippedInvalid)));
+        } catch (e) {
+            setBackupStatus(String(e));
+        }
+    };
+
+    const syncNow = async () => {
+        try {
+            const report = await api.syncNow();
+            setSyncStatus(t.settings.synced
+                .replace('{pushed}', String(report.pushed))
+                .replace('{pulled}', String(report.pulled))
+                .replace('{removed}', String(report.removed)));
+        } catch (e) {
+            setSyncStatus(String(e));
+        }
+    };
+
+    const formatSize = (bytes: number) =>
+        bytes < 1024 * 1024 ? `${(bytes / 1024).toFixed(1)} KB` : `${(bytes / 1024 / 1024).toFixed(1)} MB`;
+
     if (!isOpen) return null;
 
     const isLight = settings.theme === 'light';
@@ -187,6 +265,417 @@ export const Settings = ({ isOpen, onClose, settings, onUpdate, t }: SettingsPro
                                 {settings.useInternalShortcut ? 'ON' : 'OFF'}
                             </button>
                         </div>
+                        <label className={`block text-xs ${isLight ? 'text-gray-500' : 'text-white/50'}`}>
+                            {t.settings.toggleShortcut}
+                        </label>
+                        <input
+                            type="text"
+                            value={shortcutDraft}
+                            onChange={(e) => setShortcutDraft(e.target.value)}
+                            onKeyDown={(e) => {
+                                if (e.key === 'Enter') onUpdate('toggleShortcut', shortcutDraft);
+                            }}
+                            onBlur={() => {
+                                if (shortcutDraft !== settings.toggleShortcut) onUpdate('toggleShortcut', shortcutDraft);
+                            }}
+                            className={`w-full rounded-lg px-3 py-1.5 font-mono text-sm outline-none focus:ring-1 focus:ring-blue-500 ${inputBg} ${textColor}`}
+                        />
+                        <p className={`text-xs ${isLight ? 'text-gray-500' : 'text-white/50'}`}>
+                            {t.settings.toggleShortcutHint}
+                        </p>
+                    </div>
+
+                    {/* Paste Rules */}
+                    <div className="space-y-3">
+                        <label className={`flex items-center gap-2 text-sm font-medium ${secondaryText}`}>
+                            <ClipboardType size={16} /> {t.settings.pasteRules}
+                        </label>
+                        <p className={`text-xs ${isLight ? 'text-gray-500' : 'text-white/50'}`}>
+                            {t.settings.pasteRulesDesc}
+                        </p>
+                        {settings.pasteRules.map((rule, i) => (
+                            <div key={i} className="flex items-center gap-2">
+                                <span className={`flex-1 truncate font-mono text-sm ${textColor}`}>{rule.app}</span>
+                                <select
+                                    value={rule.mode}
+                                    onChange={(e) => updateRules(settings.pasteRules.map((r, j) =>
+                                        j === i ? { ...r, mode: e.target.value as PasteMode } : r
+                                    ))}
+                                    className={`rounded-lg px-2 py-1 text-sm outline-none ${inputBg} ${textColor}`}
+                                >
+                                    {(Object.keys(t.settings.pasteModes) as PasteMode[]).map(mode => (
+                                        <option key={mode} value={mode}>{t.settings.pasteModes[mode]}</option>
+                                    ))}
+                                </select>
+                                <button
+                                    onClick={() => updateRules(settings.pasteRules.filter((_, j) => j !== i))}
+                                    className={inactiveText}
+                                    title={t.actions.delete}
+                                >
+                                    <Trash2 size={14} />
+                                </button>
+                            </div>
+                        ))}
+                        <div className="flex gap-2">
+                            <input
+                                type="text"
+                                value={ruleAppDraft}
+                                placeholder={t.settings.pasteRuleApp}
+                                onChange={(e) => setRuleAppDraft(e.target.value)}
+                                onKeyDown={(e) => {
+                                    if (e.key === 'Enter') addRule();
+                                }}
+                                className={`flex-1 rounded-lg px-3 py-1.5 font-mono text-sm outline-none focus:ring-1 focus:ring-blue-500 ${inputBg} ${textColor}`}
+                            />
+                            <button
+                                onClick={addRule}
+                                className="rounded-lg bg-blue-600 px-3 py-1.5 text-sm font-medium text-white"
+                            >
+                                {t.settings.addRule}
+                            </button>
+                        </div>
+                        <label className={`block text-xs ${isLight ? 'text-gray-500' : 'text-white/50'}`}>
+                            {t.settings.typeDelay}
+                        </label>
+                        <input
+                            type="number"
+                            min={0}
+                            max={1000}
+                            value={settings.typeDelayMs}
+                            onChange={(e) => onUpdate('typeDelayMs', Number(e.target.value))}
+                            className={`w-24 rounded-lg px-3 py-1.5 text-sm outline-none focus:ring-1 focus:ring-blue-500 ${inputBg} ${textColor}`}
+                        />
+                    </div>
+
+                    {/* Transient Paste */}
+                    <div className="space-y-3">
+                        <label className={`flex items-center gap-2 text-sm font-medium ${secondaryText}`}>
+                            <ClipboardType size={16} /> {t.settings.transientPaste}
+                        </label>
+                        <p className={`text-xs ${isLight ? 'text-gray-500' : 'text-white/50'}`}>
+                            {t.settings.transientPasteDesc}
+                        </p>
+                        <div className={`flex rounded-lg p-1 ${inputBg}`}>
+                            <button
+                                onClick={() => onUpdate('transientPaste', !settings.transientPaste)}
+                                className={`flex-1 rounded-md py-1.5 text-sm font-medium transition-all ${settings.transientPaste
+                                    ? 'bg-blue-600 text-white shadow-md'
+                                    : inactiveText
+                                    }`}
+                            >
+                                {settings.transientPaste ? 'ON' : 'OFF'}
+                            </button>
+                        </div>
+                        {settings.transientPaste && (
+                            <>
+                                <label className={`block text-xs ${isLight ? 'text-gray-500' : 'text-white/50'}`}>
+                                    {t.settings.restoreDelay}
+                                </label>
+                                <input
+                                    type="number"
+                                    min={100}
+                                    max={10000}
+                                    step={100}
+                                    value={settings.restoreDelayMs}
+                                    onChange={(e) => onUpdate('restoreDelayMs', Number(e.target.value))}
+                                    className={`w-24 rounded-lg px-3 py-1.5 text-sm outline-none focus:ring-1 focus:ring-blue-500 ${inputBg} ${textColor}`}
+                                />
+                            </>
+                        )}
+                    </div>
+
+                    {/* OCR */}
+                    <div className="space-y-3">
+                        <label className={`flex items-center gap-2 text-sm font-medium ${secondaryText}`}>
+                            <ScanText size={16} /> {t.settings.ocr}
+                        </label>
+                        <p className={`text-xs ${isLight ? 'text-gray-500' : 'text-white/50'}`}>
+                            {t.settings.ocrDesc}
+                        </p>
+                        <div className={`flex rounded-lg p-1 ${inputBg}`}>
+                            <button
+                                onClick={() => onUpdate('ocr', !settings.ocr)}
+                                className={`flex-1 rounded-md py-1.5 text-sm font-medium transition-all ${settings.ocr
+                                    ? 'bg-blue-600 text-white shadow-md'
+                                    : inactiveText
+                                    }`}
+                            >
+                                {settings.ocr ? 'ON' : 'OFF'}
+                            </button>
+                        </div>
+                        {settings.ocr && (
+                            <>
+                                <input
+                                    type="text"
+                                    value={ocrLanguagesDraft}
+                                    placeholder={t.settings.ocrLanguages}
+                                    onChange={(e) => setOcrLanguagesDraft(e.target.value)}
+                                    onBlur={() => {
+                                        const languages = ocrLanguagesDraft.trim();
+                                        if (languages !== settings.ocrLanguages) onUpdate('ocrLanguages', languages);
+                                    }}
+                                    className={`w-full rounded-lg px-3 py-1.5 font-mono text-sm outline-none focus:ring-1 focus:ring-blue-500 ${inputBg} ${textColor}`}
+                                />
+                                {!ocrAvailable && (
+                                    <p className="text-xs text-red-500">{t.settings.ocrMissing}</p>
+                                )}
+                            </>
+                        )}
+                    </div>
+
+                    {/* Take Ownership */}
+                    <div className="space-y-3">
+                        <label className={`flex items-center gap-2 text-sm font-medium ${secondaryText}`}>
+                            <Anchor size={16} /> {t.settings.takeOwnership}
+                        </label>
+                        <p className={`text-xs ${isLight ? 'text-gray-500' : 'text-white/50'}`}>
+                            {t.settings.takeOwnershipDesc}
+                        </p>
+                        <div className={`flex rounded-lg p-1 ${inputBg}`}>
+                            <button
+                                onClick={() => onUpdate('takeOwnership', !settings.takeOwnership)}
+                                className={`flex-1 rounded-md py-1.5 text-sm font-medium transition-all ${settings.takeOwnership
+                                    ? 'bg-blue-600 text-white shadow-md'
+                                    : inactiveText
+                                    }`}
+                            >
+                                {settings.takeOwnership ? 'ON' : 'OFF'}
+                            </button>
+                        </div>
+                    </div>
+
+                    {/* Duplicates */}
+                    <div className="space-y-3">
+                        <label className={`flex items-center gap-2 text-sm font-medium ${secondaryText}`}>
+                            <CopyCheck size={16} /> {t.settings.dedup}
+                        </label>
+                        <p className={`text-xs ${isLight ? 'text-gray-500' : 'text-white/50'}`}>
+                            {t.settings.dedupDesc}
+                        </p>
+                        <select
+                            value={settings.dedup}
+                            onChange={(e) => onUpdate('dedup', e.target.value as DedupStrategy)}
+                            className={`w-full rounded-lg px-2 py-1.5 text-sm outline-none ${inputBg} ${textColor}`}
+                        >
+                            {(Object.keys(t.settings.dedupStrategies) as DedupStrategy[]).map(strategy => (
+                                <option key={strategy} value={strategy}>{t.settings.dedupStrategies[strategy]}</option>
+                            ))}
+                        </select>
+                        <label className={`block text-xs ${isLight ? 'text-gray-500' : 'text-white/50'}`}>
+                            {t.settings.similarImages}
+                        </label>
+                        <div className={`flex rounded-lg p-1 ${inputBg}`}>
+                            <button
+                                onClick={() => onUpdate('similarImages', !settings.similarImages)}
+                                className={`flex-1 rounded-md py-1.5 text-sm font-medium transition-all ${settings.similarImages
+                                    ? 'bg-blue-600 text-white shadow-md'
+                                    : inactiveText
+                                    }`}
+                            >
+                                {settings.similarImages ? 'ON' : 'OFF'}
+                            </button>
+                        </div>
+                    </div>
+
+                    {/* Usage */}
+                    <div className="space-y-3">
+                        <label className={`flex items-center gap-2 text-sm font-medium ${secondaryText}`}>
+                            <BarChart3 size={16} /> {t.settings.usage}
+                        </label>
+                        <p className={`text-xs ${isLight ? 'text-gray-500' : 'text-white/50'}`}>
+                            {t.settings.usageDesc}
+                        </p>
+                        <select
+                            value={settings.historyOrder}
+                            onChange={(e) => onUpdate('historyOrder', e.target.value as HistoryOrder)}
+                            className={`w-full rounded-lg px-2 py-1.5 text-sm outline-none ${inputBg} ${textColor}`}
+                        >
+                            {(Object.keys(t.settings.historyOrders) as HistoryOrder[]).map(order => (
+                                <option key={order} value={order}>{t.settings.historyOrders[order]}</option>
+                            ))}
+                        </select>
+                        {stats && (
+                            <div className={`space-y-1 text-xs ${isLight ? 'text-gray-500' : 'text-white/50'}`}>
+                                <p>
+                                    {t.settings.stats
+                                        .replace('{items}', String(stats.totalItems))
+                                        .replace('{pastes}', String(stats.totalPastes))
+                                        .replace('{recopies}', String(stats.totalRecopies))
+                                        .replace('{size}', formatSize(stats.storageBytes))}
+                                </p>
+                                <p>
+                                    {t.settings.statsByType
+                                        .replace('{text}', String(stats.byType.text ?? 0))
+                                        .replace('{image}', String(stats.byType.image ?? 0))}
+                                </p>
+                                {stats.mostPasted.length > 0 && (
+                                    <>
+                                        <p className="font-medium">{t.settings.mostPasted}</p>
+                                        {stats.mostPasted.slice(0, 5).map(item => (
+                                            <p key={item.id} className="truncate">
+                                                ×{item.pasteCount} {item.type === 'image' ? t.tabs.image : item.content}
+                                            </p>
+                                        ))}
+                                    </>
+                                )}
+                            </div>
+                        )}
+                    </div>
+
+                    {/* Export & Import */}
+                    <div className="space-y-3">
+                        <label className={`flex items-center gap-2 text-sm font-medium ${secondaryText}`}>
+                            <Archive size={16} /> {t.settings.backup}
+                        </label>
+                        <p className={`text-xs ${isLight ? 'text-gray-500' : 'text-white/50'}`}>
+                            {t.settings.backupDesc}
+                        </p>
+                        <input
+                            type="text"
+                            value={backupPath}
+                            placeholder={t.settings.backupPath}
+                            onChange={(e) => setBackupPath(e.target.value)}
+                            className={`w-full rounded-lg px-3 py-1.5 font-mono text-sm outline-none focus:ring-1 focus:ring-blue-500 ${inputBg} ${textColor}`}
+                        />
+                        <div className="flex gap-2">
+                            <select
+                                value={exportFormat}
+                                onChange={(e) => setExportFormat(e.target.value as ExportFormat)}
+                                className={`flex-1 rounded-lg px-2 py-1 text-sm outline-none ${inputBg} ${textColor}`}
+                            >
+                                {(Object.keys(t.settings.exportFormats) as ExportFormat[]).map(format => (
+                                    <option key={format} value={format}>{t.settings.exportFormats[format]}</option>
+                                ))}
+                            </select>
+                            <button
+                                onClick={exportHistory}
+                                disabled={!backupPath.trim()}
+                                className="rounded-lg bg-blue-600 px-3 py-1.5 text-sm font-medium text-white disabled:opacity-50"
+                            >
+                                {t.settings.export}
+                            </button>
+                        </div>
+                        <div className="flex gap-2">
+                            <select
+                                value={importSource}
+                                onChange={(e) => setImportSource(e.target.value as ImportSource | '')}
+                                className={`flex-1 rounded-lg px-2 py-1 text-sm outline-none ${inputBg} ${textColor}`}
+                            >
+                                <option value="">{t.settings.importOwn}</option>
+                                {(Object.keys(t.settings.importSources) as ImportSource[]).map(source => (
+                                    <option key={source} value={source}>{t.settings.importSources[source]}</option>
+                                ))}
+                            </select>
+                            <select
+                                value={importMode}
+                                onChange={(e) => setImportMode(e.target.value as ImportMode)}
+                                className={`flex-1 rounded-lg px-2 py-1 text-sm outline-none ${inputBg} ${textColor}`}
+                            >
+                                {(Object.keys(t.settings.importModes) as ImportMode[]).map(mode => (
+                                    <option key={mode} value={mode}>{t.settings.importModes[mode]}</option>
+                                ))}
+                            </select>
+                            <button
+                                onClick={importHistory}
+                                disabled={!backupPath.trim()}
+                                className="rounded-lg bg-blue-600 px-3 py-1.5 text-sm font-medium text-white disabled:opacity-50"
+                            >
+                                {t.settings.import}
+                            </button>
+                        </div>
+                        {backupStatus && (
+                            <p className={`text-xs ${isLight ? 'text-gray-500' : 'text-white/50'}`}>{backupStatus}</p>
+                        )}
+                    </div>
+
+                    {/* Sync */}
+                    <div className="space-y-3">
+                        <label className={`flex items-center gap-2 text-sm font-medium ${secondaryText}`}>
+                            <RefreshCw size={16} /> {t.settings.sync}
+                        </label>
+                        <p className={`text-xs ${isLight ? 'text-gray-500' : 'text-white/50'}`}>
+                            {t.settings.syncDesc}
+                        </p>
+                        <input
+                            type="text"
+                            value={syncDirDraft}
+                            placeholder={t.settings.syncDir}
+                            onChange={(e) => setSyncDirDraft(e.target.value)}
+                            onBlur={() => {
+                                const dir = syncDirDraft.trim();
+                                if (dir !== (settings.syncDir ?? '')) onUpdate('syncDir', dir || null);
+                            }}
+                            className={`w-full rounded-lg px-3 py-1.5 font-mono text-sm outline-none focus:ring-1 focus:ring-blue-500 ${inputBg} ${textColor}`}
+                        />
+                        {settings.syncDir && (
+                            <>
+                                <div className="flex gap-2">
+                                    <select
+                                        value={settings.syncScope}
+                                        onChange={(e) => onUpdate('syncScope', e.target.value as SyncScope)}
+                                        className={`flex-1 rounded-lg px-2 py-1 text-sm outline-none ${inputBg} ${textColor}`}
+                                    >
+                                        {(Object.keys(t.settings.syncScopes) as SyncScope[]).map(scope => (
+                                            <option key={scope} value={scope}>{t.settings.syncScopes[scope]}</option>
+                                        ))}
+                                    </select>
+                                    <button
+                                        onClick={syncNow}
+                                        className="rounded-lg bg-blue-600 px-3 py-1.5 text-sm font-medium text-white"
+                                    >
+                                        {t.settings.syncNow}
+                                    </button>
+                                </div>
+                                <label className={`block text-xs ${isLight ? 'text-gray-500' : 'text-white/50'}`}>
+                                    {t.settings.syncInterval}
+                                </label>
+                                <input
+                                    type="number"
+                                    min={5}
+                                    max={3600}
+                                    value={settings.syncIntervalSecs}
+                                    onChange={(e) => onUpdate('syncIntervalSecs', Number(e.target.value))}
+                                    className={`w-24 rounded-lg px-3 py-1.5 text-sm outline-none focus:ring-1 focus:ring-blue-500 ${inputBg} ${textColor}`}
+                                />
+                            </>
+                        )}
+                        {syncStatus && (
+                            <p className={`text-xs ${isLight ? 'text-gray-500' : 'text-white/50'}`}>{syncStatus}</p>
+                        )}
+                    </div>
+
+                    {/* Auto-clear */}
+                    <div className="space-y-3">
+                        <label className={`flex items-center gap-2 text-sm font-medium ${secondaryText}`}>
+                            <Eraser size={16} /> {t.settings.autoClear}
+                        </label>
+                        <p className={`text-xs ${isLight ? 'text-gray-500' : 'text-white/50'}`}>
+                            {t.settings.autoClearDesc}
+                        </p>
+                        <select
+                            value={settings.autoClear}
+                            onChange={(e) => onUpdate('autoClear', e.target.value as AutoClear)}
+                            className={`w-full rounded-lg px-2 py-1.5 text-sm outline-none ${inputBg} ${textColor}`}
+                        >
+                            {(Object.keys(t.settings.autoClearModes) as AutoClear[]).map(mode => (
+                                <option key={mode} value={mode}>{t.settings.autoClearModes[mode]}</option>
+                            ))}
+                        </select>
+                        {settings.autoClear !== 'off' && (
+                            <>
+                                <label className={`block text-xs ${isLight ? 'text-gray-500' : 'text-white/50'}`}>
+                                    {t.settings.autoClearSecs}
+                                </label>
+                                <input
+                                    type="number"
+                                    min={5}
+                                    max={3600}
+                                    value={settings.autoClearSecs}
+                                    onChange={(e) => onUpdate('autoClearSecs', Number(e.target.value))}
+                                    className={`w-24 rounded-lg px-3 py-1.5 text-sm outline-none focus:ring-1 focus:ring-blue-500 ${inputBg} ${textColor}`}
+                                />
+                            </>
+                        )}
                     </div>
 
                     {/* Shortcuts */}
@@ -249,6 +738,12 @@ export const Settings = ({ isOpen, onClose, settings, onUpdate, t }: SettingsPro
                     </div>
                 </div>
 
+                {error && (
+                    <div className="border-t border-red-500/30 bg-red-500/10 px-4 py-2 text-xs text-red-500">
+                        {error}
+                    </div>
+                )}
+
                 <div className={`border-t p-4 text-center text-xs ${borderColor} ${isLight ? 'text-gray-400' : 'text-white/30'}`}>
                     {t.settings.closeHint}
                 </div>
@@ -0,0 +1,189 @@
+import type { Snippet, TemplateError } from '../src/types';
+import { Pencil, Plus, Trash2 } from 'lucide-react';
+import { useState, useEffect } from 'react';
+import { api } from '../src/lib/api';
+import { translations } from '../locales';
+
+interface SnippetPickerProps {
+    theme: 'light' | 'dark';
+    searchQuery: string;
+    t: typeof translations['en'];
+}
+
+/** Values for a snippet's `{input:...}` placeholders, asked for before pasting. */
+interface Prompt {
+    snippet: Snippet;
+    labels: string[];
+    values: Record<string, string>;
+}
+
+export const SnippetPicker = ({ theme, searchQuery, t }: SnippetPickerProps) => {
+    const [snippets, setSnippets] = useState<Snippet[]>([]);
+    const [prompt, setPrompt] = useState<Prompt | null>(null);
+    // `id` is null while creating a new snippet.
+    const [draft, setDraft] = useState<{ id: string | null; name: string; body: string } | null>(null);
+    const [error, setError] = useState<string | null>(null);
+
+    useEffect(() => {
+        api.getSnippets().then(setSnippets);
+    }, []);
+
+    const isLight = theme === 'light';
+    const textColor = isLight ? 'text-gray-900' : 'text-white';
+    const mutedText = isLight ? 'text-gray-500' : 'text-white/50';
+    const inputBg = isLight ? 'bg-gray-100' : 'bg-black/20';
+    const rowHover = isLight ? 'hover:bg-gray-100' : 'hover:bg-white/5';
+    const iconButton = `rounded p-1 ${isLight ? 'text-gray-400 hover:bg-gray-200 hover:text-gray-600' : 'text-gray-500 hover:bg-white/10 hover:text-gray-300'}`;
+    const inputClass = `w-full rounded-lg px-3 py-1.5 text-sm outline-none focus:ring-1 focus:ring-blue-500 ${inputBg} ${textColor}`;
+
+    const query = searchQuery.toLowerCase();
+    const visible = snippets.filter(s =>
+        !query || s.name.toLowerCase().includes(query) || s.body.toLowerCase().includes(query)
+    );
+
+    const paste = async (snippet: Snippet, values: Record<string, string>) => {
+        try {
+            await api.pasteSnippet(snippet.id, values);
+            setPrompt(null);
+            setError(null);
+        } catch (e) {
+            const err = e as TemplateError;
+            // The template gained an input since the prompt was built.
+            if (err.kind === 'missingInput' && err.label) {
+                setPrompt({ snippet, labels: await api.snippetInputs(snippet.id), values });
+            }
+            setError(err.message ?? String(e));
+        }
+    };
+
+    const choose = async (snippet: Snippet) => {
+        setError(null);
+        try {
+            const labels = await api.snippetInputs(snippet.id);
+            if (labels.length === 0) {
+                await paste(snippet, {});
+            } else {
+                setPrompt({ snippet, labels, values: {} });
+            }
+        } catch (e) {
+            setError(String(e));
+        }
+    };
+
+    const saveDraft = async () => {
+        if (!draft) return;
+        try {
+            setSnippets(draft.id
+                ? await api.updateSnippet(draft.id, draft.name, draft.body)
+                : await api.createSnippet(draft.name, draft.body));
+            setDraft(null);
+            setError(null);
+        } catch (e) {
+            setError(String(e));
+        }
+    };
+
+    return (
+        <div className="flex-1 space-y-2 overflow-y-auto p-3 scrollbar-hide">
+            {prompt && (
+                <form
+                    onSubmit={(e) => { e.preventDefault(); paste(prompt.snippet, prompt.values); }}
+                    className={`space-y-2 rounded-lg border p-3 ${isLight ? 'border-gray-200' : 'border-white/10'}`}
+                >
+                    <p className={`text-sm font-semibold ${textColor}`}>{prompt.snippet.name}</p>
+                    {prompt.labels.map((label, index) => (
+                        <div key={label} className="space-y-1">
+                            <label className={`text-xs ${mutedText}`}>{label}</label>
+                            <input
+                                autoFocus={index === 0}
+                                value={prompt.values[label] ?? ''}
+                                onChange={(e) => setPrompt({ ...prompt, values: { ...prompt.values, [label]: e.target.value } })}
+                                className={inputClass}
+                            />
+                        </div>
+                    ))}
+                    <div className="flex justify-end gap-2">
+                        <button type="button" onClick={() => setPrompt(null)} className={`rounded-lg px-3 py-1.5 text-sm ${mutedText}`}>
+                            {t.snippets.cancel}
+                        </button>
+                        <button type="submit" className="rounded-lg bg-blue-600 px-3 py-1.5 text-sm font-medium text-white hover:bg-blue-500">
+                            {t.snippets.paste}
+                        </button>
+                    </div>
+                </form>
+            )}
+
+            {error && (
+                <div className="rounded-lg bg-red-500/10 px-3 py-2 text-xs text-red-500">{error}</div>
+            )}
+
+            {visible.map(snippet => (
+                <div
+                    key={snippet.id}
+                    onClick={() => choose(snippet)}
+                    className={`group flex cursor-pointer items-center gap-2 rounded-lg px-3 py-2 ${rowHover}`}
+                >
+                    <div className="flex-1 overflow-hidden">
+                        <p className={`truncate text-sm font-medium ${textColor}`}>{snippet.name}</p>
+                        <p className={`truncate font-mono text-xs ${mutedText}`}>{snippet.body}</p>
+                    </div>
+                    <div className="flex gap-1 opacity-0 transition-opacity group-hover:opacity-100">
+                        <button
+                            onClick={(e) => { e.stopPropagation(); setDraft({ id: snippet.id, name: snippet.name, body: snippet.body }); }}
+                            className={iconButton}
+                            title={t.snippets.edit}
+                        >
+                            <Pencil size={14} />
+                        </button>
+                        <button
+                            onClick={async (e) => { e.stopPropagation(); setSnippets(await api.deleteSnippet(snippet.id)); }}
+                            className={iconButton}
+                            title={t.snippets.delete}
+                        >
+                            <Trash2 size={14} />
+                        </button>
+                    </div>
+                </div>
+            ))}
+
+            {snippets.length === 0 && !draft && (
+                <p className={`px-3 py-6 text-center text-sm ${mutedText}`}>{t.snippets.empty}</p>
+            )}
+
+            {draft ? (
+                <div className={`space-y-2 rounded-lg border p-3 ${isLight ? 'border-gray-200' : 'border-white/10'}`}>
+                    <input
+                        value={draft.name}
+                        placeholder={t.snippets.name}
+                        onChange={(e) => setDraft({ ...draft, name: e.target.value })}
+                        className={inputClass}
+                    />
+                    <textarea
+                        value={draft.body}
+                        rows={4}
+                        placeholder={t.snippets.body}
+                        onChange={(e) => setDraft({ ...draft, body: e.target.value })}
+                        className={`${inputClass} resize-y font-mono`}
+                    />
+                    <p className={`text-xs ${mutedText}`}>{t.snippets.placeholders}</p>
+                    <div className="flex justify-end gap-2">
+                        <button onClick={() => setDraft(null)} className={`rounded-lg px-3 py-1.5 text-sm ${mutedText}`}>
+                            {t.snippets.cancel}
+                        </button>
+                        <button onClick={saveDraft} className="rounded-lg bg-blue-600 px-3 py-1.5 text-sm font-medium text-white hover:bg-blue-500">
+                            {t.snippets.save}
+                        </button>
+                    </div>
+                </div>
+            ) : (
+                <button
+                    onClick={() => setDraft({ id: null, name: '', body: '' })}
+                    className={`flex w-full items-center justify-center gap-1 rounded-lg py-2 text-sm ${mutedText} ${rowHover}`}
+                >
+                    <Plus size={14} />
+                    {t.snippets.new}
+                </button>
+            )}
+        </div>
+    );
+};
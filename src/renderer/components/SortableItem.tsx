@@ -8,7 +8,7 @@ export function SortableItem(props: any) {
         setNodeRef,
         transform,
         transition,
-    } = useSortable({ id: props.id });
+    } = useSortable({ id: props.id, disabled: props.disabled });
 
     const style = {
         transform: CSS.Transform.toString(transform),
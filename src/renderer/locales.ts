@@ -22,6 +22,87 @@ export const translations = {
 
             useInternalShortcut: 'Use internal "Ctrl+Alt+V" shortcut',
             internalShortcutDesc: 'If enabled, the app runs in background and listens for Ctrl+Alt+V directly. Disable system shortcut if using this.',
+            toggleShortcut: 'Shortcut to open the window',
+            toggleShortcutHint: 'e.g. Control+Alt+V or Super+Shift+C. Press Enter to apply.',
+            pasteRules: 'Per-app paste rules',
+            pasteRulesDesc: 'Applies when the focused window class contains the text below. Shift+click an item to paste it as plain text once.',
+            pasteRuleApp: 'Window class, e.g. libreoffice',
+            pasteModes: {
+                default: 'Default',
+                plainText: 'Plain text',
+                typeOut: 'Type it out'
+            },
+            typeDelay: 'Delay between typed keys (ms)',
+            transientPaste: 'Keep my clipboard after pasting',
+            transientPasteDesc: 'Pasting a history item restores what was on the clipboard before.',
+            ocr: 'Text in Images (OCR)',
+            ocrDesc: 'Recognize text in copied images with Tesseract so screenshots show up in search.',
+            ocrLanguages: 'Languages, e.g. eng+por',
+            ocrMissing: 'Tesseract was not found. Install it (e.g. the tesseract-ocr package) to use OCR.',
+            takeOwnership: 'Keep copies after the app closes',
+            takeOwnershipDesc: 'Offer every captured copy from this app, in all its formats, so it is still there when the program you copied from exits (X11).',
+            restoreDelay: 'Restore after (ms)',
+            backup: 'Export & Import',
+            backupDesc: 'JSON and ZIP exports can be imported again; Markdown and HTML are read-only reports.',
+            backupPath: 'Full file path, e.g. /home/you/clipboard.zip',
+            exportFormats: {
+                json: 'JSON',
+                zip: 'ZIP (with images)',
+                markdown: 'Markdown',
+                html: 'HTML'
+            },
+            importModes: {
+                merge: 'Merge',
+                replace: 'Replace'
+            },
+            export: 'Export',
+            import: 'Import',
+            importOwn: 'From this app (JSON/ZIP)',
+            importSources: {
+                copyq: 'CopyQ (copyq_tab_*.dat)',
+                gpaste: 'GPaste (history.xml)',
+                klipper: 'Klipper (history2.lst)',
+                cliphist: 'cliphist (db)',
+                clipman: 'clipman (clipman.json)'
+            },
+            exported: 'Exported {count} items.',
+            imported: 'Added {added}, skipped {skipped} duplicates and {invalid} unreadable items.',
+            dedup: 'Duplicates',
+            dedupDesc: 'What happens when you copy something that is already in the history.',
+            dedupStrategies: {
+                newestOnly: 'Skip only repeats of the latest item',
+                moveToTop: 'Move the existing item to the top',
+                never: 'Keep the existing item in place'
+            },
+            similarImages: 'Treat nearly identical images as duplicates',
+            usage: 'Usage',
+            usageDesc: 'Order the history by what you use most and most recently. Pinned items keep their place.',
+            historyOrders: {
+                recent: 'Newest first',
+                frecency: 'Most used first'
+            },
+            stats: '{items} items, {pastes} pastes, {recopies} repeated copies, {size} on disk',
+            statsByType: '{text} text, {image} images',
+            mostPasted: 'Most pasted',
+            sync: 'Sync Between Devices',
+            syncDesc: 'Pick a folder shared by Syncthing, NFS or similar. Each device writes its own change log there and reads the others.',
+            syncDir: 'Full folder path, leave empty to turn sync off',
+            syncScopes: {
+                pinned: 'Pinned items and collections',
+                all: 'Full history'
+            },
+            syncInterval: 'Sync every (seconds)',
+            autoClear: 'Auto-clear Clipboard',
+            autoClearDesc: 'Empty the system clipboard a while after copying, like password managers do. The history keeps the item.',
+            autoClearModes: {
+                off: 'Off',
+                all: 'Every copy',
+                sensitive: 'Sensitive items only'
+            },
+            autoClearSecs: 'Clear after (seconds)',
+            syncNow: 'Sync now',
+            synced: 'Sent {pushed}, received {pulled}, removed {removed}.',
+            addRule: 'Add',
             shortcuts: 'Keyboard Shortcuts',
             shortcutsDesc: 'To open the app with a key (e.g. Ctrl+Alt+V), create a shortcut in your Linux system:',
             shortcutsSteps: [
@@ -38,17 +119,105 @@ export const translations = {
             language: 'Language',
             closeHint: 'Close to save and apply.'
         },
+        merge: {
+            selected: '{count} selected',
+            newline: 'New line',
+            comma: 'Comma',
+            tab: 'Tab',
+            custom: 'Custom',
+            paste: 'Paste joined',
+            save: 'Save as new',
+            cancel: 'Clear selection'
+        },
+        editor: {
+            title: 'Edit Item',
+            itemTitle: 'Title',
+            note: 'Note',
+            content: 'Text',
+            revisions: 'Earlier versions',
+            original: 'Original copy',
+            restore: 'Restore',
+            sensitive: 'Sensitive (password, token)',
+            expiry: 'Delete automatically',
+            expiresAt: 'Deletes itself at {time}',
+            expiryOptions: {
+                never: 'Never',
+                '1m': 'In 1 minute',
+                '5m': 'In 5 minutes',
+                '1h': 'In 1 hour',
+                '1d': 'In 1 day'
+            },
+            save: 'Save',
+            cancel: 'Cancel'
+        },
+        imageEditor: {
+            title: 'Edit Image',
+            size: '{width} × {height} px',
+            annotate: 'Annotate',
+            annotateDesc: 'Drag on the image to draw.',
+            tools: {
+                rectangle: 'Rectangle',
+                arrow: 'Arrow'
+            },
+            color: 'Color',
+            thickness: 'Line width',
+            undoShape: 'Remove last drawing',
+            crop: 'Crop',
+            cropFields: {
+                x: 'Left',
+                y: 'Top',
+                width: 'Width',
+                height: 'Height'
+            },
+            rotate: 'Rotate clockwise',
+            resize: 'Resize',
+            resizeModes: {
+                none: 'Keep size',
+                percent: 'By percentage',
+                maxDimension: 'To a maximum side (px)'
+            },
+            format: 'Format',
+            formats: {
+                png: 'PNG',
+                jpeg: 'JPEG',
+                webp: 'WebP (lossless)'
+            },
+            quality: 'Quality (1-100)',
+            saveCopy: 'Save as new item'
+        },
         actions: {
             pin: 'Pin',
             unpin: 'Unpin',
             delete: 'Delete',
+            queue: 'Add to paste queue',
+            unqueue: 'Remove from paste queue',
+            collectQueue: 'Collect new copies into the paste queue',
+            copyCount: 'Copied {count} times',
+            edit: 'Edit title, note and text',
+            copyText: 'Copy recognized text',
+            copyDecoded: 'Copy {format} contents',
+            editImage: 'Crop, resize or annotate',
+            pastePlainHint: 'Shift+click to paste as plain text, Alt+click to type it out (Esc stops), Ctrl+click to select for merging',
             clearAll: 'Clear All',
             settings: 'Settings'
         },
         typeNav: {
             clipboard: 'Clipboard',
             emojis: 'Emojis',
-            symbols: 'Symbols'
+            symbols: 'Symbols',
+            snippets: 'Snippets'
+        },
+        snippets: {
+            empty: 'No snippets yet.',
+            new: 'New snippet',
+            name: 'Name',
+            body: 'Text',
+            placeholders: 'Placeholders: {date}, {date:%d/%m/%Y}, {clipboard}, {uuid}, {input:Label}, {cursor}. Use {{ and }} for literal braces.',
+            edit: 'Edit',
+            delete: 'Delete',
+            paste: 'Paste',
+            save: 'Save',
+            cancel: 'Cancel'
         },
         emojiCategories: {
             recent: 'Recent',
@@ -87,6 +256,87 @@ export const translations = {
 
             useInternalShortcut: 'Usar atalho interno "Ctrl+Alt+V"',
             internalShortcutDesc: "Se ativado, o aplicativo escuta 'Ctrl + Alt + V' em segundo plano. Não defina um atalho do sistema.",
+            toggleShortcut: 'Atalho para abrir a janela',
+            toggleShortcutHint: 'ex.: Control+Alt+V ou Super+Shift+C. Pressione Enter para aplicar.',
+            pasteRules: 'Regras de colagem por aplicativo',
+            pasteRulesDesc: 'Aplica-se quando a classe da janela em foco contém o texto abaixo. Shift+clique em um item para colá-lo uma vez como texto simples.',
+            pasteRuleApp: 'Classe da janela, ex.: libreoffice',
+            pasteModes: {
+                default: 'Padrão',
+                plainText: 'Texto simples',
+                typeOut: 'Digitar'
+            },
+            typeDelay: 'Intervalo entre teclas digitadas (ms)',
+            transientPaste: 'Manter minha área de transferência após colar',
+            transientPasteDesc: 'Colar um item do histórico restaura o que estava na área de transferência antes.',
+            ocr: 'Texto em Imagens (OCR)',
+            ocrDesc: 'Reconhecer texto em imagens copiadas com o Tesseract para que capturas de tela apareçam na busca.',
+            ocrLanguages: 'Idiomas, ex.: eng+por',
+            ocrMissing: 'O Tesseract não foi encontrado. Instale-o (ex.: pacote tesseract-ocr) para usar o OCR.',
+            takeOwnership: 'Manter cópias após fechar o aplicativo',
+            takeOwnershipDesc: 'Oferecer cada cópia capturada a partir deste aplicativo, em todos os formatos, para que continue disponível quando o programa de origem fechar (X11).',
+            restoreDelay: 'Restaurar após (ms)',
+            backup: 'Exportar e Importar',
+            backupDesc: 'Exportações JSON e ZIP podem ser importadas de novo; Markdown e HTML são relatórios somente leitura.',
+            backupPath: 'Caminho completo do arquivo, ex.: /home/voce/clipboard.zip',
+            exportFormats: {
+                json: 'JSON',
+                zip: 'ZIP (com imagens)',
+                markdown: 'Markdown',
+                html: 'HTML'
+            },
+            importModes: {
+                merge: 'Mesclar',
+                replace: 'Substituir'
+            },
+            export: 'Exportar',
+            import: 'Importar',
+            importOwn: 'Deste app (JSON/ZIP)',
+            importSources: {
+                copyq: 'CopyQ (copyq_tab_*.dat)',
+                gpaste: 'GPaste (history.xml)',
+                klipper: 'Klipper (history2.lst)',
+                cliphist: 'cliphist (db)',
+                clipman: 'clipman (clipman.json)'
+            },
+            exported: '{count} itens exportados.',
+            imported: '{added} adicionados, {skipped} duplicados e {invalid} ilegíveis ignorados.',
+            dedup: 'Duplicados',
+            dedupDesc: 'O que acontece ao copiar algo que já está no histórico.',
+            dedupStrategies: {
+                newestOnly: 'Ignorar só repetições do último item',
+                moveToTop: 'Mover o item existente para o topo',
+                never: 'Manter o item existente no lugar'
+            },
+            similarImages: 'Tratar imagens quase idênticas como duplicadas',
+            usage: 'Uso',
+            usageDesc: 'Ordenar o histórico pelo que você mais usa e usou por último. Itens fixados mantêm o lugar.',
+            historyOrders: {
+                recent: 'Mais recentes primeiro',
+                frecency: 'Mais usados primeiro'
+            },
+            stats: '{items} itens, {pastes} colagens, {recopies} cópias repetidas, {size} em disco',
+            statsByType: '{text} textos, {image} imagens',
+            mostPasted: 'Mais colados',
+            sync: 'Sincronizar Entre Dispositivos',
+            syncDesc: 'Escolha uma pasta compartilhada pelo Syncthing, NFS ou similar. Cada dispositivo grava seu próprio registro de alterações nela e lê os dos outros.',
+            syncDir: 'Caminho completo da pasta, deixe vazio para desligar',
+            syncScopes: {
+                pinned: 'Itens fixados e coleções',
+                all: 'Histórico completo'
+            },
+            syncInterval: 'Sincronizar a cada (segundos)',
+            autoClear: 'Limpar Área de Transferência Automaticamente',
+            autoClearDesc: 'Esvaziar a área de transferência do sistema um tempo depois de copiar, como fazem os gerenciadores de senhas. O histórico mantém o item.',
+            autoClearModes: {
+                off: 'Desligado',
+                all: 'Todas as cópias',
+                sensitive: 'Só itens sensíveis'
+            },
+            autoClearSecs: 'Limpar após (segundos)',
+            syncNow: 'Sincronizar agora',
+            synced: '{pushed} enviados, {pulled} recebidos, {removed} removidos.',
+            addRule: 'Adicionar',
             shortcuts: 'Atalho de Teclado',
             shortcutsDesc: 'Para abrir o app com uma tecla (ex: Ctrl+Alt+V), crie um atalho no seu sistema Linux:',
             shortcutsSteps: [
@@ -103,17 +353,105 @@ export const translations = {
             language: 'Idioma',
             closeHint: 'Feche para salvar e aplicar.'
         },
+        merge: {
+            selected: '{count} selecionados',
+            newline: 'Nova linha',
+            comma: 'Vírgula',
+            tab: 'Tab',
+            custom: 'Personalizado',
+            paste: 'Colar unidos',
+            save: 'Salvar como novo',
+            cancel: 'Limpar seleção'
+        },
+        editor: {
+            title: 'Editar Item',
+            itemTitle: 'Título',
+            note: 'Nota',
+            content: 'Texto',
+            revisions: 'Versões anteriores',
+            original: 'Cópia original',
+            restore: 'Restaurar',
+            sensitive: 'Sensível (senha, token)',
+            expiry: 'Excluir automaticamente',
+            expiresAt: 'Exclui-se às {time}',
+            expiryOptions: {
+                never: 'Nunca',
+                '1m': 'Em 1 minuto',
+                '5m': 'Em 5 minutos',
+                '1h': 'Em 1 hora',
+                '1d': 'Em 1 dia'
+            },
+            save: 'Salvar',
+            cancel: 'Cancelar'
+        },
+        imageEditor: {
+            title: 'Editar Imagem',
+            size: '{width} × {height} px',
+            annotate: 'Anotar',
+            annotateDesc: 'Arraste sobre a imagem para desenhar.',
+            tools: {
+                rectangle: 'Retângulo',
+                arrow: 'Seta'
+            },
+            color: 'Cor',
+            thickness: 'Espessura da linha',
+            undoShape: 'Remover último desenho',
+            crop: 'Recortar',
+            cropFields: {
+                x: 'Esquerda',
+                y: 'Topo',
+                width: 'Largura',
+                height: 'Altura'
+            },
+            rotate: 'Girar no sentido horário',
+            resize: 'Redimensionar',
+            resizeModes: {
+                none: 'Manter tamanho',
+                percent: 'Por porcentagem',
+                maxDimension: 'Até um lado máximo (px)'
+            },
+            format: 'Formato',
+            formats: {
+                png: 'PNG',
+                jpeg: 'JPEG',
+                webp: 'WebP (sem perdas)'
+            },
+            quality: 'Qualidade (1-100)',
+            saveCopy: 'Salvar como novo item'
+        },
         actions: {
             pin: 'Fixar',
             unpin: 'Desafixar',
             delete: 'Excluir',
+            queue: 'Adicionar à fila de colagem',
+            unqueue: 'Remover da fila de colagem',
+            collectQueue: 'Coletar novas cópias na fila de colagem',
+            copyCount: 'Copiado {count} vezes',
+            edit: 'Editar título, nota e texto',
+            copyText: 'Copiar texto reconhecido',
+            copyDecoded: 'Copiar conteúdo do {format}',
+            editImage: 'Recortar, redimensionar ou anotar',
+            pastePlainHint: 'Shift+clique para colar como texto simples, Alt+clique para digitar (Esc interrompe), Ctrl+clique para selecionar e unir',
             clearAll: 'Limpar Tudo',
             settings: 'Configurações'
         },
         typeNav: {
             clipboard: 'Clipboard',
             emojis: 'Emojis',
-            symbols: 'Símbolos'
+            symbols: 'Símbolos',
+            snippets: 'Modelos'
+        },
+        snippets: {
+            empty: 'Nenhum modelo ainda.',
+            new: 'Novo modelo',
+            name: 'Nome',
+            body: 'Texto',
+            placeholders: 'Marcadores: {date}, {date:%d/%m/%Y}, {clipboard}, {uuid}, {input:Rótulo}, {cursor}. Use {{ e }} para chaves literais.',
+            edit: 'Editar',
+            delete: 'Excluir',
+            paste: 'Colar',
+            save: 'Salvar',
+            cancel: 'Cancelar'
         },
         emojiCategories: {
             recent: 'Recentes',
@@ -1,13 +1,58 @@
 import { invoke } from '@tauri-apps/api/core';
 import { listen, type UnlistenFn } from '@tauri-apps/api/event';
-import type { ClipboardItem, Settings } from '../types';
+import type { ClipboardItem, Collection, ExportFormat, HistoryFilter, ImageEdit, ImageOutput, ImportMode, ItemUpdate, ImportSource, ImportSummary, PasteQueue, Separator, Settings, Snippet, Stats, SyncReport, Tag, Transform, TrashedItem } from '../types';
 
 export const api = {
-    getHistory: () => invoke<ClipboardItem[]>('get_history'),
+    getHistory: (filter?: HistoryFilter) => invoke<ClipboardItem[]>('get_history', { filter }),
     deleteItem: (id: string) => invoke<ClipboardItem[]>('delete_item', { id }),
     togglePin: (id: string) => invoke<ClipboardItem[]>('toggle_pin', { id }),
+    updateItem: (id: string, update: ItemUpdate) => invoke<ClipboardItem[]>('update_item', { id, update }),
+    restoreRevision: (id: string, index: number) => invoke<ClipboardItem[]>('restore_revision', { id, index }),
+    setItemExpiry: (id: string, at: number | null) => invoke<ClipboardItem[]>('set_item_expiry', { id, at }),
+    ocrAvailable: () => invoke<boolean>('ocr_available'),
+    copyOcrText: (id: string) => invoke<void>('copy_ocr_text', { id }),
+    copyDecoded: (id: string, index: number) => invoke<void>('copy_decoded', { id, index }),
+    editImage: (id: string, edits: ImageEdit[], output?: ImageOutput) => invoke<ClipboardItem[]>('edit_image', { id, edits, output }),
     clearAll: () => invoke<ClipboardItem[]>('clear_all'),
+    undoLastAction: () => invoke<ClipboardItem[]>('undo_last_action'),
+    getTrash: () => invoke<TrashedItem[]>('get_trash'),
+    restoreItem: (id: string) => invoke<ClipboardItem[]>('restore_item', { id }),
+    emptyTrash: () => invoke<TrashedItem[]>('empty_trash'),
+    exportHistory: (path: string, format: ExportFormat, filter?: HistoryFilter) =>
+        invoke<number>('export_history', { path, format, filter }),
+    importHistory: (path: string, mode: ImportMode, source?: ImportSource) =>
+        invoke<ImportSummary>('import_history', { path, mode, source }),
+    syncNow: () => invoke<SyncReport>('sync_now'),
+    getStats: () => invoke<Stats>('get_stats'),
+    getTags: () => invoke<Tag[]>('get_tags'),
+    createTag: (name: string) => invoke<Tag[]>('create_tag', { name }),
+    renameTag: (id: string, name: string) => invoke<Tag[]>('rename_tag', { id, name }),
+    deleteTag: (id: string) => invoke<Tag[]>('delete_tag', { id }),
+    setItemTags: (id: string, tagIds: string[]) => invoke<ClipboardItem[]>('set_item_tags', { id, tagIds }),
+    getCollections: () => invoke<Collection[]>('get_collections'),
+    createCollection: (name: string) => invoke<Collection[]>('create_collection', { name }),
+    renameCollection: (id: string, name: string) => invoke<Collection[]>('rename_collection', { id, name }),
+    deleteCollection: (id: string) => invoke<Collection[]>('delete_collection', { id }),
+    setItemCollection: (id: string, collectionId: string | null) => invoke<ClipboardItem[]>('set_item_collection', { id, collectionId }),
     pasteItem: (id: string) => invoke<void>('paste_item', { id }),
+    pastePlain: (id: string) => invoke<void>('paste_plain', { id }),
+    mergeItems: (ids: string[], separator: Separator, save = false) =>
+        invoke<ClipboardItem[]>('merge_items', { ids, separator, save }),
+    typeItem: (id: string) => invoke<void>('type_item', { id }),
+    getPasteQueue: () => invoke<PasteQueue>('get_paste_queue'),
+    setPasteQueue: (ids: string[]) => invoke<PasteQueue>('set_paste_queue', { ids }),
+    setQueueCollecting: (collecting: boolean) => invoke<PasteQueue>('set_queue_collecting', { collecting }),
+    clearPasteQueue: () => invoke<PasteQueue>('clear_paste_queue'),
+    pasteNextQueued: () => invoke<void>('paste_next_queued'),
+    getTransforms: () => invoke<Transform[]>('get_transforms'),
+    pasteTransformed: (id: string, transforms: Transform[], saveAsNew = false) =>
+        invoke<ClipboardItem[]>('paste_transformed', { id, transforms, saveAsNew }),
+    getSnippets: () => invoke<Snippet[]>('get_snippets'),
+    createSnippet: (name: string, body: string) => invoke<Snippet[]>('create_snippet', { name, body }),
+    updateSnippet: (id: string, name: string, body: string) => invoke<Snippet[]>('update_snippet', { id, name, body }),
+    deleteSnippet: (id: string) => invoke<Snippet[]>('delete_snippet', { id }),
+    snippetInputs: (id: string) => invoke<string[]>('snippet_inputs', { id }),
+    pasteSnippet: (id: string, inputs: Record<string, string>) => invoke<void>('paste_snippet', { id, inputs }),
     getSettings: () => invoke<Settings>('get_settings'),
     updateSetting: (key: string, value: any) => invoke<Settings>('update_setting', { key, value }),
     reorderItems: (activeId: string, overId: string) => invoke<ClipboardItem[]>('reorder_items', { active_id: activeId, over_id: overId }),
@@ -33,6 +78,42 @@ export const api = {
         };
     },
 
+    onCapturePaused: (callback: (paused: boolean) => void) => {
+        let unlisten: UnlistenFn | undefined;
+        const promise = listen<boolean>('capture-paused', (event) => {
+            callback(event.payload);
+        });
+        promise.then(u => unlisten = u);
+
+        return () => {
+            if (unlisten) unlisten();
+        };
+    },
+
+    onPasteQueueChanged: (callback: (queue: PasteQueue) => void) => {
+        let unlisten: UnlistenFn | undefined;
+        const promise = listen<PasteQueue>('paste-queue-changed', (event) => {
+            callback(event.payload);
+        });
+        promise.then(u => unlisten = u);
+
+        return () => {
+            if (unlisten) unlisten();
+        };
+    },
+
+    onItemExpired: (callback: (id: string) => void) => {
+        let unlisten: UnlistenFn | undefined;
+        const promise = listen<string>('item-expired', (event) => {
+            callback(event.payload);
+        });
+        promise.then(u => unlisten = u);
+
+        return () => {
+            if (unlisten) unlisten();
+        };
+    },
+
     onForceFocus: (callback: () => void) => {
         let unlisten: UnlistenFn | undefined;
         const promise = listen<void>('force-focus', () => {
@@ -4,13 +4,254 @@ export interface ClipboardItem {
     content: string;
     timestamp: number;
     isPinned: boolean;
+    tags: string[];
+    collectionId: string | null;
+    /** Detected content kind; null for images. */
+    kind: ContentKind | null;
+    meta: ContentMeta;
+    /** Other representations keyed by MIME type, e.g. text/html. */
+    formats?: Record<string, string>;
+    /** Times this content was copied, counted by dedup. */
+    copyCount: number;
+    /** Times this item was pasted from the history. */
+    pasteCount: number;
+    lastPastedAt?: number;
+    /** Shown instead of the first line of the content. */
+    title?: string;
+    note?: string;
+    /** Contents replaced by edits, the original copy first. */
+    revisions?: Revision[];
+    /** Unix ms at which the item deletes itself. */
+    expiresAt?: number;
+    /** Passwords and the like; cleared from the clipboard by `autoClear: 'sensitive'`. */
+    isSensitive: boolean;
+    /** Text recognized in an image; empty when none was found. */
+    ocrText?: string;
+    /** QR codes and barcodes found in an image. */
+    decoded?: DecodedCode[];
+}
+
+export interface DecodedCode {
+    /** Symbology, e.g. `QR_CODE` or `EAN_13`. */
+    format: string;
+    text: string;
+}
+
+/** A step of `editImage`, applied in order. Coordinates are image pixels. */
+export type ImageEdit =
+    | { op: 'crop'; x: number; y: number; width: number; height: number }
+    | { op: 'resize'; by: { percent: number } | { maxDimension: number } }
+    /** Clockwise, in quarter turns. */
+    | { op: 'rotate'; degrees: number }
+    | { op: 'rectangle'; x: number; y: number; width: number; height: number; style?: Stroke }
+    | { op: 'arrow'; x1: number; y1: number; x2: number; y2: number; style?: Stroke };
+
+export interface Stroke {
+    /** `#rrggbb` or `#rrggbbaa`. */
+    color: string;
+    thickness: number;
+}
+
+export type ImageFormat = 'png' | 'jpeg' | 'webp';
+
+export interface ImageOutput {
+    format: ImageFormat;
+    /** JPEG quality, 1-100; WebP is always lossless. */
+    quality?: number;
+}
+
+export interface Revision {
+    content: string;
+    formats?: Record<string, string>;
+    replacedAt: number;
+}
+
+/** Omitted fields are left alone; an empty title or note clears it. */
+export interface ItemUpdate {
+    title?: string;
+    note?: string;
+    content?: string;
+    sensitive?: boolean;
+}
+
+export type ContentKind = 'text' | 'url' | 'email' | 'path' | 'color' | 'json' | 'code' | 'number';
+
+export interface ContentMeta {
+    host?: string;
+    pathExists?: boolean;
+    rgba?: [number, number, number, number];
+    jsonValid?: boolean;
+    language?: string;
+}
+
+export interface Label {
+    id: string;
+    name: string;
+}
+
+export type Tag = Label;
+export type Collection = Label;
+
+export interface Snippet {
+    id: string;
+    name: string;
+    body: string;
+    createdAt: number;
+    updatedAt: number;
+}
+
+/** Why `pasteSnippet` failed; `label` is the input to prompt for on `missingInput`. */
+export interface TemplateError {
+    kind: 'unterminated' | 'unknownPlaceholder' | 'invalidDateFormat' | 'missingInput' | 'multipleCursors' | 'notFound';
+    label: string | null;
+    message: string;
+}
+
+export interface HistoryFilter {
+    tagId?: string;
+    collectionId?: string;
+    kind?: ContentKind;
+}
+
+export type Transform =
+    | 'trim'
+    | 'uppercase'
+    | 'lowercase'
+    | 'titleCase'
+    | 'camelCase'
+    | 'snakeCase'
+    | 'kebabCase'
+    | 'stripFormatting'
+    | 'urlEncode'
+    | 'urlDecode'
+    | 'base64Encode'
+    | 'base64Decode'
+    | 'jsonPretty'
+    | 'jsonMinify'
+    | 'sortLines';
+
+/** Joins merged items: `'newline'`, `'comma'`, `'tab'` or `{ custom: '...' }`. */
+export type Separator = 'newline' | 'comma' | 'tab' | { custom: string };
+
+export interface TrashedItem {
+    item: ClipboardItem;
+    deletedAt: number;
+    position: number;
 }
 
 export interface Settings {
-    position: 'cursor' | 'fixed';
+    position: 'cursor' | 'fixed' | 'center';
     grouping: 'categorized' | 'none';
     zoom: number;
     theme: 'dark' | 'light';
     language: string | null;
     useInternalShortcut: boolean;
+    toggleShortcut: string;
+    hotkeys: Hotkeys;
+    trashRetentionDays: number;
+    pasteRules: PasteRule[];
+    typeDelayMs: number;
+    transientPaste: boolean;
+    restoreDelayMs: number;
+    /** Shared folder for multi-device sync; `null` turns sync off. */
+    syncDir: string | null;
+    syncScope: SyncScope;
+    syncIntervalSecs: number;
+    dedup: DedupStrategy;
+    /** Nearly identical images count as duplicates. */
+    similarImages: boolean;
+    historyOrder: HistoryOrder;
+    /** Which copies are wiped from the system clipboard after `autoClearSecs`; the history keeps them. */
+    autoClear: AutoClear;
+    autoClearSecs: number;
+    /** Re-offer captured copies so they survive the source app closing (X11). */
+    takeOwnership: boolean;
+    /** Recognize text in captured images (needs tesseract). */
+    ocr: boolean;
+    /** Tesseract language codes joined by '+', e.g. `eng+por`. */
+    ocrLanguages: string;
+}
+
+export type DedupStrategy = 'newestOnly' | 'moveToTop' | 'never';
+
+/** `sensitive` covers items marked sensitive and copies from password managers. */
+export type AutoClear = 'off' | 'all' | 'sensitive';
+
+/** `frecency` puts often and recently used unpinned items first; pinned items keep their place. */
+export type HistoryOrder = 'recent' | 'frecency';
+
+export interface Stats {
+    totalItems: number;
+    pinnedItems: number;
+    trashedItems: number;
+    totalPastes: number;
+    /** Copies of content that was already in the history. */
+    totalRecopies: number;
+    byType: Record<string, number>;
+    byKind: Partial<Record<ContentKind, number>>;
+    /** Most pasted first. */
+    mostPasted: ClipboardItem[];
+    /** Size of the database file. */
+    storageBytes: number;
+}
+
+/** `pinned` syncs pinned items and items in collections; collections are always synced. */
+export type SyncScope = 'pinned' | 'all';
+
+export interface SyncReport {
+    pushed: number;
+    pulled: number;
+    removed: number;
+}
+
+export type PasteMode = 'default' | 'plainText' | 'typeOut';
+
+export interface PasteRule {
+    /** Matched case-insensitively against the focused window's class. */
+    app: string;
+    mode: PasteMode;
+}
+
+export interface PinnedHotkey {
+    slot: number;
+    shortcut: string;
+}
+
+export interface Hotkeys {
+    pastePrevious: string | null;
+    pastePinned: PinnedHotkey[];
+    pauseCapture: string | null;
+    clearHistory: string | null;
+    pasteNextQueued: string | null;
+}
+
+export interface PasteQueue {
+    itemIds: string[];
+    /** Index of the next item to paste. */
+    position: number;
+    collecting: boolean;
+}
+
+export type ExportFormat = 'json' | 'zip' | 'markdown' | 'html';
+
+/** `merge` skips items already in the history; `replace` trashes the current history first. */
+export type ImportMode = 'merge' | 'replace';
+
+/** Another clipboard manager's history file; omitted for this app's own exports. */
+export type ImportSource = 'copyq' | 'gpaste' | 'klipper' | 'cliphist' | 'clipman';
+
+export interface ImportSummary {
+    added: number;
+    skippedDuplicates: number;
+    skippedInvalid: number;
+    replaced: number;
+    pruned: number;
+    tagsCreated: number;
+    collectionsCreated: number;
+}
+
+export interface SettingsError {
+    kind: 'unknownKey' | 'invalidValue' | 'outOfRange' | 'unsupportedLanguage' | 'shortcut';
+    key: string | null;
+    message: string;
 }